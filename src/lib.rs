@@ -0,0 +1,2 @@
+// The engine is a library so its logic can be tested without a window, the binary only runs it
+pub mod render;
@@ -1,6 +1,3 @@
-mod render;
-
 fn main() {
-    env_logger::init();
-    render::run();
+    rover_engine::render::run();
 }
@@ -0,0 +1,104 @@
+use cgmath::*;
+use std::time::Duration;
+use super::camera::{Camera, UpAxis};
+use super::stats::FrameStats;
+use super::terrain::block::HALF_BLOCK_SIZE;
+use super::terrain::chunk::{CHUNK_HEIGHT, CHUNK_WIDTH};
+
+// distance of the flight from the center of the chunk
+const ORBIT_RADIUS: f32 = 8.0;
+const ORBIT_POINTS: usize = 8;
+
+// Catmull-Rom spline going through all of its control points
+pub struct CameraPath {
+    points: Vec<Point3<f32>>,
+}
+
+impl CameraPath {
+    pub fn new(points: Vec<Point3<f32>>) -> Self {
+        assert!(!points.is_empty(), "a camera path needs at least one point");
+        CameraPath { points }
+    }
+
+    // t goes from 0 on the first point to 1 on the last one
+    pub fn evaluate(&self, t: f32) -> Point3<f32> {
+        let segments = self.points.len() - 1;
+        if segments == 0 {
+            return self.points[0];
+        }
+
+        let t = t.clamp(0.0, 1.0) * segments as f32;
+        let i = (t.floor() as usize).min(segments - 1);
+        // the end points are repeated so the curve starts and stops on them
+        let p0 = self.points[i.saturating_sub(1)].to_vec();
+        let p1 = self.points[i].to_vec();
+        let p2 = self.points[i + 1].to_vec();
+        let p3 = self.points[(i + 2).min(segments)].to_vec();
+        Point3::from_vec(catmull_rom(p0, p1, p2, p3, t - i as f32))
+    }
+}
+
+fn catmull_rom(p0: Vector3<f32>, p1: Vector3<f32>, p2: Vector3<f32>, p3: Vector3<f32>, t: f32) -> Vector3<f32> {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3) * 0.5
+}
+
+// Flies the camera around the chunk for a fixed time, so runs can be compared
+pub struct Benchmark {
+    path: CameraPath,
+    target: Point3<f32>,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl Benchmark {
+    pub fn new(duration: Duration) -> Self {
+        let block_size = 2.0 * HALF_BLOCK_SIZE;
+        let target = Point3::new(
+            CHUNK_WIDTH as f32 * block_size / 2.0 - HALF_BLOCK_SIZE,
+            CHUNK_HEIGHT as f32 * block_size / 4.0,
+            CHUNK_WIDTH as f32 * block_size / 2.0 - HALF_BLOCK_SIZE,
+        );
+        // one turn around the chunk, going up and down
+        let points = (0..=ORBIT_POINTS).map(|i| {
+            let angle = i as f32 / ORBIT_POINTS as f32 * std::f32::consts::PI * 2.0;
+            let height = if i % 2 == 0 { 6.0 } else { 10.0 };
+            Point3::new(target.x + ORBIT_RADIUS * angle.cos(), height, target.z + ORBIT_RADIUS * angle.sin())
+        }).collect();
+
+        Benchmark {
+            path: CameraPath::new(points),
+            target,
+            duration,
+            elapsed: Duration::from_secs(0),
+        }
+    }
+
+    pub fn update_camera(&mut self, camera: &mut Camera, dt: Duration) {
+        self.elapsed += dt;
+        let position = self.path.evaluate(self.elapsed.as_secs_f32() / self.duration.as_secs_f32());
+        // always looking at the center of the chunk, the path goes around the y axis
+        *camera = Camera::look_at(position, self.target, UpAxis::Y);
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+pub fn summary(stats: &FrameStats) -> String {
+    let average = stats.average();
+    format!(
+        "Benchmark: {} frames in {:.2}s, average {:.2}ms ({:.0} fps), min {:.2}ms, max {:.2}ms",
+        stats.frames,
+        stats.total.as_secs_f32(),
+        average.as_secs_f32() * 1000.0,
+        1.0 / average.as_secs_f32(),
+        stats.min.as_secs_f32() * 1000.0,
+        stats.max.as_secs_f32() * 1000.0,
+    )
+}
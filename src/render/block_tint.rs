@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::mem;
+use std::ops::Range;
+use cgmath::*;
+use super::terrain::block::HALF_BLOCK_SIZE;
+use super::terrain::chunk::{CHUNK_HEIGHT, CHUNK_WIDTH};
+use super::terrain::mesh::Mesh;
+
+// the blocks keep the colors of their mesh until they are tinted
+pub const DEFAULT_TINT: [f32; 3] = [1.0; 3];
+
+// Color multiplied with the vertex color in simple.vert and water.vert. It comes from a
+// second vertex buffer, one per vertex of the mesh, so a block is recolored by writing
+// over its vertices instead of meshing the chunk again.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TintVertex {
+    pub tint: [f32; 3],
+}
+
+impl super::vertex::Vertex for TintVertex {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<TintVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                // Tint, after the locations of ColorVertex
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 6,
+                    format: wgpu::VertexFormat::Float3,
+                },
+            ],
+        }
+    }
+}
+
+// index of a block of the chunk, z is contiguous like in the blocks array
+pub fn block_index([x, y, z]: [usize; 3]) -> u32 {
+    ((x * CHUNK_HEIGHT + y) * CHUNK_WIDTH + z) as u32
+}
+
+// Block of each vertex of a mesh local to its chunk. A triangle lies on a face of its block,
+// so its center moved back along the normal by half a block is inside of it. The
+// deduplication merges the corners shared by the coplanar faces of neighbouring blocks,
+// such a corner belongs to the last block using it and the tint of a block then fades
+// into the faces next to it.
+pub fn vertex_blocks(mesh: &Mesh) -> Vec<u32> {
+    let mut blocks = vec![u32::MAX; mesh.vertices.len()];
+    let max = [CHUNK_WIDTH - 1, CHUNK_HEIGHT - 1, CHUNK_WIDTH - 1];
+    for triangle in mesh.indices.chunks_exact(3) {
+        let center = triangle.iter().fold(Vector3::zero(), |sum, &i| sum + Vector3::from(mesh.vertices[i as usize].position)) / 3.0;
+        let normal = Vector3::from(mesh.vertices[triangle[0] as usize].normal);
+        let inside = (center - normal * HALF_BLOCK_SIZE) / (2.0 * HALF_BLOCK_SIZE);
+        let inside = [inside.x, inside.y, inside.z];
+        let cell = [0, 1, 2].map(|axis| (inside[axis].round().max(0.0) as usize).min(max[axis]));
+        for &i in triangle {
+            blocks[i as usize] = block_index(cell);
+        }
+    }
+    blocks
+}
+
+// The tints of the vertices of a mesh, kept on the CPU to know what to write to the GPU
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockTints {
+    vertex_blocks: Vec<u32>,
+    tints: Vec<TintVertex>,
+}
+
+impl BlockTints {
+    // the vertices start untinted, then take the tints already given to their blocks
+    pub fn new(mesh: &Mesh, block_tints: &HashMap<u32, [f32; 3]>) -> Self {
+        let vertex_blocks = vertex_blocks(mesh);
+        let tints = vertex_blocks.iter()
+            .map(|block| TintVertex { tint: *block_tints.get(block).unwrap_or(&DEFAULT_TINT) })
+            .collect();
+        BlockTints { vertex_blocks, tints }
+    }
+
+    pub fn tints(&self) -> &[TintVertex] {
+        &self.tints
+    }
+
+    // Tints the vertices of the block, returns the range of vertices to upload again.
+    // The vertices of a block are next to each other, a deduplicated mesh can put vertices of
+    // other blocks in the range, they keep their tint. None when the block has no face in the mesh.
+    pub fn set(&mut self, block: u32, tint: [f32; 3]) -> Option<Range<usize>> {
+        let first = self.vertex_blocks.iter().position(|&b| b == block)?;
+        let last = self.vertex_blocks.iter().rposition(|&b| b == block)?;
+        for (vertex, &b) in self.tints[first..=last].iter_mut().zip(&self.vertex_blocks[first..=last]) {
+            if b == block {
+                vertex.tint = tint;
+            }
+        }
+        Some(first..last + 1)
+    }
+
+    // color given to a vertex by the shader, before the lighting
+    pub fn tinted_color(&self, mesh: &Mesh, vertex: usize) -> [f32; 3] {
+        let color = mesh.vertices[vertex].color;
+        let tint = self.tints[vertex].tint;
+        [color[0] * tint[0], color[1] * tint[1], color[2] * tint[2]]
+    }
+}
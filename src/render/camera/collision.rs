@@ -0,0 +1,125 @@
+use cgmath::*;
+use std::time::Duration;
+use crate::render::math::Aabb;
+use crate::render::terrain::{block::HALF_BLOCK_SIZE, chunk::Chunk};
+
+// the boxes touching a block face are not inside it
+const SKIN: f32 = 0.001;
+// downward acceleration of the walking camera, in units per second squared
+const GRAVITY: f32 = 20.0;
+const JUMP_SPEED: f32 = 6.0;
+// the falls stay slower than this, in units per second
+const MAX_FALL_SPEED: f32 = 50.0;
+// the walking camera is a box going from its feet to just over the eye
+const WALKER_HALF_EXTENTS: [f32; 3] = [0.15, 0.4, 0.15];
+const EYE_HEIGHT: f32 = 0.7;
+
+// Blocks the water and the air let through. Under the chunk the world floor is solid.
+fn is_solid(chunk: &Chunk, cell: [i32; 3]) -> bool {
+    if cell[1] < 0 {
+        return chunk.world_floor;
+    }
+    matches!(chunk.block_at(cell[0], cell[1], cell[2]), Some(block_type) if chunk.registry.get(block_type).is_solid())
+}
+
+// cells overlapping [min, max] on one axis, the faces only touching them are left out
+fn cells(min: f32, max: f32) -> std::ops::RangeInclusive<i32> {
+    let block_size = 2.0 * HALF_BLOCK_SIZE;
+    let first = ((min + HALF_BLOCK_SIZE + SKIN) / block_size).floor() as i32;
+    let last = ((max + HALF_BLOCK_SIZE - SKIN) / block_size).floor() as i32;
+    first..=last
+}
+
+// Moves the box by the displacement one axis at a time, the vertical one first. Along each
+// axis the whole distance covered is checked, so a fast box cannot go through a thin wall
+// or a corner. Gives the displacement actually done and the axes that were blocked.
+pub fn sweep(chunk: &Chunk, aabb: Aabb, displacement: Vector3<f32>) -> (Vector3<f32>, [bool; 3]) {
+    let mut aabb = aabb;
+    let mut done = Vector3::zero();
+    let mut blocked = [false; 3];
+
+    for &axis in [1, 0, 2].iter() {
+        let distance = displacement[axis];
+        if distance == 0.0 {
+            continue;
+        }
+        // room covered by the move along the axis
+        let mut swept = aabb;
+        if distance > 0.0 {
+            swept.max[axis] += distance;
+        } else {
+            swept.min[axis] += distance;
+        }
+
+        let mut allowed = distance;
+        for x in cells(swept.min.x, swept.max.x) {
+            for y in cells(swept.min.y, swept.max.y) {
+                for z in cells(swept.min.z, swept.max.z) {
+                    let cell = [x, y, z];
+                    if !is_solid(chunk, cell) {
+                        continue;
+                    }
+                    let center = cell[axis] as f32 * 2.0 * HALF_BLOCK_SIZE;
+                    // the blocks already overlapping the box do not stop it
+                    if distance > 0.0 && center - HALF_BLOCK_SIZE >= aabb.max[axis] - SKIN {
+                        allowed = allowed.min(center - HALF_BLOCK_SIZE - aabb.max[axis] - SKIN).max(0.0);
+                    } else if distance < 0.0 && center + HALF_BLOCK_SIZE <= aabb.min[axis] + SKIN {
+                        allowed = allowed.max(center + HALF_BLOCK_SIZE - aabb.min[axis] + SKIN).min(0.0);
+                    }
+                }
+            }
+        }
+
+        blocked[axis] = allowed != distance;
+        aabb.min[axis] += allowed;
+        aabb.max[axis] += allowed;
+        done[axis] = allowed;
+    }
+
+    (done, blocked)
+}
+
+// Gravity and collisions of the camera in the walk mode, the controller moves it first
+pub struct Walker {
+    fall_speed: f32,
+    on_ground: bool,
+}
+
+impl Default for Walker {
+    fn default() -> Self {
+        Walker::new()
+    }
+}
+
+impl Walker {
+    pub fn new() -> Self {
+        Walker { fall_speed: 0.0, on_ground: false }
+    }
+
+    // box of the camera standing at position
+    pub fn aabb(position: Point3<f32>) -> Aabb {
+        let half = Vector3::from(WALKER_HALF_EXTENTS);
+        let center = position.to_vec() - Vector3::new(0.0, EYE_HEIGHT - half.y, 0.0);
+        Aabb::new(center - half, center + half)
+    }
+
+    // Replays the move of the camera from previous against the chunk, the vertical move
+    // of the controller is replaced by the fall, or a jump when the camera stands
+    pub fn update(&mut self, chunk: &Chunk, previous: Point3<f32>, position: &mut Point3<f32>, jump: bool, dt: Duration) {
+        let dt = dt.as_secs_f32();
+        if jump && self.on_ground {
+            self.fall_speed = JUMP_SPEED;
+        }
+        self.fall_speed = (self.fall_speed - GRAVITY * dt).max(-MAX_FALL_SPEED);
+
+        let mut displacement = *position - previous;
+        displacement.y = self.fall_speed * dt;
+        let (done, blocked) = sweep(chunk, Walker::aabb(previous), displacement);
+
+        self.on_ground = blocked[1] && self.fall_speed < 0.0;
+        if blocked[1] {
+            self.fall_speed = 0.0;
+        }
+        *position = previous + done;
+    }
+}
@@ -1,10 +1,12 @@
 use cgmath::*;
-use std::f32::consts::FRAC_PI_2;
 use std::time::Duration;
 use winit::dpi::PhysicalPosition;
 use winit::event::*;
 
-use super::Camera;
+use super::{Camera, SAFE_FRAC_PI_2};
+
+// radians per second when holding a roll key
+const FREE_ROLL_SPEED: f32 = std::f32::consts::FRAC_PI_2;
 
 #[derive(Debug)]
 pub struct CameraController {
@@ -14,11 +16,16 @@ pub struct CameraController {
     pub amount_backward: f32,
     pub amount_up: f32,
     pub amount_down: f32,
+    // only used by the free camera
+    pub amount_roll_left: f32,
+    pub amount_roll_right: f32,
     pub rotate_horizontal: f32,
     pub rotate_vertical: f32,
     pub scroll: f32,
     pub speed: f32,
     pub sensitivity: f32,
+    // mouse motions shorter than this are dropped, in logical pixels, 0 keeps them all
+    pub dead_zone: f32,
 }
 
 impl CameraController {
@@ -30,11 +37,14 @@ impl CameraController {
             amount_backward: 0.0,
             amount_up: 0.0,
             amount_down: 0.0,
+            amount_roll_left: 0.0,
+            amount_roll_right: 0.0,
             rotate_horizontal: 0.0,
             rotate_vertical: 0.0,
             scroll: 0.0,
             speed,
             sensitivity,
+            dead_zone: 0.0,
         }
     }
 
@@ -69,11 +79,39 @@ impl CameraController {
                 self.amount_down = amount;
                 true
             }
+            // the keys each side of Z, like Q and E on a QWERTY keyboard
+            VirtualKeyCode::A => {
+                self.amount_roll_left = amount;
+                true
+            }
+            VirtualKeyCode::E => {
+                self.amount_roll_right = amount;
+                true
+            }
             _ => false,
         }
     }
 
+    // releases the keys held down, for when the input goes somewhere else
+    pub fn stop(&mut self) {
+        self.amount_left = 0.0;
+        self.amount_right = 0.0;
+        self.amount_forward = 0.0;
+        self.amount_backward = 0.0;
+        self.amount_up = 0.0;
+        self.amount_down = 0.0;
+        self.amount_roll_left = 0.0;
+        self.amount_roll_right = 0.0;
+        self.rotate_horizontal = 0.0;
+        self.rotate_vertical = 0.0;
+        self.scroll = 0.0;
+    }
+
+    // some mice and compositors send tiny motions while still, they would slowly turn the camera
     pub fn process_mouse(&mut self, mouse_dx: f64, mouse_dy: f64) {
+        if (mouse_dx.hypot(mouse_dy) as f32) < self.dead_zone {
+            return;
+        }
         self.rotate_horizontal = mouse_dx as f32;
         self.rotate_vertical = mouse_dy as f32;
     }
@@ -87,12 +125,16 @@ impl CameraController {
     }
 
     pub fn update_camera(&mut self, camera: &mut Camera, dt: Duration) {
+        if camera.is_free() {
+            self.update_free_camera(camera, dt);
+            return;
+        }
         let dt = dt.as_secs_f32();
 
-        // Move forward/backward and left/right
-        let (yaw_sin, yaw_cos) = camera.yaw.0.sin_cos();
-        let forward = Vector3::new(yaw_cos, 0.0, yaw_sin).normalize();
-        let right = Vector3::new(-yaw_sin, 0.0, yaw_cos).normalize();
+        // Move forward/backward and left/right, on the plane across the up axis
+        let axis = camera.up_axis;
+        let forward = axis.direction(camera.yaw, Rad(0.0));
+        let right = axis.direction(camera.yaw + Rad::turn_div_4(), Rad(0.0));
         camera.position += forward * (self.amount_forward - self.amount_backward) * self.speed * dt;
         camera.position += right * (self.amount_right - self.amount_left) * self.speed * dt;
 
@@ -100,15 +142,13 @@ impl CameraController {
         // Note: this isn't an actual zoom. The camera's position
         // changes when zooming. I've added this to make it easier
         // to get closer to an object you want to focus on.
-        let (pitch_sin, pitch_cos) = camera.pitch.0.sin_cos();
-        let scrollward =
-            Vector3::new(pitch_cos * yaw_cos, pitch_sin, pitch_cos * yaw_sin).normalize();
+        let scrollward = axis.direction(camera.yaw, camera.pitch);
         camera.position += scrollward * self.scroll * self.speed * self.sensitivity * dt;
         self.scroll = 0.0;
 
         // Move up/down. Since we don't use roll, we can just
-        // modify the y coordinate directly.
-        camera.position.y += (self.amount_up - self.amount_down) * self.speed * dt;
+        // move along the up axis directly.
+        camera.position += axis.up() * (self.amount_up - self.amount_down) * self.speed * dt;
 
         // Rotate
         camera.yaw += Rad(self.rotate_horizontal) * self.sensitivity * dt;
@@ -121,10 +161,31 @@ impl CameraController {
         self.rotate_vertical = 0.0;
 
         // Keep the camera's angle from going too high/low.
-        if camera.pitch < -Rad(FRAC_PI_2) {
-            camera.pitch = -Rad(FRAC_PI_2);
-        } else if camera.pitch > Rad(FRAC_PI_2) {
-            camera.pitch = Rad(FRAC_PI_2);
+        if camera.pitch < -Rad(SAFE_FRAC_PI_2) {
+            camera.pitch = -Rad(SAFE_FRAC_PI_2);
+        } else if camera.pitch > Rad(SAFE_FRAC_PI_2) {
+            camera.pitch = Rad(SAFE_FRAC_PI_2);
         }
     }
+
+    // Every move follows the axes of the camera, the pitch is not limited
+    fn update_free_camera(&mut self, camera: &mut Camera, dt: Duration) {
+        let dt = dt.as_secs_f32();
+
+        let forward = camera.forward();
+        let up = camera.up();
+        let right = camera.right();
+        camera.position += forward * (self.amount_forward - self.amount_backward + self.scroll * self.sensitivity) * self.speed * dt;
+        camera.position += right * (self.amount_right - self.amount_left) * self.speed * dt;
+        camera.position += up * (self.amount_up - self.amount_down) * self.speed * dt;
+        self.scroll = 0.0;
+
+        camera.rotate(
+            Rad(self.rotate_horizontal) * self.sensitivity * dt,
+            Rad(-self.rotate_vertical) * self.sensitivity * dt,
+            Rad(FREE_ROLL_SPEED) * (self.amount_roll_right - self.amount_roll_left) * dt,
+        );
+        self.rotate_horizontal = 0.0;
+        self.rotate_vertical = 0.0;
+    }
 }
@@ -0,0 +1,71 @@
+use cgmath::*;
+use crate::render::math::Aabb;
+use super::{Camera, Projection};
+
+// Planes bounding what the camera sees, their normals look inside
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    // Gribb & Hartmann, the depth of the projection goes from 0 to 1 as in wgpu
+    pub fn from_matrix(view_proj: Matrix4<f32>) -> Self {
+        let row = |i: usize| Vector4::new(view_proj.x[i], view_proj.y[i], view_proj.z[i], view_proj.w[i]);
+        let planes = [
+            row(3) + row(0), // left
+            row(3) - row(0), // right
+            row(3) + row(1), // bottom
+            row(3) - row(1), // top
+            row(2),          // near
+            row(3) - row(2), // far
+        ];
+
+        let mut frustum = Frustum { planes };
+        // with unit normals the plane equation gives the distance to the plane
+        for plane in frustum.planes.iter_mut() {
+            *plane /= plane.truncate().magnitude();
+        }
+        frustum
+    }
+
+    pub fn from_camera(camera: &Camera, projection: &Projection) -> Self {
+        Frustum::from_matrix(projection.calc_matrix() * camera.calc_matrix())
+    }
+
+    // the chunks are culled with the frozen frustum when there is one, the camera is left out
+    pub fn culling(frozen: Option<&Frustum>, camera: &Camera, projection: &Projection) -> Self {
+        match frozen {
+            Some(frozen) => *frozen,
+            None => Frustum::from_camera(camera, projection),
+        }
+    }
+
+    fn distance(plane: &Vector4<f32>, point: Point3<f32>) -> f32 {
+        plane.truncate().dot(point.to_vec()) + plane.w
+    }
+
+    // false only when the sphere is fully outside, it can still be outside near the corners
+    pub fn contains_sphere(&self, center: Point3<f32>, radius: f32) -> bool {
+        self.planes.iter().all(|plane| Frustum::distance(plane, center) >= -radius)
+    }
+
+    // false only when the box is fully behind one of the planes
+    pub fn contains_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            // corner the furthest along the normal of the plane
+            let corner = Point3::new(
+                if plane.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if plane.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if plane.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+            );
+            Frustum::distance(plane, corner) >= 0.0
+        })
+    }
+
+    // the sphere around the box is a cheaper first test
+    pub fn contains_box(&self, aabb: &Aabb) -> bool {
+        let radius = aabb.size().magnitude() / 2.0;
+        self.contains_sphere(Point3::from_vec(aabb.center()), radius) && self.contains_aabb(aabb)
+    }
+}
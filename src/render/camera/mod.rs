@@ -1,16 +1,83 @@
 pub mod projection;
 pub mod controller;
+pub mod frustum;
+pub mod collision;
+pub mod shake;
 
 pub use controller::CameraController;
 pub use projection::Projection;
+pub use frustum::Frustum;
+pub use shake::CameraShake;
 
+use std::str::FromStr;
+use std::time::Duration;
+use anyhow::*;
 use cgmath::*;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+// the view matrix cannot be built looking straight up or down
+pub const SAFE_FRAC_PI_2: f32 = std::f32::consts::FRAC_PI_2 - 0.0001;
+
+// Axis of the world pointing to the sky. The yaw goes around it from the east axis toward
+// the north one, the three of them keep the handedness of x, y and z.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpAxis {
+    #[default]
+    Y,
+    // for the scenes made in tools where the ground is the xy plane
+    Z,
+}
+
+impl UpAxis {
+    pub fn up(&self) -> Vector3<f32> {
+        match self {
+            UpAxis::Y => Vector3::unit_y(),
+            UpAxis::Z => Vector3::unit_z(),
+        }
+    }
+
+    // direction of a yaw of 0
+    pub fn east(&self) -> Vector3<f32> {
+        Vector3::unit_x()
+    }
+
+    // direction of a yaw of 90°, +Z when Y is up
+    pub fn north(&self) -> Vector3<f32> {
+        self.east().cross(self.up())
+    }
+
+    // direction of the yaw and pitch, pitching up goes toward the up axis
+    pub fn direction(&self, yaw: Rad<f32>, pitch: Rad<f32>) -> Vector3<f32> {
+        let (pitch_sin, pitch_cos) = pitch.0.sin_cos();
+        let (yaw_sin, yaw_cos) = yaw.0.sin_cos();
+        self.east() * pitch_cos * yaw_cos + self.up() * pitch_sin + self.north() * pitch_cos * yaw_sin
+    }
+}
+
+impl FromStr for UpAxis {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "y" => Ok(UpAxis::Y),
+            "z" => Ok(UpAxis::Z),
+            _ => bail!("expected y or z"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Camera {
     pub position: Point3<f32>,
     pub yaw: Rad<f32>,
     pub pitch: Rad<f32>,
+    // Some in the free mode, the camera can then roll and loop around. The yaw and
+    // pitch still follow the direction so going back to the default mode keeps it.
+    pub orientation: Option<Quaternion<f32>>,
+    // only moves the view, the position stays where the controller put it
+    pub shake: Option<CameraShake>,
+    pub up_axis: UpAxis,
 }
 
 impl Camera {
@@ -23,14 +90,153 @@ impl Camera {
             position: position.into(),
             yaw: yaw.into(),
             pitch: pitch.into(),
+            orientation: None,
+            shake: None,
+            up_axis: UpAxis::Y,
+        }
+    }
+
+    // the same camera, the yaw and pitch then go around this axis
+    pub fn with_up_axis(mut self, up_axis: UpAxis) -> Self {
+        self.up_axis = up_axis;
+        self
+    }
+
+    // Camera at position looking at target, with the yaw and pitch going around up_axis so
+    // the view keeps it at the top without rolling. Looking straight along the up axis
+    // faces away from its north.
+    pub fn look_at<V: Into<Point3<f32>>, T: Into<Point3<f32>>>(position: V, target: T, up_axis: UpAxis) -> Self {
+        let position = position.into();
+        let direction = (target.into() - position).normalize();
+
+        // the direction projected on the plane across the up axis gives the yaw
+        let vertical = direction.dot(up_axis.up()).clamp(-1.0, 1.0);
+        let horizontal = direction - up_axis.up() * vertical;
+        let yaw = if horizontal.magnitude2() > f32::EPSILON {
+            Rad(horizontal.dot(up_axis.north()).atan2(horizontal.dot(up_axis.east())))
+        } else {
+            Rad::from(Deg(-90.0))
+        };
+
+        Self {
+            position,
+            yaw,
+            pitch: Rad(vertical.asin().clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2)),
+            orientation: None,
+            shake: None,
+            up_axis,
+        }
+    }
+
+    // unit vector the camera looks along
+    pub fn direction(&self) -> Vector3<f32> {
+        if let Some(orientation) = self.orientation {
+            return orientation.rotate_vector(-Vector3::unit_z());
+        }
+        self.up_axis.direction(self.yaw, self.pitch)
+    }
+
+    // same as the direction, with right and up it makes the basis of the view
+    pub fn forward(&self) -> Vector3<f32> {
+        self.direction()
+    }
+
+    // unit vector to the right of the screen, it stays horizontal out of the free mode
+    pub fn right(&self) -> Vector3<f32> {
+        match self.orientation {
+            Some(orientation) => orientation.rotate_vector(Vector3::unit_x()),
+            None => self.direction().cross(self.up_axis.up()).normalize(),
+        }
+    }
+
+    // unit vector to the top of the screen, it leans back when the camera looks down
+    pub fn up(&self) -> Vector3<f32> {
+        match self.orientation {
+            Some(orientation) => orientation.rotate_vector(Vector3::unit_y()),
+            None => self.right().cross(self.direction()),
+        }
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.orientation.is_some()
+    }
+
+    // The free mode starts from the current yaw and pitch and goes back to the yaw and
+    // pitch of its direction, the roll is lost
+    pub fn set_free(&mut self, free: bool) {
+        self.orientation = if free {
+            // the camera looks along its -Z axis with +Y at the top
+            Some(Quaternion::from(Matrix3::from_cols(self.right(), self.up(), -self.forward())))
+        } else {
+            None
+        };
+    }
+
+    // Turns the free camera around its own axes. A positive yaw turns right, a positive
+    // pitch looks up and a positive roll tilts the top of the screen to the right.
+    pub fn rotate(&mut self, yaw: Rad<f32>, pitch: Rad<f32>, roll: Rad<f32>) {
+        let orientation = match self.orientation {
+            Some(orientation) => orientation,
+            None => return,
+        };
+        let turn = Quaternion::from_angle_y(-yaw) * Quaternion::from_angle_x(pitch) * Quaternion::from_angle_z(-roll);
+        self.orientation = Some((orientation * turn).normalize());
+
+        let direction = self.direction();
+        let axis = self.up_axis;
+        self.yaw = Rad(direction.dot(axis.north()).atan2(direction.dot(axis.east())));
+        self.pitch = Rad(direction.dot(axis.up()).clamp(-1.0, 1.0).asin().clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2));
+    }
+
+    // a new shake replaces the one still going on
+    pub fn add_shake(&mut self, intensity: f32, duration: Duration) {
+        self.shake = Some(CameraShake::new(intensity, duration));
+    }
+
+    // once a frame, the shake is dropped when it is over
+    pub fn update(&mut self, dt: Duration) {
+        if let Some(shake) = &mut self.shake {
+            shake.update(dt);
+            if shake.is_finished() {
+                self.shake = None;
+            }
+        }
+    }
+
+    // where the view is built from, the position moved by the shake
+    pub fn view_position(&self) -> Point3<f32> {
+        match &self.shake {
+            Some(shake) => self.position + shake.offset(self.right(), self.up()),
+            None => self.position,
         }
     }
 
     pub fn calc_matrix(&self) -> Matrix4<f32> {
         Matrix4::look_to_rh(
-            self.position,
-            Vector3::new(self.yaw.0.cos(), self.pitch.0.sin(), self.yaw.0.sin()).normalize(),
-            Vector3::unit_y(),
+            self.view_position(),
+            self.direction(),
+            self.up(),
         )
     }
-}
\ No newline at end of file
+}
+
+// Ray from the near plane through a point of the window, in physical pixels from its top
+// left corner. The point is unprojected through the inverse view projection so it keeps
+// its fractional part, the center of the window gives the direction of the camera.
+pub fn cursor_ray(camera: &Camera, projection: &Projection, cursor: PhysicalPosition<f64>, size: PhysicalSize<u32>) -> (Point3<f32>, Vector3<f32>) {
+    let x = (2.0 * cursor.x / size.width.max(1) as f64 - 1.0) as f32;
+    let y = (1.0 - 2.0 * cursor.y / size.height.max(1) as f64) as f32;
+
+    let inverse = match (projection.calc_matrix() * camera.calc_matrix()).invert() {
+        Some(inverse) => inverse,
+        None => return (camera.view_position(), camera.direction()),
+    };
+    // the depth of wgpu goes from 0 on the near plane to 1 on the far one
+    let unproject = |depth: f32| {
+        let point = inverse * Vector4::new(x, y, depth, 1.0);
+        Point3::from_homogeneous(point)
+    };
+    let near = unproject(0.0);
+    let far = unproject(1.0);
+    (near, (far - near).normalize())
+}
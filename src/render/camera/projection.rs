@@ -9,6 +9,10 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
     0.0, 0.0, 0.5, 1.0,
 );
 
+// bounds of the vertical field of view, in degrees
+pub const MIN_FOV: f32 = 30.0;
+pub const MAX_FOV: f32 = 110.0;
+
 pub struct Projection {
     aspect: f32,
     fov_y: Rad<f32>,
@@ -30,6 +34,24 @@ impl Projection {
         self.aspect = width as f32 / height as f32;
     }
 
+    pub fn fov_y(&self) -> Rad<f32> {
+        self.fov_y
+    }
+
+    // the field of view is kept between MIN_FOV and MAX_FOV
+    pub fn set_fov_y<F: Into<Rad<f32>>>(&mut self, fov_y: F) {
+        let fov_y: Deg<f32> = Deg::from(fov_y.into());
+        self.fov_y = Deg(fov_y.0.clamp(MIN_FOV, MAX_FOV)).into();
+    }
+
+    // Distance from which a sphere of that radius fits in the view, the narrowest of the
+    // vertical and horizontal fields of view decides
+    pub fn fit_distance(&self, radius: f32) -> f32 {
+        let half_fov_y = self.fov_y.0 / 2.0;
+        let half_fov_x = (half_fov_y.tan() * self.aspect).atan();
+        radius / half_fov_y.min(half_fov_x).sin()
+    }
+
     pub fn calc_matrix(&self) -> Matrix4<f32> {
         OPENGL_TO_WGPU_MATRIX * perspective(self.fov_y, self.aspect, self.znear, self.z_far)
     }
@@ -0,0 +1,60 @@
+use std::time::Duration;
+use cgmath::*;
+use crate::render::terrain::noise::hash_rng;
+
+// Random offset of the view fading out over its duration. A new offset is drawn at each
+// update, the position of the camera itself never moves.
+#[derive(Debug)]
+pub struct CameraShake {
+    intensity: f32,
+    duration: Duration,
+    elapsed: Duration,
+    step: i32,
+    // along the right and up axes of the view, in [-1, 1]
+    offset: Vector2<f32>,
+}
+
+impl CameraShake {
+    pub fn new(intensity: f32, duration: Duration) -> Self {
+        let mut shake = CameraShake {
+            intensity,
+            duration,
+            elapsed: Duration::from_secs(0),
+            step: 0,
+            offset: Vector2::zero(),
+        };
+        shake.draw_offset();
+        shake
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        self.step += 1;
+        self.draw_offset();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    // the amplitude goes down linearly to zero at the end of the duration
+    pub fn amplitude(&self) -> f32 {
+        if self.is_finished() {
+            return 0.0;
+        }
+        self.intensity * (1.0 - self.elapsed.as_secs_f32() / self.duration.as_secs_f32())
+    }
+
+    // offset of the view, right and up being the axes of the screen
+    pub fn offset(&self, right: Vector3<f32>, up: Vector3<f32>) -> Vector3<f32> {
+        (right * self.offset.x + up * self.offset.y) * self.amplitude()
+    }
+
+    fn draw_offset(&mut self) {
+        // the same shake always goes the same way, which keeps the replays identical
+        self.offset = Vector2::new(
+            hash_rng(0, self.step, 0, 0) * 2.0 - 1.0,
+            hash_rng(0, self.step, 1, 0) * 2.0 - 1.0,
+        );
+    }
+}
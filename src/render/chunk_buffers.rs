@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use cgmath::*;
+use log::info;
+use super::terrain::{self, block::HALF_BLOCK_SIZE, chunk::{Chunk, MeshMode}, mesh::Mesh, mesh_cache::MeshCache};
+use super::block_tint::{self, BlockTints};
+use super::growable_buffer::GrowableBuffer;
+use super::math::Aabb;
+use super::vertex::ColorVertex;
+
+// GPU side of a chunk, the vertices are already moved to the chunk position. The buffers
+// are kept between the meshes of the chunk, see GrowableBuffer.
+pub struct ChunkBuffers {
+    pub vertex_buffer: GrowableBuffer,
+    pub index_buffer: GrowableBuffer,
+    // the point mode draws the vertices without their indices
+    pub num_vertices: u32,
+    pub num_index: u32,
+    pub water_vertex_buffer: GrowableBuffer,
+    pub water_index_buffer: GrowableBuffer,
+    pub num_water_vertices: u32,
+    pub num_water_index: u32,
+    pub glass_vertex_buffer: GrowableBuffer,
+    pub glass_index_buffer: GrowableBuffer,
+    pub num_glass_index: u32,
+    // second vertex buffer of each mesh, see block_tint
+    pub tint_buffer: GrowableBuffer,
+    pub water_tint_buffer: GrowableBuffer,
+    pub glass_tint_buffer: GrowableBuffer,
+    tints: [BlockTints; 3],
+    // given again to the vertices of the next meshes
+    block_tints: HashMap<u32, [f32; 3]>,
+    // corners of the space taken by the chunk blocks
+    pub bounds: Aabb,
+    // false when the chunk is out of the view and is not drawn
+    pub visible: bool,
+}
+
+impl ChunkBuffers {
+    // Meshes the chunk and uploads it, both renderers go through here
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, mesh_cache: &mut MeshCache, chunk: &Chunk, mode: MeshMode, dedup: bool) -> Self {
+        let block_tints = HashMap::new();
+        let meshes = ChunkMeshes::new(mesh_cache, chunk, mode, dedup, &block_tints);
+        let create = |label, usage, data| GrowableBuffer::new(device, queue, label, usage, data);
+        let [tints, water_tints, glass_tints] = &meshes.tints;
+
+        ChunkBuffers {
+            vertex_buffer: create("Vertex Buffer", wgpu::BufferUsage::VERTEX, bytemuck::cast_slice(&meshes.vertices)),
+            index_buffer: create("Indices Buffer", wgpu::BufferUsage::INDEX, bytemuck::cast_slice(&meshes.indices)),
+            num_vertices: meshes.vertices.len() as u32,
+            num_index: meshes.indices.len() as u32,
+            water_vertex_buffer: create("Water Vertex Buffer", wgpu::BufferUsage::VERTEX, bytemuck::cast_slice(&meshes.water_vertices)),
+            water_index_buffer: create("Water Indices Buffer", wgpu::BufferUsage::INDEX, bytemuck::cast_slice(&meshes.water_indices)),
+            num_water_vertices: meshes.water_vertices.len() as u32,
+            num_water_index: meshes.water_indices.len() as u32,
+            glass_vertex_buffer: create("Glass Vertex Buffer", wgpu::BufferUsage::VERTEX, bytemuck::cast_slice(&meshes.glass_vertices)),
+            glass_index_buffer: create("Glass Indices Buffer", wgpu::BufferUsage::INDEX, bytemuck::cast_slice(&meshes.glass_indices)),
+            num_glass_index: meshes.glass_indices.len() as u32,
+            tint_buffer: create("Tint Buffer", wgpu::BufferUsage::VERTEX, bytemuck::cast_slice(tints.tints())),
+            water_tint_buffer: create("Water Tint Buffer", wgpu::BufferUsage::VERTEX, bytemuck::cast_slice(water_tints.tints())),
+            glass_tint_buffer: create("Glass Tint Buffer", wgpu::BufferUsage::VERTEX, bytemuck::cast_slice(glass_tints.tints())),
+            tints: meshes.tints,
+            block_tints,
+            bounds: chunk_bounds(chunk),
+            visible: true,
+        }
+    }
+
+    // Meshes the chunk again into the same buffers, returns how many had to be reallocated
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, mesh_cache: &mut MeshCache, chunk: &Chunk, mode: MeshMode, dedup: bool) -> usize {
+        let meshes = ChunkMeshes::new(mesh_cache, chunk, mode, dedup, &self.block_tints);
+        let [tints, water_tints, glass_tints] = &meshes.tints;
+        let reallocated = [
+            self.vertex_buffer.write(device, queue, bytemuck::cast_slice(&meshes.vertices)),
+            self.index_buffer.write(device, queue, bytemuck::cast_slice(&meshes.indices)),
+            self.water_vertex_buffer.write(device, queue, bytemuck::cast_slice(&meshes.water_vertices)),
+            self.water_index_buffer.write(device, queue, bytemuck::cast_slice(&meshes.water_indices)),
+            self.glass_vertex_buffer.write(device, queue, bytemuck::cast_slice(&meshes.glass_vertices)),
+            self.glass_index_buffer.write(device, queue, bytemuck::cast_slice(&meshes.glass_indices)),
+            self.tint_buffer.write(device, queue, bytemuck::cast_slice(tints.tints())),
+            self.water_tint_buffer.write(device, queue, bytemuck::cast_slice(water_tints.tints())),
+            self.glass_tint_buffer.write(device, queue, bytemuck::cast_slice(glass_tints.tints())),
+        ];
+        self.tints = meshes.tints;
+        self.num_vertices = meshes.vertices.len() as u32;
+        self.num_index = meshes.indices.len() as u32;
+        self.num_water_vertices = meshes.water_vertices.len() as u32;
+        self.num_water_index = meshes.water_indices.len() as u32;
+        self.num_glass_index = meshes.glass_indices.len() as u32;
+        self.bounds = chunk_bounds(chunk);
+        reallocated.iter().filter(|r| **r).count()
+    }
+
+    // Multiplies the color of a block of the chunk by the tint, only its vertices are
+    // uploaded again. The tint is kept when the chunk is meshed again.
+    pub fn set_block_tint(&mut self, queue: &wgpu::Queue, block: [usize; 3], tint: [f32; 3]) {
+        let block = block_tint::block_index(block);
+        self.block_tints.insert(block, tint);
+        let buffers = [&self.tint_buffer, &self.water_tint_buffer, &self.glass_tint_buffer];
+        for (tints, buffer) in self.tints.iter_mut().zip(buffers.iter()) {
+            if let Some(range) = tints.set(block, tint) {
+                let offset = (range.start * std::mem::size_of::<block_tint::TintVertex>()) as wgpu::BufferAddress;
+                queue.write_buffer(&buffer.buffer, offset, bytemuck::cast_slice(&tints.tints()[range]));
+            }
+        }
+    }
+
+    // allocated size of the nine buffers on the GPU
+    pub fn memory_bytes(&self) -> u64 {
+        self.vertex_buffer.capacity + self.index_buffer.capacity
+            + self.water_vertex_buffer.capacity + self.water_index_buffer.capacity
+            + self.glass_vertex_buffer.capacity + self.glass_index_buffer.capacity
+            + self.tint_buffer.capacity + self.water_tint_buffer.capacity + self.glass_tint_buffer.capacity
+    }
+}
+
+// the terrain, water and glass meshes, in world space
+struct ChunkMeshes {
+    vertices: Vec<ColorVertex>,
+    indices: Vec<u16>,
+    water_vertices: Vec<ColorVertex>,
+    water_indices: Vec<u16>,
+    glass_vertices: Vec<ColorVertex>,
+    glass_indices: Vec<u16>,
+    // of the terrain, water and glass
+    tints: [BlockTints; 3],
+}
+
+impl ChunkMeshes {
+    fn new(mesh_cache: &mut MeshCache, chunk: &Chunk, mode: MeshMode, dedup: bool, block_tints: &HashMap<u32, [f32; 3]>) -> Self {
+        let offset = chunk.world_offset();
+
+        // the cached meshes are local to their chunk so identical chunks can share them
+        let key = terrain::mesh_cache::mesh_key(chunk, mode, dedup);
+        let mesh = mesh_cache.get_or_insert_with(key, || create_chunk_mesh(chunk, mode, dedup));
+        let vertices = translate(&mesh.vertices, offset);
+        let indices = mesh.indices.clone();
+
+        let water = chunk.create_water_mesh();
+        terrain::mesh::debug_validate_mesh(&water.vertices, &water.indices);
+        let water_vertices = translate(&water.vertices, offset);
+
+        let glass = chunk.create_glass_mesh();
+        terrain::mesh::debug_validate_mesh(&glass.vertices, &glass.indices);
+        let glass_vertices = translate(&glass.vertices, offset);
+
+        // the blocks are found from the local positions
+        let tints = [
+            BlockTints::new(mesh, block_tints),
+            BlockTints::new(&water, block_tints),
+            BlockTints::new(&glass, block_tints),
+        ];
+
+        ChunkMeshes {
+            vertices,
+            indices,
+            water_vertices,
+            water_indices: water.indices,
+            glass_vertices,
+            glass_indices: glass.indices,
+            tints,
+        }
+    }
+}
+
+fn chunk_bounds(chunk: &Chunk) -> Aabb {
+    let min = Vector3::new(-HALF_BLOCK_SIZE, -HALF_BLOCK_SIZE, -HALF_BLOCK_SIZE) + chunk.world_offset();
+    let size = Vector3::new(chunk.width as f32, chunk.height as f32, chunk.width as f32) * 2.0 * HALF_BLOCK_SIZE;
+    Aabb::new(min, min + size)
+}
+
+fn create_chunk_mesh(chunk: &Chunk, mode: MeshMode, dedup: bool) -> Mesh {
+    let mesh = chunk.create_mesh_with(mode);
+    terrain::mesh::debug_validate_mesh(&mesh.vertices, &mesh.indices);
+    if !dedup {
+        return mesh;
+    }
+    let unique = terrain::mesh::deduplicate_vertices(&mesh);
+    info!("Deduplicated {} vertices down to {}", mesh.vertex_count(), unique.vertex_count());
+    terrain::mesh::debug_validate_mesh(&unique.vertices, &unique.indices);
+    unique
+}
+
+// moves the vertices of a chunk to where it is in the world
+pub fn translate(vertices: &[ColorVertex], offset: Vector3<f32>) -> Vec<ColorVertex> {
+    vertices.iter().map(|vertex| {
+        let position = Vector3::from(vertex.position) + offset;
+        ColorVertex { position: position.into(), ..*vertex }
+    }).collect()
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+use cgmath::*;
+use super::terrain::block::BlockType;
+use super::terrain::chunk::Chunk;
+
+// Changes asked to the engine by any input source, possibly from another thread. They
+// go through a channel and are applied at the start of the next update.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EngineCommand {
+    // in the main chunk, the positions out of it and the blocks its registry lacks are ignored
+    SetBlock { position: [usize; 3], block_type: BlockType },
+    // the camera keeps its orientation
+    TeleportCamera { position: Point3<f32> },
+    // shakes the view, then fades out over the duration
+    ShakeCamera { intensity: f32, duration: Duration },
+    // builds a new world, like the N key
+    SetSeed(u32),
+    // only the chunks in the view get the current terrain, like the R key
+    RegenerateVisible,
+    SetPaused(bool),
+    // reads the config file again, like the F6 key
+    ReloadConfig,
+}
+
+// Applies a SetBlock to the chunk, which is then meshed again by the update. False when
+// the position is out of the chunk or the block is not in its registry, the chunk is then
+// left as is.
+pub fn set_block(chunk: &mut Chunk, [x, y, z]: [usize; 3], block_type: BlockType) -> bool {
+    if chunk.block_at(x as i32, y as i32, z as i32).is_none() || !chunk.registry.contains(block_type) {
+        return false;
+    }
+    chunk.set_block(x, y, z, block_type);
+    true
+}
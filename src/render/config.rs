@@ -0,0 +1,257 @@
+use anyhow::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use super::terrain::{chunk::TerrainConfig, chunk_manager::DEFAULT_VIEW_RADIUS};
+use super::window::WindowConfig;
+use super::camera::{CameraController, Projection, UpAxis};
+
+pub const USAGE: &str = "usage: rover-engine [--width <px>] [--height <px>] [--fullscreen] \
+[--cursor-grab <lock|confine>] [--seed <u32>] [--frequency <f64>] [--amplitude <f64>] [--noise <opensimplex|perlin|value>] [--sea-level <blocks>] [--color-jitter <0-1>] [--scene <file>] [--blocks <file>] \
+[--benchmark <seconds>] [--frames <count>] [--max-frame-time <seconds>] [--autosave] [--multi-chunk] [--chunk-seeds] [--config <file>] [--anisotropy <1-16>] [--hdr] [--debug-labels] [--record <file>] [--replay <file>] [--generation-budget <chunks>] [--lod-threshold <px>] [--mouse-dead-zone <px>] [--ao-strength <0-1>] [--light-center <x,y,z>] [--light-radius <blocks>] [--up-axis <y|z>]";
+
+// Settings tweaked while running, they are written back to the config file on exit
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewConfig {
+    // vertical field of view, in degrees
+    pub fov: f32,
+    // chunks drawn on each side of the main one
+    pub view_radius: i32,
+    pub sensitivity: f32,
+    // mouse motions under this length in logical pixels are ignored, 0 turns it off
+    pub mouse_dead_zone: f32,
+}
+
+impl Default for ViewConfig {
+    fn default() -> Self {
+        ViewConfig {
+            fov: 45.0,
+            view_radius: DEFAULT_VIEW_RADIUS,
+            sensitivity: 0.6,
+            mouse_dead_zone: 0.0,
+        }
+    }
+}
+
+impl ViewConfig {
+    // the settings taken by the camera, the view radius needs the chunks to be generated again
+    pub fn apply(&self, projection: &mut Projection, controller: &mut CameraController) {
+        projection.set_fov_y(cgmath::Deg(self.fov));
+        controller.sensitivity = self.sensitivity;
+        controller.dead_zone = self.mouse_dead_zone;
+    }
+
+    // the settings as they are while running, saved on exit
+    pub fn current(projection: &Projection, controller: &CameraController, view_radius: i32) -> Self {
+        ViewConfig {
+            fov: cgmath::Deg::from(projection.fov_y()).0,
+            view_radius,
+            sensitivity: controller.sensitivity,
+            mouse_dead_zone: controller.dead_zone,
+        }
+    }
+}
+
+// Settings given on the command line, or read from a json file with --config
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub window: WindowConfig,
+    pub terrain: TerrainConfig,
+    pub view: ViewConfig,
+    // voxel file loaded instead of generating the terrain
+    pub scene: Option<String>,
+    // json file with the colors of the blocks, see BlockRegistry
+    pub blocks: Option<String>,
+    // flies on a fixed path for that many seconds then exits with the frame times
+    #[serde(skip)]
+    pub benchmark: Option<f32>,
+    // exits after drawing that many frames, for the smoke tests
+    #[serde(skip)]
+    pub frames: Option<u32>,
+    // longest time step given to the update, in seconds, 0.1 when None
+    pub max_frame_time: Option<f32>,
+    // saves the session when the window is closed
+    pub autosave: bool,
+    // draws the chunks around the main one from the start
+    pub multi_chunk: bool,
+    // gives each chunk around the main one its own seed, to look at the seams
+    pub chunk_seeds: bool,
+    // chunks around the main one generated by each update, 2 when None
+    pub generation_budget: Option<usize>,
+    // darkening of the corners by the ambient occlusion, from 0 to 1, 1 when None
+    pub ao_strength: Option<f32>,
+    // error in pixels the level of detail of a chunk can show, 1 when None and 0 turns it off
+    pub lod_threshold: Option<f32>,
+    // the light goes around that point at that distance, the origin and 3.46 blocks when None
+    pub light_center: Option<[f32; 3]>,
+    pub light_radius: Option<f32>,
+    // axis of the world pointing up, for the camera and the light
+    pub up_axis: UpAxis,
+    // anisotropic filtering of the block atlas, off when None
+    pub anisotropy: Option<u8>,
+    // draws the scene in floating point colors then tone maps it, when the adapter can
+    pub hdr: bool,
+    // the frame index goes in the labels of the passes, for the GPU debuggers
+    pub debug_labels: bool,
+    // the inputs are saved to that file on exit
+    #[serde(skip)]
+    pub record: Option<String>,
+    // the inputs are read from that file instead of the devices
+    #[serde(skip)]
+    pub replay: Option<String>,
+    // file given with --config, the config is saved back to it on exit
+    #[serde(skip)]
+    pub path: Option<String>,
+}
+
+impl Config {
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--width" => config.window.width = parse_positive(&arg, args.next())?,
+                "--height" => config.window.height = parse_positive(&arg, args.next())?,
+                "--fullscreen" => config.window.fullscreen = true,
+                "--cursor-grab" => config.window.cursor_grab = parse_value(&arg, args.next())?,
+                "--seed" => config.terrain.seed = parse_value(&arg, args.next())?,
+                "--frequency" => config.terrain.frequency = parse_value(&arg, args.next())?,
+                "--amplitude" => config.terrain.amplitude = parse_value(&arg, args.next())?,
+                "--noise" => config.terrain.noise = parse_value(&arg, args.next())?,
+                "--sea-level" => config.terrain.sea_level = parse_value(&arg, args.next())?,
+                "--color-jitter" => config.terrain.color_jitter = parse_strength(&arg, args.next())?,
+                "--scene" => config.scene = Some(parse_value(&arg, args.next())?),
+                "--blocks" => config.blocks = Some(parse_value(&arg, args.next())?),
+                "--benchmark" => config.benchmark = Some(parse_duration(&arg, args.next())?),
+                "--frames" => config.frames = Some(parse_positive(&arg, args.next())?),
+                "--max-frame-time" => config.max_frame_time = Some(parse_duration(&arg, args.next())?),
+                "--autosave" => config.autosave = true,
+                "--multi-chunk" => config.multi_chunk = true,
+                "--chunk-seeds" => config.chunk_seeds = true,
+                "--anisotropy" => config.anisotropy = Some(parse_anisotropy(&arg, args.next())?),
+                "--hdr" => config.hdr = true,
+                "--debug-labels" => config.debug_labels = true,
+                "--generation-budget" => config.generation_budget = Some(parse_positive(&arg, args.next())? as usize),
+                "--lod-threshold" => config.lod_threshold = Some(parse_threshold(&arg, args.next())?),
+                "--mouse-dead-zone" => config.view.mouse_dead_zone = parse_threshold(&arg, args.next())?,
+                "--ao-strength" => config.ao_strength = Some(parse_strength(&arg, args.next())?),
+                "--light-center" => config.light_center = Some(parse_point(&arg, args.next())?),
+                "--up-axis" => config.up_axis = parse_value(&arg, args.next())?,
+                "--light-radius" => config.light_radius = Some(parse_distance(&arg, args.next())?),
+                "--record" => config.record = Some(parse_value(&arg, args.next())?),
+                "--replay" => config.replay = Some(parse_value(&arg, args.next())?),
+                // replaces the flags given before, the ones after override the file
+                "--config" => {
+                    let path: String = parse_value(&arg, args.next())?;
+                    if Path::new(&path).exists() {
+                        config = Config::load(&path)?;
+                    }
+                    config.path = Some(path);
+                }
+                _ => bail!("Unknown argument: {}\n{}", arg, USAGE),
+            }
+        }
+
+        Ok(config)
+    }
+
+    // Reads the config file again, what only comes from the command line is kept. The
+    // config in use is left as is when the file cannot be read or parsed. The keys are not
+    // part of it, they are still the ones of State::device_input.
+    pub fn reload(&self) -> Result<Config> {
+        let path = self.path.as_ref().context("No config file to reload, it is given with --config")?;
+        let mut config = Config::load(path)?;
+        config.path = self.path.clone();
+        config.benchmark = self.benchmark;
+        config.frames = self.frames;
+        config.record = self.record.clone();
+        config.replay = self.replay.clone();
+        Ok(config)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).with_context(|| format!("Unable to write the config to {}", path.display()))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read the config from {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Result<T> {
+    let value = value.with_context(|| format!("Missing value for {}\n{}", flag, USAGE))?;
+    value
+        .parse()
+        .ok()
+        .with_context(|| format!("Invalid value for {}: {}", flag, value))
+}
+
+fn parse_positive(flag: &str, value: Option<String>) -> Result<u32> {
+    let size: u32 = parse_value(flag, value)?;
+    if size == 0 {
+        bail!("{} must be greater than 0", flag);
+    }
+    Ok(size)
+}
+
+fn parse_duration(flag: &str, value: Option<String>) -> Result<f32> {
+    let seconds: f32 = parse_value(flag, value)?;
+    if !(seconds > 0.0 && seconds.is_finite()) {
+        bail!("{} must be a positive number of seconds", flag);
+    }
+    Ok(seconds)
+}
+
+fn parse_threshold(flag: &str, value: Option<String>) -> Result<f32> {
+    let pixels: f32 = parse_value(flag, value)?;
+    if !(pixels >= 0.0 && pixels.is_finite()) {
+        bail!("{} must be a number of pixels, 0 or more", flag);
+    }
+    Ok(pixels)
+}
+
+fn parse_distance(flag: &str, value: Option<String>) -> Result<f32> {
+    let distance: f32 = parse_value(flag, value)?;
+    if !(distance > 0.0 && distance.is_finite()) {
+        bail!("{} must be a positive distance", flag);
+    }
+    Ok(distance)
+}
+
+// three numbers separated by commas
+fn parse_point(flag: &str, value: Option<String>) -> Result<[f32; 3]> {
+    let value: String = parse_value(flag, value)?;
+    let coordinates: Vec<f32> = value.split(',')
+        .map(|c| c.trim().parse::<f32>().ok().filter(|c| c.is_finite()))
+        .collect::<Option<_>>()
+        .with_context(|| format!("Invalid value for {}: {}", flag, value))?;
+    match coordinates[..] {
+        [x, y, z] => Ok([x, y, z]),
+        _ => bail!("{} must be given as x,y,z", flag),
+    }
+}
+
+fn parse_strength(flag: &str, value: Option<String>) -> Result<f32> {
+    let strength: f32 = parse_value(flag, value)?;
+    if !(0.0..=1.0).contains(&strength) {
+        bail!("{} must be between 0 and 1", flag);
+    }
+    Ok(strength)
+}
+
+fn parse_anisotropy(flag: &str, value: Option<String>) -> Result<u8> {
+    let anisotropy: u8 = parse_value(flag, value)?;
+    if !(1..=16).contains(&anisotropy) {
+        bail!("{} must be between 1 and 16", flag);
+    }
+    Ok(anisotropy)
+}
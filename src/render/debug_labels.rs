@@ -0,0 +1,32 @@
+use std::borrow::Cow;
+
+// Labels of the encoders and the passes, as shown by the GPU debuggers like RenderDoc.
+// With --debug-labels they tell the frame they belong to, the fixed names are kept otherwise
+// so nothing is formatted for each frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DebugLabels {
+    pub enabled: bool,
+}
+
+impl DebugLabels {
+    pub fn new(enabled: bool) -> Self {
+        DebugLabels { enabled }
+    }
+
+    pub fn label<'a>(&self, name: &'a str, frame: u64) -> Cow<'a, str> {
+        if self.enabled {
+            Cow::Owned(format!("{} (frame {})", name, frame))
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+
+    // with what the pass draws, like the number of chunks, only asked for when enabled
+    pub fn detailed_label<'a, F: FnOnce() -> String>(&self, name: &'a str, frame: u64, detail: F) -> Cow<'a, str> {
+        if self.enabled {
+            Cow::Owned(format!("{} (frame {}, {})", name, frame, detail()))
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+}
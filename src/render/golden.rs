@@ -0,0 +1,71 @@
+use anyhow::*;
+use log::info;
+use std::path::Path;
+
+// set to anything but an empty value to write the captured images as the new goldens
+pub const UPDATE_GOLDENS_VAR: &str = "ROVER_UPDATE_GOLDENS";
+// a pixel differs once one of its channels is further than this from the golden
+pub const DEFAULT_PIXEL_TOLERANCE: u8 = 8;
+// part of the pixels that may differ, drivers do not rasterize the edges the same way
+pub const DEFAULT_MAX_DIFF_RATIO: f32 = 0.01;
+
+#[derive(Debug, PartialEq)]
+pub struct ImageDiff {
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    // largest channel difference over the whole image
+    pub max_channel_diff: u8,
+}
+
+impl ImageDiff {
+    pub fn ratio(&self) -> f32 {
+        if self.total_pixels == 0 {
+            return 0.0;
+        }
+        self.differing_pixels as f32 / self.total_pixels as f32
+    }
+}
+
+// Counts the pixels with a channel further than the tolerance from the golden one
+pub fn diff_images(actual: &image::RgbaImage, golden: &image::RgbaImage, tolerance: u8) -> Result<ImageDiff> {
+    ensure!(
+        actual.dimensions() == golden.dimensions(),
+        "The image is {}x{} but the golden is {}x{}",
+        actual.width(), actual.height(), golden.width(), golden.height()
+    );
+
+    let mut diff = ImageDiff { differing_pixels: 0, total_pixels: 0, max_channel_diff: 0 };
+    for (a, g) in actual.pixels().zip(golden.pixels()) {
+        let channel_diff = a.0.iter().zip(g.0.iter())
+            .map(|(a, g)| (*a as i16 - *g as i16).unsigned_abs() as u8)
+            .max()
+            .unwrap_or(0);
+        diff.total_pixels += 1;
+        diff.max_channel_diff = diff.max_channel_diff.max(channel_diff);
+        if channel_diff > tolerance {
+            diff.differing_pixels += 1;
+        }
+    }
+    Ok(diff)
+}
+
+// Compares the image with the golden at path, or replaces the golden when UPDATE_GOLDENS_VAR is set
+pub fn check_golden<P: AsRef<Path>>(path: P, actual: &image::RgbaImage, tolerance: u8, max_diff_ratio: f32) -> Result<()> {
+    let path = path.as_ref();
+    if matches!(std::env::var_os(UPDATE_GOLDENS_VAR), Some(value) if !value.is_empty()) {
+        actual.save(path).with_context(|| format!("Unable to write the golden {}", path.display()))?;
+        info!("Golden image updated: {}", path.display());
+        return Ok(());
+    }
+
+    let golden = image::open(path)
+        .with_context(|| format!("Unable to read the golden {}, set {} to create it", path.display(), UPDATE_GOLDENS_VAR))?
+        .to_rgba8();
+    let diff = diff_images(actual, &golden, tolerance)?;
+    ensure!(
+        diff.ratio() <= max_diff_ratio,
+        "{} of {} pixels differ from the golden {} (up to {} per channel)",
+        diff.differing_pixels, diff.total_pixels, path.display(), diff.max_channel_diff
+    );
+    Ok(())
+}
@@ -0,0 +1,14 @@
+// A mesh uploaded once to the GPU, drawn with its indices
+pub struct GpuMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub num_index: u32,
+    // filled by Mesh::upload
+    pub(crate) memory_bytes: u64,
+}
+
+impl GpuMesh {
+    pub fn memory_bytes(&self) -> u64 {
+        self.memory_bytes
+    }
+}
@@ -0,0 +1,75 @@
+use super::memory;
+
+// capacity given to a reallocated buffer, relative to its previous one
+const GROWTH_FACTOR: f64 = 1.5;
+
+// GPU buffer reused by the successive meshes of a chunk. It is only reallocated when the
+// data outgrows it, and then with some room so a run of growing edits rarely reallocates.
+pub struct GrowableBuffer {
+    pub buffer: wgpu::Buffer,
+    // allocated size in bytes, the data may take less
+    pub capacity: u64,
+    label: &'static str,
+    usage: wgpu::BufferUsage,
+}
+
+impl GrowableBuffer {
+    // the first allocation fits the data exactly
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, label: &'static str, usage: wgpu::BufferUsage, data: &[u8]) -> Self {
+        let capacity = memory::aligned_size(data.len());
+        let mut buffer = GrowableBuffer {
+            buffer: create_buffer(device, label, usage, capacity),
+            capacity,
+            label,
+            usage,
+        };
+        buffer.write(device, queue, data);
+        buffer
+    }
+
+    // uploads the data at the start of the buffer, returns whether it had to be reallocated
+    pub fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, data: &[u8]) -> bool {
+        let reallocated = if let Some(capacity) = reallocation(self.capacity, data.len()) {
+            self.capacity = capacity;
+            self.buffer = create_buffer(device, self.label, self.usage, capacity);
+            true
+        } else {
+            false
+        };
+
+        // the copies are made of whole words, the padding is never drawn
+        let size = memory::aligned_size(data.len());
+        if size == data.len() as u64 {
+            queue.write_buffer(&self.buffer, 0, data);
+        } else {
+            let mut padded = data.to_vec();
+            padded.resize(size as usize, 0);
+            queue.write_buffer(&self.buffer, 0, &padded);
+        }
+        reallocated
+    }
+}
+
+// the capacity to reallocate with when the data does not fit, None when it does
+pub fn reallocation(capacity: u64, needed: usize) -> Option<u64> {
+    if needed as u64 > capacity {
+        Some(grown_capacity(capacity, needed))
+    } else {
+        None
+    }
+}
+
+// at least GROWTH_FACTOR times the previous capacity, more if the data needs it
+pub fn grown_capacity(capacity: u64, needed: usize) -> u64 {
+    let grown = (capacity as f64 * GROWTH_FACTOR).ceil() as usize;
+    memory::aligned_size(grown.max(needed))
+}
+
+fn create_buffer(device: &wgpu::Device, label: &str, usage: wgpu::BufferUsage, capacity: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: capacity,
+        usage: usage | wgpu::BufferUsage::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -0,0 +1,53 @@
+use super::terrain::block::BlockType;
+use super::terrain::registry::BlockRegistry;
+
+// blocks of the engine that can be placed in build mode, in the order of the number keys
+pub const HOTBAR_BLOCKS: [BlockType; 6] = [
+    BlockType::STONE,
+    BlockType::DIRT,
+    BlockType::GRASS,
+    BlockType::WATER,
+    BlockType::LAMP,
+    BlockType::GLASS,
+];
+
+pub struct Hotbar {
+    blocks: Vec<BlockType>,
+    selected: usize,
+}
+
+impl Default for Hotbar {
+    fn default() -> Self {
+        Hotbar::new()
+    }
+}
+
+impl Hotbar {
+    pub fn new() -> Self {
+        Hotbar { blocks: HOTBAR_BLOCKS.to_vec(), selected: 0 }
+    }
+
+    // the blocks a file added to the registry come after the ones of the engine
+    pub fn with_registry(registry: &BlockRegistry) -> Self {
+        let mut hotbar = Hotbar::new();
+        hotbar.blocks.extend(registry.iter().map(|block| BlockType(block.id)).filter(|block_type| block_type.0 > BlockType::GLASS.0));
+        hotbar
+    }
+
+    pub fn selected(&self) -> BlockType {
+        self.blocks[self.selected]
+    }
+
+    // moves the selection by that many slots, wrapping around at both ends
+    pub fn scroll(&mut self, steps: i32) {
+        let len = self.blocks.len() as i32;
+        self.selected = (self.selected as i32 + steps).rem_euclid(len) as usize;
+    }
+
+    // slots out of the hotbar are ignored
+    pub fn select(&mut self, slot: usize) {
+        if slot < self.blocks.len() {
+            self.selected = slot;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use winit::event::*;
+use super::state::State;
+
+// bumped whenever the layout below changes, older files are refused
+pub const RECORDING_VERSION: u32 = 1;
+
+// The inputs the State reacts to, kept apart from the winit events which cannot all be
+// built back or saved
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Key { key: VirtualKeyCode, state: ElementState },
+    MouseMotion { dx: f64, dy: f64 },
+    MouseWheel { delta: MouseScrollDelta },
+    MouseButton { button: MouseButton, state: ElementState },
+}
+
+impl InputEvent {
+    pub fn from_device_event(event: &DeviceEvent) -> Option<Self> {
+        match event {
+            DeviceEvent::Key(KeyboardInput { virtual_keycode: Some(key), state, .. }) => {
+                Some(InputEvent::Key { key: *key, state: *state })
+            }
+            DeviceEvent::MouseMotion { delta } => Some(InputEvent::MouseMotion { dx: delta.0, dy: delta.1 }),
+            DeviceEvent::MouseWheel { delta } => Some(InputEvent::MouseWheel { delta: *delta }),
+            _ => None,
+        }
+    }
+
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        match event {
+            WindowEvent::MouseInput { button, state, .. } => Some(InputEvent::MouseButton { button: *button, state: *state }),
+            _ => None,
+        }
+    }
+
+    // gives the event to the State the way the event loop does
+    #[allow(deprecated)]
+    pub fn dispatch(&self, state: &mut State, window: &winit::window::Window) -> bool {
+        match *self {
+            InputEvent::Key { key, state: key_state } => state.device_input(window, &DeviceEvent::Key(KeyboardInput {
+                scancode: 0,
+                state: key_state,
+                virtual_keycode: Some(key),
+                modifiers: ModifiersState::empty(),
+            })),
+            InputEvent::MouseMotion { dx, dy } => state.device_input(window, &DeviceEvent::MouseMotion { delta: (dx, dy) }),
+            InputEvent::MouseWheel { delta } => state.device_input(window, &DeviceEvent::MouseWheel { delta }),
+            InputEvent::MouseButton { button, state: button_state } => state.window_input(window, &WindowEvent::MouseInput {
+                // the State does not look at the device
+                device_id: unsafe { DeviceId::dummy() },
+                state: button_state,
+                button,
+                modifiers: ModifiersState::empty(),
+            }),
+        }
+    }
+}
+
+// an input and the simulation time it came at, since the start of the recording
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub time: Duration,
+    pub event: InputEvent,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub version: u32,
+    pub inputs: Vec<RecordedInput>,
+}
+
+// Keeps the inputs of a run, the time is the one given to the updates so a replay with
+// the same time steps gets the same frames
+#[derive(Default)]
+pub struct InputRecorder {
+    pub inputs: Vec<RecordedInput>,
+}
+
+impl InputRecorder {
+    pub fn new() -> Self {
+        InputRecorder::default()
+    }
+
+    pub fn record(&mut self, time: Duration, event: InputEvent) {
+        self.inputs.push(RecordedInput { time, event });
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let recording = Recording { version: RECORDING_VERSION, inputs: self.inputs.clone() };
+        let json = serde_json::to_string(&recording)?;
+        fs::write(path, json).with_context(|| format!("Unable to write the recording to {}", path.display()))
+    }
+}
+
+// Hands the recorded inputs back once the simulation time reaches them
+pub struct InputPlayer {
+    inputs: Vec<RecordedInput>,
+    next: usize,
+}
+
+impl InputPlayer {
+    // the inputs are sorted by time, the ones at the same time keep their order
+    pub fn new(mut inputs: Vec<RecordedInput>) -> Self {
+        inputs.sort_by_key(|input| input.time);
+        InputPlayer { inputs, next: 0 }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read the recording from {}", path.display()))?;
+        let recording: Recording = serde_json::from_str(&json)
+            .with_context(|| format!("Invalid recording file {}", path.display()))?;
+        if recording.version != RECORDING_VERSION {
+            bail!("Recording version {} is not supported, expected {}", recording.version, RECORDING_VERSION);
+        }
+        Ok(InputPlayer::new(recording.inputs))
+    }
+
+    // the inputs recorded up to time which were not given yet
+    pub fn due(&mut self, time: Duration) -> &[RecordedInput] {
+        let start = self.next;
+        while self.next < self.inputs.len() && self.inputs[self.next].time <= time {
+            self.next += 1;
+        }
+        &self.inputs[start..self.next]
+    }
+}
@@ -0,0 +1,57 @@
+use super::{light, material, texture, uniform};
+
+// The bind groups a pipeline can read, each one has its layout in BindGroupLayouts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindGroupSlot {
+    Uniforms,
+    Light,
+    Atlas,
+    Materials,
+}
+
+// Groups of the pipelines drawing the blocks, the opaque, water and glass ones
+pub const BLOCK_PIPELINE_GROUPS: [BindGroupSlot; 3] = [BindGroupSlot::Uniforms, BindGroupSlot::Atlas, BindGroupSlot::Materials];
+
+// Groups of the light marker pipeline, in the order of its sets in light.vert. The same
+// list builds the pipeline layout and sets the bind groups before the draw.
+pub const LIGHT_PIPELINE_GROUPS: [BindGroupSlot; 2] = [BindGroupSlot::Uniforms, BindGroupSlot::Light];
+
+// Bind group layouts shared by the pipelines and their bind groups. Each one is created
+// once, the pipelines using the same groups then take the same layout. The type of the
+// layouts is only left open for the tests, which cannot create a device.
+pub struct BindGroupLayouts<L = wgpu::BindGroupLayout> {
+    // camera uniforms, always at group 0
+    pub uniform: L,
+    pub light: L,
+    // texture and sampler, for the block atlas
+    pub diffuse: L,
+    // properties of the block types, for the pipelines drawing them
+    pub material: L,
+}
+
+impl BindGroupLayouts {
+    pub fn new(device: &wgpu::Device) -> Self {
+        BindGroupLayouts {
+            uniform: uniform::create_bind_group_layout(device),
+            light: light::create_bind_group_layout(device),
+            diffuse: texture::create_diffuse_bind_group_layout(device),
+            material: material::create_bind_group_layout(device),
+        }
+    }
+}
+
+impl<L> BindGroupLayouts<L> {
+    pub fn get(&self, slot: BindGroupSlot) -> &L {
+        match slot {
+            BindGroupSlot::Uniforms => &self.uniform,
+            BindGroupSlot::Light => &self.light,
+            BindGroupSlot::Atlas => &self.diffuse,
+            BindGroupSlot::Materials => &self.material,
+        }
+    }
+
+    // the layouts of the groups in that order, for a pipeline layout
+    pub fn for_groups(&self, groups: &[BindGroupSlot]) -> Vec<&L> {
+        groups.iter().map(|&slot| self.get(slot)).collect()
+    }
+}
@@ -1,3 +1,15 @@
+use std::time::Duration;
+use cgmath::*;
+use super::camera::UpAxis;
+
+// the marker cube is smaller than the blocks
+pub const MARKER_SCALE: f32 = 0.25;
+// the orbit starts at [2, 2, 2], around the origin
+pub const DEFAULT_ORBIT_CENTER: [f32; 3] = [0.0, 0.0, 0.0];
+pub const DEFAULT_ORBIT_RADIUS: f32 = 3.464_101_6;
+// the light goes around the terrain at this speed, at a time scale of 1
+pub const ROTATION_SPEED: Deg<f32> = Deg(60.0);
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Light {
@@ -5,6 +17,71 @@ pub struct Light {
     // Due to uniforms requiring 16 byte (4 float) spacing, we need to use a padding field here
     pub _padding: u32,
     pub color: [f32; 3],
+    pub _padding2: u32,
+    // places the marker cube, follows the position
+    pub model: [[f32; 4]; 4],
+}
+
+impl Light {
+    pub fn new(position: [f32; 3], color: [f32; 3]) -> Self {
+        Light {
+            position,
+            _padding: 0,
+            color,
+            _padding2: 0,
+            model: marker_model(position).into(),
+        }
+    }
+
+    // the marker moves with the light, its mesh is never rebuilt
+    pub fn set_position(&mut self, position: [f32; 3]) {
+        self.position = position;
+        self.model = marker_model(position).into();
+    }
+}
+
+// Circle of the light around the up axis going through the center. The angle is
+// kept instead of rotating the position again at each update, the rounding errors would
+// then change the radius a bit more with every frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightOrbit {
+    pub center: Point3<f32>,
+    pub radius: f32,
+    pub up_axis: UpAxis,
+    angle: Rad<f32>,
+}
+
+impl Default for LightOrbit {
+    fn default() -> Self {
+        LightOrbit::new(DEFAULT_ORBIT_CENTER.into(), DEFAULT_ORBIT_RADIUS)
+    }
+}
+
+impl LightOrbit {
+    pub fn new(center: Point3<f32>, radius: f32) -> Self {
+        LightOrbit { center, radius, up_axis: UpAxis::Y, angle: Rad(0.0) }
+    }
+
+    pub fn advance<A: Into<Rad<f32>>>(&mut self, angle: A) {
+        self.angle = (self.angle + angle.into()).normalize();
+    }
+
+    // goes around at ROTATION_SPEED for that animation time
+    pub fn update(&mut self, dt: Duration) {
+        self.advance(ROTATION_SPEED * dt.as_secs_f32());
+    }
+
+    // the light stays as high above the center as it is away from it on the two other axes
+    pub fn position(&self) -> Point3<f32> {
+        let axis = self.up_axis;
+        let start = (axis.east() + axis.north() + axis.up()).normalize() * self.radius;
+        self.center + Quaternion::from_axis_angle(axis.up(), self.angle).rotate_vector(start)
+    }
+}
+
+// the unit cube of the marker scaled down then moved to the light
+pub fn marker_model(position: [f32; 3]) -> Matrix4<f32> {
+    Matrix4::from_translation(position.into()) * Matrix4::from_scale(MARKER_SCALE)
 }
 
 pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
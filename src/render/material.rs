@@ -0,0 +1,104 @@
+use super::terrain::{atlas, block::BlockType};
+use super::terrain::registry::BlockRegistry;
+
+// Properties of the block types read by simple.frag, the vertices give the index of
+// theirs so every block of a chunk is still drawn by the same call. They are taken from
+// the definitions of the registry, the air is the first one and leaves the color of the
+// untextured meshes as it is.
+pub const MAX_MATERIALS: usize = 16;
+// the meshes that are not made of blocks
+pub const PLAIN_MATERIAL: u32 = BlockType::AIR.0;
+
+// Same layout as the Material struct of the shader, std140 rounds the elements of an
+// array to 16 bytes so the specular terms and the tiles take a whole vec4 each
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BlockMaterial {
+    // multiplies the vertex color, the alpha is unused
+    pub base_color: [f32; 4],
+    // strength of the highlight, 0 for a matte block
+    pub specular: f32,
+    // the higher the smaller the highlight
+    pub shininess: f32,
+    // 1 for the blocks the sky light does not tint
+    pub emissive: f32,
+    pub _padding: f32,
+    // atlas tiles of the top, bottom and side faces, the shader picks one from the normal
+    pub tiles: [u32; 4],
+}
+
+impl BlockMaterial {
+    pub const fn new(specular: f32, shininess: f32) -> Self {
+        BlockMaterial {
+            base_color: [1.0; 4],
+            specular,
+            shininess,
+            emissive: 0.0,
+            _padding: 0.0,
+            tiles: [atlas::PLAIN_TILE; 4],
+        }
+    }
+}
+
+pub fn material_id(block_type: BlockType) -> u32 {
+    block_type.0
+}
+
+// The uniform array of the materials, the unused slots stay zeroed
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Materials {
+    pub materials: [BlockMaterial; MAX_MATERIALS],
+}
+
+impl Default for Materials {
+    fn default() -> Self {
+        Materials::new()
+    }
+}
+
+impl Materials {
+    // the materials of the builtin blocks
+    pub fn new() -> Self {
+        Materials::from_registry(&BlockRegistry::builtin())
+    }
+
+    pub fn from_registry(registry: &BlockRegistry) -> Self {
+        let mut materials = [BlockMaterial::new(0.0, 1.0); MAX_MATERIALS];
+        for block in registry.iter() {
+            let textures = block.textures();
+            let material = &mut materials[block.id as usize];
+            *material = BlockMaterial::new(block.specular, block.shininess);
+            material.emissive = if block.emissive { 1.0 } else { 0.0 };
+            material.tiles = [textures.top, textures.bottom, textures.side, 0];
+        }
+        Materials { materials }
+    }
+}
+
+pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStage::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+        label: Some("material_bind_group_layout"),
+    })
+}
+
+pub fn create_bind_group(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, buffer: &wgpu::Buffer) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: buffer.as_entire_binding(),
+        }],
+        label: Some("material_bind_group"),
+    })
+}
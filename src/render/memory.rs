@@ -0,0 +1,34 @@
+// Sizes of the GPU allocations, as requested from the device
+
+// create_buffer_init pads the contents to the next COPY_BUFFER_ALIGNMENT, even when they are aligned
+pub fn init_buffer_size(contents_len: usize) -> u64 {
+    let unpadded = contents_len as u64;
+    unpadded + wgpu::COPY_BUFFER_ALIGNMENT - unpadded % wgpu::COPY_BUFFER_ALIGNMENT
+}
+
+// rounded up to COPY_BUFFER_ALIGNMENT, empty buffers still take one word
+pub fn aligned_size(len: usize) -> u64 {
+    let mask = wgpu::COPY_BUFFER_ALIGNMENT - 1;
+    ((len as u64 + mask) & !mask).max(wgpu::COPY_BUFFER_ALIGNMENT)
+}
+
+// every mip level is a quarter of the previous one, rounded down to at least 1x1
+pub fn texture_size(width: u32, height: u32, mip_level_count: u32, format: wgpu::TextureFormat) -> u64 {
+    (0..mip_level_count)
+        .map(|level| (width >> level).max(1) as u64 * (height >> level).max(1) as u64)
+        .sum::<u64>() * bytes_per_texel(format)
+}
+
+fn bytes_per_texel(format: wgpu::TextureFormat) -> u64 {
+    match format {
+        wgpu::TextureFormat::Rgba16Float => 8,
+        wgpu::TextureFormat::Rgba32Float => 16,
+        // the color and depth formats used by the engine
+        _ => 4,
+    }
+}
+
+// megabytes with a decimal, for the debug overlay
+pub fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
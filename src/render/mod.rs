@@ -1,57 +1,113 @@
 pub mod window;
+pub mod config;
 pub mod camera;
 pub mod state;
 pub mod vertex;
 pub mod texture;
 pub mod uniform;
+pub mod uniform_ring;
 pub mod instance;
 pub mod light;
+pub mod material;
+pub mod block_tint;
 pub mod terrain;
+pub mod session;
+pub mod stats;
+pub mod text;
+pub mod benchmark;
+pub mod hotbar;
+pub mod shader;
+pub mod chunk_buffers;
+pub mod growable_buffer;
+pub mod gpu_mesh;
+pub mod memory;
+pub mod settings;
+pub mod command;
+pub mod input_replay;
+pub mod scissor;
+pub mod tonemap;
+pub mod layouts;
+pub mod debug_labels;
+pub mod math;
+// image comparisons for the rendering tests
+pub mod golden;
 
 use futures::executor::block_on;
-use winit::{event::*, event_loop::{ControlFlow, EventLoop}, window::WindowBuilder};
+use log::{info, error, warn};
+use winit::{event::*, event_loop::{ControlFlow, EventLoop}};
 use state::State;
+use config::Config;
 
 pub fn run() {
+    // verbosity can be changed with RUST_LOG, the engine logs at info level by default
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("rover_engine=info")).init();
+
+    let config = match Config::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
     let title = env!("CARGO_PKG_NAME");
     let event_loop = EventLoop::new();
-    let window = WindowBuilder::new()
-        .with_title(title)
-        .build(&event_loop)
-        .unwrap();
+    let window = config.window.build(title, &event_loop).unwrap();
 
-    let mut state = block_on(State::new(&window));
+    let mut state = block_on(State::new(&window, &config));
+    let max_frame_time = stats::max_frame_time(config.max_frame_time);
     let mut last_render_time = std::time::Instant::now();
+    let mut frame_limit = config.frames.map(stats::FrameLimit::new);
+    // time given to the updates so far, the recorded inputs are stamped with it
+    let mut simulation_time = std::time::Duration::from_secs(0);
+    let mut recorder = config.record.as_ref().map(|_| input_replay::InputRecorder::new());
+    let mut player = match &config.replay {
+        Some(path) => match input_replay::InputPlayer::load(path) {
+            Ok(player) => Some(player),
+            Err(e) => {
+                error!("{:?}", e);
+                std::process::exit(2);
+            }
+        },
+        None => None,
+    };
     
     event_loop.run(move |event, _, control_flow|  {
         *control_flow = ControlFlow::Poll;
+        // the panel sees every event, it decides which ones the camera gets
+        state.settings.handle_event(&event);
         match event {
             // device events
             Event::DeviceEvent {
                 ref event,
                 .. // We're not using device_id currently
-            } => {
+            // the devices are ignored during a replay, they would change the result
+            } if player.is_none() => {
+                if let (Some(recorder), Some(input)) = (&mut recorder, input_replay::InputEvent::from_device_event(event)) {
+                    recorder.record(simulation_time, input);
+                }
                 state.device_input(&window, event);
             },
             // window events
             Event::WindowEvent {
                 ref event,
                 window_id,
-            } if window_id == window.id() => if !state.window_input(&window, event) { 
+            } if window_id == window.id() => if !window_input(&mut state, &window, event, simulation_time, &mut recorder, player.is_some()) { 
                 match event {
-                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                    WindowEvent::KeyboardInput { input, .. } => match input {
-                        KeyboardInput {
+                    _ if window::requests_exit(event) => *control_flow = ControlFlow::Exit,
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
                             state: ElementState::Pressed,
-                            virtual_keycode: Some(VirtualKeyCode::Escape),
+                            virtual_keycode: Some(VirtualKeyCode::F11),
                             ..
-                        } => *control_flow = ControlFlow::Exit,
-                        _ => {}
-                    },
+                        },
+                        ..
+                    } => window::toggle_fullscreen(&window),
                     WindowEvent::Resized(physical_size) => {
                         state.resize(*physical_size);
                     },
-                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                    WindowEvent::ScaleFactorChanged { scale_factor, new_inner_size } => {
+                        state.scale_factor = *scale_factor;
                         // new_inner_size is &&mut so we have to dereference it twice
                         state.resize(**new_inner_size);
                     },
@@ -65,16 +121,43 @@ pub fn run() {
                 let dt = now - last_render_time;
                 last_render_time = now;
 
+                if let Some(player) = &mut player {
+                    for input in player.due(simulation_time) {
+                        input.event.dispatch(&mut state, &window);
+                    }
+                }
+
                 // on each new frame we update the system
+                state.record_frame(dt);
+                let dt = stats::clamp_frame_time(dt, max_frame_time);
                 state.update(dt);
+                simulation_time += dt;
+                if state.benchmark_finished() {
+                    info!("{}", benchmark::summary(&state.frame_stats));
+                    let draw_stats = state.draw_stats();
+                    let (terrain, total) = (draw_stats.terrain, draw_stats.total());
+                    info!("Last frame: {} draw calls, {} vertices, {} triangles in total, {} draw calls, {} vertices, {} triangles for the terrain",
+                        total.draw_calls, total.vertices, total.triangles, terrain.draw_calls, terrain.vertices, terrain.triangles);
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
                 match state.render() {
-                    Ok(_) => {}
+                    // only the frames that reached the screen are counted
+                    Ok(_) => if let Some(limit) = &mut frame_limit {
+                        if limit.record() {
+                            info!("{} frames drawn, exiting", limit.drawn);
+                            *control_flow = ControlFlow::Exit;
+                        }
+                    },
                     // Recreate the swap_chain if lost
                     Err(wgpu::SwapChainError::Lost) => state.resize(state.size),
                     // The system is out of memory, we should probably quit
-                    Err(wgpu::SwapChainError::OutOfMemory) => *control_flow = ControlFlow::Exit,
+                    Err(wgpu::SwapChainError::OutOfMemory) => {
+                        error!("The swap chain is out of memory, exiting");
+                        *control_flow = ControlFlow::Exit
+                    },
                     // All other errors (Outdated, Timeout) should be resolved by the next frame
-                    Err(e) => eprintln!("{:?}", e),
+                    Err(e) => warn!("{:?}", e),
                 }
             },
             // set redraw
@@ -82,7 +165,40 @@ pub fn run() {
                 // RedrawRequested will only trigger once, unless we manually request it.
                 window.request_redraw();
             },
+            // last event, sent once whatever asked for the exit
+            Event::LoopDestroyed => {
+                if let (Some(recorder), Some(path)) = (&recorder, &config.record) {
+                    match recorder.save(path) {
+                        Ok(_) => info!("{} inputs recorded to {}", recorder.inputs.len(), path),
+                        Err(e) => error!("{:?}", e),
+                    }
+                }
+                state.on_exit();
+            }
             _ => {}
         }
     });
-}
\ No newline at end of file
+}
+
+// The mouse buttons go through the recorder like the device events, the other window
+// events are left to the event loop
+fn window_input(
+    state: &mut State,
+    window: &winit::window::Window,
+    event: &WindowEvent,
+    time: std::time::Duration,
+    recorder: &mut Option<input_replay::InputRecorder>,
+    replaying: bool,
+) -> bool {
+    let input = input_replay::InputEvent::from_window_event(event);
+    match input {
+        Some(_) if replaying => true,
+        Some(input) => {
+            if let Some(recorder) = recorder {
+                recorder.record(time, input);
+            }
+            state.window_input(window, event)
+        }
+        None => state.window_input(window, event),
+    }
+}
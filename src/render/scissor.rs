@@ -0,0 +1,48 @@
+// Rectangle of pixels the draws of a pass are clipped to, the origin is the top left
+// corner of the surface. It lets the overlays be drawn without touching the rest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScissorRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ScissorRect {
+    // a region of that size in a corner of the surface, margin pixels away from its edges
+    pub fn corner(corner: Corner, size: [u32; 2], margin: u32, surface: [u32; 2]) -> Self {
+        let right = surface[0].saturating_sub(size[0] + margin);
+        let bottom = surface[1].saturating_sub(size[1] + margin);
+        let (x, y) = match corner {
+            Corner::TopLeft => (margin, margin),
+            Corner::TopRight => (right, margin),
+            Corner::BottomLeft => (margin, bottom),
+            Corner::BottomRight => (right, bottom),
+        };
+        ScissorRect { x, y, width: size[0], height: size[1] }.clamped(surface)
+    }
+
+    // wgpu refuses the rectangles going past the surface
+    pub fn clamped(&self, surface: [u32; 2]) -> Self {
+        let x = self.x.min(surface[0]);
+        let y = self.y.min(surface[1]);
+        ScissorRect {
+            x,
+            y,
+            width: self.width.min(surface[0] - x),
+            height: self.height.min(surface[1] - y),
+        }
+    }
+
+    pub fn apply(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_scissor_rect(self.x, self.y, self.width, self.height);
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use super::camera::Camera;
+use super::terrain::block::BlockType;
+use super::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+use super::terrain::registry::BlockRegistry;
+
+// bumped whenever the layout below changes, older files are refused
+pub const SESSION_VERSION: u32 = 3;
+pub const DEFAULT_SESSION_PATH: &str = "session.json";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockEdit {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+    pub block_type: BlockType,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct CameraPose {
+    pub position: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+// Everything needed to resume: the world is regenerated from its config, then the edits are replayed
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    pub terrain: TerrainConfig,
+    pub edits: Vec<BlockEdit>,
+    pub camera: CameraPose,
+}
+
+impl Session {
+    pub fn capture(chunk: &Chunk, camera: &Camera) -> Self {
+        let generated = Chunk::generate(chunk.terrain);
+        let mut edits = vec![];
+        for x in 0..CHUNK_WIDTH {
+            for y in 0..CHUNK_HEIGHT {
+                for z in 0..CHUNK_WIDTH {
+                    let block_type = chunk.blocks[x][y][z].block_type;
+                    if block_type != generated.blocks[x][y][z].block_type {
+                        edits.push(BlockEdit { x, y, z, block_type });
+                    }
+                }
+            }
+        }
+
+        Session {
+            version: SESSION_VERSION,
+            terrain: chunk.terrain,
+            edits,
+            camera: CameraPose {
+                position: camera.position.into(),
+                yaw: camera.yaw.0,
+                pitch: camera.pitch.0,
+            },
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).with_context(|| format!("Unable to write the session to {}", path.display()))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read the session from {}", path.display()))?;
+        let session: Session = serde_json::from_str(&json)
+            .with_context(|| format!("Invalid session file {}", path.display()))?;
+        ensure!(
+            session.version == SESSION_VERSION,
+            "Unsupported session version {} (expected {})", session.version, SESSION_VERSION
+        );
+        for edit in session.edits.iter() {
+            ensure!(
+                edit.x < CHUNK_WIDTH && edit.y < CHUNK_HEIGHT && edit.z < CHUNK_WIDTH,
+                "Block edit out of the chunk: ({}, {}, {})", edit.x, edit.y, edit.z
+            );
+        }
+        Ok(session)
+    }
+
+    // the edits are ids of the registry the session was saved with, they must all be in this one
+    pub fn check_blocks(&self, registry: &BlockRegistry) -> Result<()> {
+        for edit in self.edits.iter() {
+            ensure!(registry.contains(edit.block_type), "Unknown block {} in a block edit", edit.block_type.0);
+        }
+        Ok(())
+    }
+
+    // rebuilds the world and the camera as they were when captured
+    pub fn restore(&self) -> (Chunk, Camera) {
+        let mut chunk = Chunk::generate(self.terrain);
+        for edit in self.edits.iter() {
+            chunk.set_block(edit.x, edit.y, edit.z, edit.block_type);
+        }
+        let camera = Camera::new(
+            self.camera.position,
+            cgmath::Rad(self.camera.yaw),
+            cgmath::Rad(self.camera.pitch),
+        );
+
+        (chunk, camera)
+    }
+}
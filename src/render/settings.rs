@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::time::Duration;
+use egui_wgpu_backend::{RenderPass, ScreenDescriptor};
+use egui_winit_platform::{Platform, PlatformDescriptor};
+use winit::event::{DeviceEvent, Event};
+use super::camera::projection::{MAX_FOV, MIN_FOV};
+use super::terrain::{chunk::CHUNK_HEIGHT, chunk_manager::MAX_VIEW_RADIUS};
+use super::tonemap::{MAX_EXPOSURE, MIN_EXPOSURE};
+
+// The settings panel is an egui window drawn over the finished frame. While the pointer
+// is over it, or while one of its fields is typed in, the camera gets none of that input.
+pub const PANEL_WIDTH: f32 = 220.0;
+// from the top right corner of the window, in logical pixels
+pub const PANEL_MARGIN: f32 = 16.0;
+
+// What the panel edits, the state applies the values that changed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    // in degrees
+    pub fov: f32,
+    pub light_color: [f32; 3],
+    pub render_distance: i32,
+    pub seed: u32,
+    pub sea_level: usize,
+    pub fog: bool,
+    pub wireframe: bool,
+    pub ao_strength: f32,
+    // None without HDR
+    pub exposure: Option<f32>,
+}
+
+pub struct SettingsPanel {
+    pub open: bool,
+    platform: Platform,
+    // seconds since the panel was created, for the animations of egui
+    time: f64,
+}
+
+impl SettingsPanel {
+    pub fn new(size: winit::dpi::PhysicalSize<u32>, scale_factor: f64) -> Self {
+        let platform = Platform::new(PlatformDescriptor {
+            physical_width: size.width,
+            physical_height: size.height,
+            scale_factor,
+            font_definitions: egui::FontDefinitions::default(),
+            style: egui::Style::default(),
+        });
+        SettingsPanel { open: false, platform, time: 0.0 }
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    // the window events, egui keeps track of the pointer even while the panel is closed
+    pub fn handle_event<T>(&mut self, event: &Event<T>) {
+        self.platform.handle_event(event);
+    }
+
+    // the input the panel keeps from the camera, F1 still has to close it
+    pub fn captures(&self, event: &DeviceEvent) -> bool {
+        match event {
+            DeviceEvent::Key(_) => self.open && self.platform.context().wants_keyboard_input(),
+            DeviceEvent::MouseWheel { .. } | DeviceEvent::MouseMotion { .. } | DeviceEvent::Button { .. } => self.wants_pointer(),
+            _ => false,
+        }
+    }
+
+    // the clicks on the panel do not grab the cursor
+    pub fn wants_pointer(&self) -> bool {
+        self.open && self.platform.context().wants_pointer_input()
+    }
+
+    // Lays the panel out for this frame from the input received since the last one, the
+    // widgets the user touched change the settings. Gives the meshes to draw.
+    pub fn run(&mut self, dt: Duration, settings: &mut Settings) -> Vec<egui::ClippedMesh> {
+        self.time += dt.as_secs_f64();
+        self.platform.update_time(self.time);
+        self.platform.begin_frame();
+        let context = self.platform.context();
+        let screen = context.input().screen_rect();
+        egui::Window::new("Settings")
+            .default_pos(egui::pos2(screen.right() - PANEL_WIDTH - PANEL_MARGIN, PANEL_MARGIN))
+            .default_width(PANEL_WIDTH)
+            .resizable(false)
+            .show(&context, |ui| {
+                ui.add(egui::Slider::new(&mut settings.fov, MIN_FOV..=MAX_FOV).text("FOV"));
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgb(&mut settings.light_color);
+                    ui.label("Light color");
+                });
+                ui.add(egui::Slider::new(&mut settings.render_distance, 1..=MAX_VIEW_RADIUS).text("Render distance"));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut settings.seed));
+                    ui.label("Seed");
+                });
+                ui.add(egui::Slider::new(&mut settings.sea_level, 0..=CHUNK_HEIGHT).text("Sea level"));
+                ui.add(egui::Slider::new(&mut settings.ao_strength, 0.0..=1.0).text("Ambient occlusion"));
+                if let Some(exposure) = &mut settings.exposure {
+                    ui.add(egui::Slider::new(exposure, MIN_EXPOSURE..=MAX_EXPOSURE).text("Exposure"));
+                }
+                ui.checkbox(&mut settings.fog, "Fog");
+                ui.checkbox(&mut settings.wireframe, "Wireframe");
+            });
+        let (_, shapes) = self.platform.end_frame();
+        context.tessellate(shapes)
+    }
+
+    // the font atlas of egui, uploaded again by the renderer when its version changes
+    pub fn texture(&self) -> Arc<egui::Texture> {
+        self.platform.context().texture()
+    }
+}
+
+// Draws the meshes of the last panel frame, after the tone mapping and the text overlay so
+// nothing of the scene goes over them
+pub struct PanelRenderer {
+    render_pass: RenderPass,
+    paint_jobs: Vec<egui::ClippedMesh>,
+}
+
+impl PanelRenderer {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        PanelRenderer { render_pass: RenderPass::new(device, format), paint_jobs: vec![] }
+    }
+
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, texture: &egui::Texture, paint_jobs: Vec<egui::ClippedMesh>, screen: &ScreenDescriptor) {
+        self.render_pass.update_texture(device, queue, texture);
+        self.render_pass.update_buffers(device, queue, &paint_jobs, screen);
+        self.paint_jobs = paint_jobs;
+    }
+
+    // nothing is drawn before the first prepare
+    pub fn draw(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, screen: &ScreenDescriptor) {
+        if !self.paint_jobs.is_empty() {
+            self.render_pass.execute(encoder, view, &self.paint_jobs, screen, None);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::{ensure, Context, Result};
+use log::{info, error};
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+
+// directory holding .spv files used instead of the shaders built in the engine
+pub const SHADER_DIR_VAR: &str = "ROVER_SHADER_DIR";
+const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+
+// SPIR-V shader read at runtime
+pub struct ShaderFile {
+    label: String,
+    words: Vec<u32>,
+}
+
+impl ShaderFile {
+    // the pipeline only appears in the error messages
+    pub fn load<P: AsRef<Path>>(path: P, pipeline: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .with_context(|| format!("Unable to read the shader {} of the {}", path.display(), pipeline))?;
+        ensure!(
+            !bytes.is_empty() && bytes.len() % 4 == 0,
+            "The shader {} of the {} is not SPIR-V: its size is not a whole number of words", path.display(), pipeline
+        );
+        let words: Vec<u32> = bytes.chunks_exact(4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        ensure!(
+            words[0] == SPIRV_MAGIC_NUMBER,
+            "The shader {} of the {} is not SPIR-V: wrong magic number", path.display(), pipeline
+        );
+
+        Ok(ShaderFile {
+            label: path.display().to_string(),
+            words,
+        })
+    }
+
+    pub fn descriptor(&self) -> wgpu::ShaderModuleDescriptor<'_> {
+        wgpu::ShaderModuleDescriptor {
+            label: Some(&self.label),
+            source: wgpu::ShaderSource::SpirV(Cow::Borrowed(&self.words)),
+            flags: wgpu::ShaderFlags::VALIDATION,
+        }
+    }
+}
+
+// Creates the embedded shader, or the file of the same name in SHADER_DIR_VAR when it is
+// set. A file that cannot be used is reported and the embedded shader is kept.
+pub fn create_module(device: &wgpu::Device, pipeline: &str, embedded: wgpu::ShaderModuleDescriptor) -> wgpu::ShaderModule {
+    if let Some(dir) = std::env::var_os(SHADER_DIR_VAR) {
+        // include_spirv! labels the shader with its path
+        if let Some(name) = embedded.label.and_then(|label| Path::new(label).file_name()) {
+            match ShaderFile::load(Path::new(&dir).join(name), pipeline) {
+                Ok(file) => {
+                    info!("Using {} for the {}", file.label, pipeline);
+                    return device.create_shader_module(&file.descriptor());
+                }
+                Err(e) => error!("{:?}", e),
+            }
+        }
+    }
+    device.create_shader_module(&embedded)
+}
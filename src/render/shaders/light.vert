@@ -16,14 +16,11 @@ layout(set=1, binding=0)
 uniform Light {
     vec3 u_position;
     vec3 u_color;
+    mat4 u_model;
 };
 
-// Let's keep our light smaller than our other objects
-float scale = 0.25;
-
 void main() {
-    vec3 v_position = a_position * scale + u_position;
-    gl_Position = u_view_proj * vec4(v_position, 1);
+    gl_Position = u_view_proj * u_model * vec4(a_position, 1);
 
     v_color = u_color;
 }
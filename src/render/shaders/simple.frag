@@ -1,8 +1,85 @@
 #version 450
 
 layout(location=0) in vec3 v_color;
+layout(location=1) in vec3 v_normal;
+layout(location=2) in vec3 v_position;
+layout(location=3) in vec2 v_tex_coords;
+layout(location=4) in float v_ao;
+layout(location=5) flat in uint v_material_id;
 layout(location=0) out vec4 f_color;
 
+// camera
+layout(set=0, binding=0) 
+uniform Uniforms {
+    vec4 u_view_position;
+    mat4 u_view_proj; // unused
+    float u_time; // unused
+    float u_fog;
+    float u_ao_strength;
+    vec4 u_ambient_color;
+};
+
+// block atlas, darkens the colors into some details
+layout(set=1, binding=0) uniform texture2D t_atlas;
+layout(set=1, binding=1) uniform sampler s_atlas;
+
+// same layout as material::BlockMaterial
+struct Material {
+    vec4 base_color;
+    float specular;
+    float shininess;
+    float emissive;
+    uvec4 tiles; // top, bottom, side
+};
+// material::MAX_MATERIALS, indexed by the block ids of the registry
+layout(set=2, binding=0)
+uniform Materials {
+    Material u_materials[16];
+};
+
+// part of the color coming from the sky
+const float AMBIENT_STRENGTH = 0.15;
+// distances between which the terrain fades into the sky
+const float FOG_START = 30.0;
+const float FOG_END = 80.0;
+// the highlights come from above, like the brightness of the faces
+const vec3 LIGHT_DIRECTION = vec3(0.0, 1.0, 0.0);
+// atlas::TILE_SIZE and atlas::TILE_COUNT
+const float TILE_SIZE = 16.0;
+const float TILE_COUNT = 10.0;
+
+// same as atlas::tile_tex_coords. The mip level is chosen from the unwrapped coords so
+// it does not jump at the border of the blocks, where the wrapped ones start over.
+vec4 sample_tile(uint tile, vec2 tex_coords) {
+    float inset = 0.5 / TILE_SIZE;
+    vec2 uv = inset + fract(tex_coords) * (1.0 - 2.0 * inset);
+    vec2 atlas_uv = vec2((float(tile) + uv.x) / TILE_COUNT, uv.y);
+    vec2 scale = vec2(1.0 / TILE_COUNT, 1.0);
+    return textureGrad(sampler2D(t_atlas, s_atlas), atlas_uv, dFdx(tex_coords) * scale, dFdy(tex_coords) * scale);
+}
+
 void main() {
-    f_color = vec4(v_color, 1.0);
-}
\ No newline at end of file
+    Material material = u_materials[v_material_id];
+    // the faces are axis aligned, the meshes without normals take the side tile
+    uint tile = v_normal.y > 0.5 ? material.tiles.x : (v_normal.y < -0.5 ? material.tiles.y : material.tiles.z);
+    vec4 texel = sample_tile(tile, v_tex_coords);
+    float detail = texel.r;
+    // the sky does not tint the emissive blocks
+    float emissive = material.emissive;
+    // same as lighting::ao_shade, 0 leaves the color as it is
+    float ao = mix(1.0, v_ao, u_ao_strength);
+    vec3 base = v_color * material.base_color.rgb * detail;
+    vec3 lit = base * ao * (vec3(1.0 - AMBIENT_STRENGTH) + u_ambient_color.rgb * AMBIENT_STRENGTH);
+
+    // blinn-phong highlight, the meshes without normals get none
+    vec3 view_direction = normalize(u_view_position.xyz - v_position);
+    vec3 halfway = normalize(LIGHT_DIRECTION + view_direction);
+    float facing = length(v_normal) > 0.0 ? max(dot(normalize(v_normal), halfway), 0.0) : 0.0;
+    lit += vec3(material.specular * pow(facing, material.shininess)) * ao;
+
+    vec3 color = mix(lit, base, emissive);
+
+    float distance = length(v_position - u_view_position.xyz);
+    float fog = clamp((distance - FOG_START) / (FOG_END - FOG_START), 0.0, 1.0) * u_fog;
+    f_color = vec4(mix(color, u_ambient_color.rgb, fog), 1.0);
+}
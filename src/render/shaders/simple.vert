@@ -2,8 +2,19 @@
 
 layout(location=0) in vec3 a_position;
 layout(location=1) in vec3 a_color;
+layout(location=2) in vec3 a_normal;
+layout(location=3) in vec2 a_tex_coords;
+layout(location=4) in float a_ao;
+layout(location=5) in uint a_material_id;
+// from the second vertex buffer, see block_tint.rs
+layout(location=6) in vec3 a_tint;
 
 layout(location=0) out vec3 v_color;
+layout(location=1) out vec3 v_normal;
+layout(location=2) out vec3 v_position;
+layout(location=3) out vec2 v_tex_coords;
+layout(location=4) out float v_ao;
+layout(location=5) flat out uint v_material_id;
 
 // camera
 layout(set=0, binding=0) 
@@ -14,8 +25,13 @@ uniform Uniforms {
 
 void main() {
     // color
-    v_color = a_color;
+    v_color = a_color * a_tint;
+    v_normal = a_normal;
+    v_tex_coords = a_tex_coords;
+    v_ao = a_ao;
+    v_material_id = a_material_id;
 
     // camera position
+    v_position = a_position;
     gl_Position = u_view_proj * vec4(a_position, 1.0);
 }
\ No newline at end of file
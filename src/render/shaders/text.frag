@@ -0,0 +1,14 @@
+#version 450
+
+layout(location=0) in vec2 v_tex_coords;
+layout(location=1) in vec3 v_color;
+
+layout(location=0) out vec4 f_color;
+
+layout(set=0, binding=0) uniform texture2D t_font;
+layout(set=0, binding=1) uniform sampler s_font;
+
+void main() {
+    float coverage = texture(sampler2D(t_font, s_font), v_tex_coords).a;
+    f_color = vec4(v_color, coverage);
+}
@@ -0,0 +1,23 @@
+#version 450
+
+layout(location=0) in vec2 a_position;
+layout(location=1) in vec2 a_tex_coords;
+layout(location=2) in vec3 a_color;
+
+layout(location=0) out vec2 v_tex_coords;
+layout(location=1) out vec3 v_color;
+
+// screen size in pixels
+layout(set=0, binding=2)
+uniform Screen {
+    vec2 u_screen_size;
+};
+
+void main() {
+    v_tex_coords = a_tex_coords;
+    v_color = a_color;
+
+    // orthographic projection from pixels (top left origin) to clip space
+    vec2 ndc = a_position / u_screen_size * 2.0 - 1.0;
+    gl_Position = vec4(ndc.x, -ndc.y, 0.0, 1.0);
+}
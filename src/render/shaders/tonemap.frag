@@ -0,0 +1,18 @@
+#version 450
+
+layout(location=0) in vec2 v_tex_coords;
+layout(location=0) out vec4 f_color;
+
+layout(set=0, binding=0) uniform texture2D t_hdr;
+layout(set=0, binding=1) uniform sampler s_hdr;
+
+layout(set=0, binding=2)
+uniform ToneMap {
+    float u_exposure;
+};
+
+void main() {
+    vec3 hdr = texture(sampler2D(t_hdr, s_hdr), v_tex_coords).rgb * u_exposure;
+    // Reinhard, the bright colors get close to 1 without ever clipping
+    f_color = vec4(hdr / (hdr + vec3(1.0)), 1.0);
+}
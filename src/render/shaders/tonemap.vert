@@ -0,0 +1,11 @@
+#version 450
+
+layout(location=0) out vec2 v_tex_coords;
+
+// a single triangle covering the screen, the vertices come from their index only
+void main() {
+    vec2 position = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    // the texture rows go down while the clip space goes up
+    v_tex_coords = vec2(position.x, 1.0 - position.y);
+    gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,50 @@
+#version 450
+
+layout(location=0) in vec3 a_position;
+layout(location=1) in vec3 a_color;
+layout(location=2) in vec3 a_normal;
+layout(location=3) in vec2 a_tex_coords;
+layout(location=4) in float a_ao;
+layout(location=5) in uint a_material_id;
+// from the second vertex buffer, see block_tint.rs
+layout(location=6) in vec3 a_tint;
+
+layout(location=0) out vec3 v_color;
+layout(location=1) out vec3 v_normal;
+layout(location=2) out vec3 v_position;
+layout(location=3) out vec2 v_tex_coords;
+layout(location=4) out float v_ao;
+layout(location=5) flat out uint v_material_id;
+
+// camera
+layout(set=0, binding=0) 
+uniform Uniforms {
+    vec4 u_view_position; // unused
+    mat4 u_view_proj;
+    float u_time;
+    float u_fog; // unused
+    vec4 u_ambient_color; // unused
+};
+
+// waves, the speed has to stay a whole number to loop with the time
+const float WAVE_HEIGHT = 0.05;
+const float WAVE_FREQUENCY = 1.5;
+const float WAVE_SPEED = 2.0;
+
+void main() {
+    // color
+    v_color = a_color * a_tint;
+    v_normal = a_normal;
+    v_tex_coords = a_tex_coords;
+    v_ao = a_ao;
+    v_material_id = a_material_id;
+
+    // the surface only goes down so it never pokes through the blocks above
+    vec3 position = a_position;
+    float phase = u_time * WAVE_SPEED;
+    position.y += WAVE_HEIGHT * 0.5 * (sin(position.x * WAVE_FREQUENCY + phase) + sin(position.z * WAVE_FREQUENCY + phase) - 2.0);
+
+    // camera position
+    v_position = position;
+    gl_Position = u_view_proj * vec4(position, 1.0);
+}
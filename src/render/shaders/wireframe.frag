@@ -0,0 +1,10 @@
+#version 450
+
+layout(location=0) out vec4 f_color;
+
+// dark enough to stand out on every block color
+const vec3 WIREFRAME_COLOR = vec3(0.05, 0.05, 0.05);
+
+void main() {
+    f_color = vec4(WIREFRAME_COLOR, 1.0);
+}
@@ -2,47 +2,203 @@ use vertex::Vertex;
 use wgpu::util::DeviceExt;
 use winit::{event::*, window::Window};
 use cgmath::*;
-use super::{camera, uniform, vertex, light, texture, terrain};
+use log::{info, warn, error};
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::Context;
+use futures::executor::block_on;
+use super::{camera, uniform, vertex, block_tint, light, material, texture, debug_labels::DebugLabels, terrain, session, stats, text, window, benchmark, hotbar, shader, memory, settings, tonemap, config::{Config, ViewConfig}};
+use super::command::{self, EngineCommand};
+use super::scissor::{Corner, ScissorRect};
+use super::chunk_buffers::{self, ChunkBuffers};
+use super::uniform_ring::UniformRing;
+use super::gpu_mesh::GpuMesh;
+use super::layouts::{BindGroupLayouts, BindGroupSlot, BLOCK_PIPELINE_GROUPS, LIGHT_PIPELINE_GROUPS};
+use terrain::chunk_manager::{ChunkManager, RenderMode, SeedMode};
+
+// how far the targeted block can be
+const TARGET_DISTANCE: f32 = 10.0;
+// the outline of the targeted block, grown a little so the faces do not hide it
+const OUTLINE_INFLATION: f32 = 0.005;
+const OUTLINE_COLOR: [f32; 3] = [0.05, 0.05, 0.05];
+// present modes in the order they are cycled through
+pub const PRESENT_MODES: [wgpu::PresentMode; 3] = [
+    wgpu::PresentMode::Fifo,
+    wgpu::PresentMode::Mailbox,
+    wgpu::PresentMode::Immediate,
+];
+// length of the debug lines showing the face normals
+const NORMAL_LINE_LENGTH: f32 = 0.2;
+// part of the glass color over what is behind it
+pub const GLASS_OPACITY: f64 = 0.35;
+// blends with the constant blend color, set to GLASS_OPACITY by the glass pass
+const GLASS_BLEND: wgpu::BlendState = wgpu::BlendState {
+    src_factor: wgpu::BlendFactor::BlendColor,
+    dst_factor: wgpu::BlendFactor::OneMinusBlendColor,
+    operation: wgpu::BlendOperation::Add,
+};
+// pulls the wireframe in front of the faces it is drawn over
+pub const WIREFRAME_DEPTH_BIAS: wgpu::DepthBiasState = wgpu::DepthBiasState {
+    constant: -2,
+    slope_scale: -1.0,
+    clamp: 0.0,
+};
+// the point mode draws each terrain vertex on its own
+pub const POINT_TOPOLOGY: wgpu::PrimitiveTopology = wgpu::PrimitiveTopology::PointList;
+// the 8 bits formats the screenshots and the text renderer know how to handle
+pub const SWAP_CHAIN_FORMATS: [wgpu::TextureFormat; 4] = [
+    wgpu::TextureFormat::Bgra8UnormSrgb,
+    wgpu::TextureFormat::Rgba8UnormSrgb,
+    wgpu::TextureFormat::Bgra8Unorm,
+    wgpu::TextureFormat::Rgba8Unorm,
+];
+// what most surfaces support, used when the preferred format is not in SWAP_CHAIN_FORMATS
+pub const FALLBACK_SWAP_CHAIN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+pub const SCREENSHOT_PATH: &str = "screenshot.png";
+// bounds of the time scale, each key press doubles or halves it
+const MIN_TIME_SCALE: f32 = 0.125;
+const MAX_TIME_SCALE: f32 = 8.0;
+// frame times gathered by the vsync off benchmark
+const FRAME_HISTOGRAM_WINDOW: std::time::Duration = std::time::Duration::from_secs(10);
+// field of view change for each key press
+const FOV_STEP: cgmath::Deg<f32> = cgmath::Deg(5.0);
+// color of the sky, also used for the fog and the ambient light
+pub const DEFAULT_AMBIENT_COLOR: [f32; 3] = [0.078, 0.078, 0.121];
+// directory where wgpu records its api calls, needs the "trace" feature
+pub const TRACE_PATH_VAR: &str = "ROVER_WGPU_TRACE";
+
+// What State::from_device builds the renderer on, created by the caller. The swap chain is
+// created from swap_chain_desc, the depth texture and the pipelines use depth_format. With
+// a HDR format the scene is drawn into a texture of that format then tone mapped. Without
+// a surface the renderer is offscreen, swap_chain_desc then only gives the size and the
+// format of the frames, which are read back with capture_frame.
+pub struct DeviceContext {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub surface: Option<wgpu::Surface>,
+    pub swap_chain_desc: wgpu::SwapChainDescriptor,
+    pub depth_format: wgpu::TextureFormat,
+    pub hdr_format: Option<wgpu::TextureFormat>,
+}
 
 pub struct State {
-    // swap chain
-    pub surface: wgpu::Surface,
+    // swap chain, None for an offscreen renderer
+    pub surface: Option<wgpu::Surface>,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
     pub swap_chain_desc: wgpu::SwapChainDescriptor,
-    pub swap_chain: wgpu::SwapChain,
+    pub swap_chain: Option<wgpu::SwapChain>,
     pub size: winit::dpi::PhysicalSize<u32>,
+    pub scale_factor: f64,
     // rendering pipeline
     pub render_pipeline: wgpu::RenderPipeline,
     pub light_render_pipeline: wgpu::RenderPipeline,
+    pub water_render_pipeline: wgpu::RenderPipeline,
+    pub glass_render_pipeline: wgpu::RenderPipeline,
+    pub line_render_pipeline: wgpu::RenderPipeline,
+    pub point_render_pipeline: wgpu::RenderPipeline,
+    pub outline_render_pipeline: wgpu::RenderPipeline,
+    // None when the device cannot draw polygons as lines
+    pub wireframe_render_pipeline: Option<wgpu::RenderPipeline>,
+    // the bind groups created from now on share them with the pipelines
+    pub layouts: BindGroupLayouts,
+    // None when the scene is drawn straight into the swap chain
+    pub tone_mapper: Option<tonemap::ToneMapper>,
+    // target of the scene pipelines, see color_format
+    color_format: wgpu::TextureFormat,
     // buffers
-    pub vertex_buffer: wgpu::Buffer,
-    pub index_buffer: wgpu::Buffer,
-    pub uniform_buffer: wgpu::Buffer,
-    pub light_buffer: wgpu::Buffer,
+    pub chunk_buffers: HashMap<(i32, i32), ChunkBuffers>,
+    // the uniforms and the light are written every frame, each frame has its own copy
+    pub uniform_ring: UniformRing,
+    pub light_ring: UniformRing,
     // bind groups
-    pub uniform_bind_group: wgpu::BindGroup,
-    pub light_bind_group: wgpu::BindGroup,
+    pub atlas_bind_group: wgpu::BindGroup,
+    pub material_bind_group: wgpu::BindGroup,
+    // small cube drawn where the light is
+    light_marker: GpuMesh,
     // uniforms
     pub uniforms: uniform::Uniforms,
+    pub ambient_color: [f32; 3],
     // textures & materials
     pub depth_texture: texture::Texture,
+    // only its size is kept, the bind group owns it
+    pub atlas_memory_bytes: u64,
     // pub debug_material: vertex::Material,
     // lights
     pub light: light::Light,
+    pub light_orbit: light::LightOrbit,
     // camera
     pub camera: camera::Camera,
     pub projection: camera::Projection,
     pub camera_controller: camera::CameraController,
+    // debug overlay
+    pub text_renderer: text::TextRenderer,
+    pub debug_text: Vec<String>,
+    pub show_debug: bool,
+    // the chunks are culled with it instead of the camera, to fly around and look at what it culls
+    pub frozen_frustum: Option<camera::Frustum>,
+    pub frame_stats: stats::FrameStats,
+    // frames submitted so far, shown in the labels of the passes
+    pub frame_index: u64,
+    pub debug_labels: DebugLabels,
+    // frame times gathered with the vsync off, and the present mode to go back to
+    frame_histogram: Option<(stats::FrameTimeHistogram, wgpu::PresentMode)>,
+    // filled by render, the captured frames leave it alone
+    draw_stats: stats::FrameDrawStats,
+    // a line along the normal of each face of the main chunk, None when hidden
+    normal_lines: Option<(wgpu::Buffer, u32)>,
+    // edges of the block picked by the last update, written again when it changes
+    outline_buffer: wgpu::Buffer,
+    outline_target: Option<[usize; 3]>,
+    // the terrain and water vertices are drawn as points, to see how dense the meshes are
+    pub point_mode: bool,
+    // the edges of the terrain triangles are drawn over them
+    pub wireframe: bool,
+    // Some in the walk mode, the camera then falls and collides with the main chunk
+    pub walker: Option<camera::collision::Walker>,
+    // the camera follows the benchmark path instead of the controller
+    pub benchmark: Option<benchmark::Benchmark>,
     // states
     //pub mouse_pressed: bool,
     pub mouse_capture: bool,
+    // last position of the cursor over the window, the blocks are picked through it when it is shown
+    pub cursor_position: Option<winit::dpi::PhysicalPosition<f64>>,
+    pub cursor_grab: window::CursorGrab,
+    // animations are frozen but the camera still moves
+    pub paused: bool,
+    // speed of the animations, the camera and the frame rate are left as is
+    pub time_scale: f32,
+    pub autosave: bool,
+    // the runtime changes are written back to config.path on exit
+    pub config: Config,
+    // the mouse wheel picks the block placed by the right click
+    pub build_mode: bool,
+    pub hotbar: hotbar::Hotbar,
+    // the camera does not get the input the panel takes
+    pub settings: settings::SettingsPanel,
+    pub panel_renderer: settings::PanelRenderer,
+    pub fog: bool,
+    // from 0, no ambient occlusion, to 1
+    pub ao_strength: f32,
+    // commands sent through command_sender, drained by each update
+    commands: std::sync::mpsc::Receiver<EngineCommand>,
+    command_sender: std::sync::mpsc::Sender<EngineCommand>,
     // data
-    pub num_index: u32,
+    pub chunk: terrain::chunk::Chunk,
+    pub chunk_manager: ChunkManager,
+    // colors of the blocks, given to every chunk
+    pub block_registry: Arc<terrain::registry::BlockRegistry>,
+    pub render_mode: RenderMode,
+    pub mesh_mode: terrain::chunk::MeshMode,
+    // merge the identical vertices before uploading the mesh
+    pub dedup_vertices: bool,
+    pub mesh_cache: terrain::mesh_cache::MeshCache,
+    // pixels of error allowed by the level of detail of the chunks
+    pub lod_threshold: f32,
 }
 
 impl State {
-    pub async fn new(window: &Window) -> Self {
+    pub async fn new(window: &Window, config: &Config) -> Self {
         // getting the window size
         let size = window.inner_size();
 
@@ -58,105 +214,170 @@ impl State {
         ).await.unwrap();
 
         // device and queue from adapter
+        let trace_path = State::trace_path(std::env::var_os(TRACE_PATH_VAR));
+        if let Some(path) = &trace_path {
+            if let Err(e) = std::fs::create_dir_all(path) {
+                warn!("Cannot create the trace directory {}: {}", path.display(), e);
+            }
+            info!("Tracing the device to {}", path.display());
+        }
         let (device, queue) = adapter.request_device(
             &wgpu::DeviceDescriptor {
-                features: wgpu::Features::empty(),
+                // only the wireframe overlay needs it, it is left out when missing
+                features: adapter.features() & wgpu::Features::NON_FILL_POLYGON_MODE,
                 limits: wgpu::Limits::default(),
                 label: None,
             },
-            None, // Trace path
+            trace_path.as_deref(),
         ).await.unwrap();
 
+        let swap_chain_desc = State::create_swap_chain_desc(&size, &surface, &adapter);
+        let depth_format = texture::Texture::choose_depth_format(&adapter, &texture::Texture::DEPTH_FORMATS);
+        info!("Depth format: {:?}", depth_format);
+        // the scene goes straight to the swap chain when HDR is off or unsupported
+        let hdr_format = if !config.hdr {
+            None
+        } else if tonemap::is_supported(&adapter, tonemap::HDR_FORMAT) {
+            Some(tonemap::HDR_FORMAT)
+        } else {
+            warn!("{:?} cannot be rendered to on this adapter, HDR is disabled", tonemap::HDR_FORMAT);
+            None
+        };
+        let context = DeviceContext { device, queue, surface: Some(surface), swap_chain_desc, depth_format, hdr_format };
+        let mut state = State::from_device(context, config);
+        state.scale_factor = window.scale_factor();
+        state.settings = settings::SettingsPanel::new(state.size, state.scale_factor);
+        state
+    }
+
+    // Format of the color target the scene is drawn into, the HDR format when it is on. A
+    // pipeline added to the scene pass has to target it, along with depth_format.
+    pub fn color_format(&self) -> wgpu::TextureFormat {
+        self.color_format
+    }
+
+    // format of the depth texture of the scene pass, chosen from the adapter at creation
+    pub fn depth_format(&self) -> wgpu::TextureFormat {
+        self.depth_texture.format
+    }
+
+    // trace directory from the value of TRACE_PATH_VAR, an empty value disables it
+    pub fn trace_path(value: Option<std::ffi::OsString>) -> Option<std::path::PathBuf> {
+        value.filter(|v| !v.is_empty()).map(std::path::PathBuf::from)
+    }
+
+    // builds the renderer on a device created by the caller, so it can be shared with other wgpu code
+    pub fn from_device(context: DeviceContext, config: &Config) -> Self {
+        let DeviceContext { device, queue, surface, swap_chain_desc, depth_format, hdr_format } = context;
+
         // swap chain
-        let (swap_chain_desc, swap_chain) = State::create_swap_chain(&size, &surface, &device, &adapter);
+        let size = winit::dpi::PhysicalSize::new(swap_chain_desc.width, swap_chain_desc.height);
+        let swap_chain = surface.as_ref().map(|surface| device.create_swap_chain(surface, &swap_chain_desc));
 
         // camera
-        let camera = camera::Camera::new((0.0, 5.0, 10.0), cgmath::Deg(-90.0), cgmath::Deg(-20.0));
-        let projection = camera::Projection::new(swap_chain_desc.width, swap_chain_desc.height, cgmath::Deg(45.0), 0.1, 100.0);
-        let camera_controller = camera::CameraController::new(5.0, 0.6);
+        let camera = camera::Camera::new((0.0, 5.0, 10.0), cgmath::Deg(-90.0), cgmath::Deg(-20.0)).with_up_axis(config.up_axis);
+        let projection = camera::Projection::new(swap_chain_desc.width, swap_chain_desc.height, cgmath::Deg(config.view.fov), 0.1, 100.0);
+        let mut camera_controller = camera::CameraController::new(5.0, config.view.sensitivity);
+        camera_controller.dead_zone = config.view.mouse_dead_zone;
 
         // uniforms
         let mut uniforms = uniform::Uniforms::new();
         uniforms.update_view_proj(&camera, &projection);
+        let ambient_color = DEFAULT_AMBIENT_COLOR;
+        uniforms.set_ambient_color(ambient_color);
 
         // light
-        let light = light::Light {
-            position: [2.0, 2.0, 2.0],
-            _padding: 0,
-            color: [1.0, 1.0, 1.0],
-        };
+        let light_orbit = light_orbit(config);
+        let light = light::Light::new(light_orbit.position().into(), [1.0, 1.0, 1.0]);
 
         // data
-        let chunk = terrain::chunk::Chunk::new();
-        let (vertices, indices) = chunk.create_mesh();
-        let vertices: &[vertex::ColorVertex] = &vertices.as_slice();
-        let indices: &[u16] = &indices.as_slice();
-        let num_index = indices.len() as u32;
+        let block_registry = Arc::new(match &config.blocks {
+            Some(path) => terrain::registry::BlockRegistry::load(path).unwrap_or_else(|e| {
+                error!("{:?}", e);
+                terrain::registry::BlockRegistry::builtin()
+            }),
+            None => terrain::registry::BlockRegistry::builtin(),
+        });
+        let mut chunk = match &config.scene {
+            Some(path) => terrain::chunk::Chunk::from_voxel_file(path).unwrap_or_else(|e| {
+                error!("{:?}", e);
+                terrain::chunk::Chunk::generate(config.terrain)
+            }),
+            None => terrain::chunk::Chunk::generate(config.terrain),
+        }.with_registry(block_registry.clone());
+        // the chunks around are only meshed once they are drawn
+        let view_radius = config.view.view_radius.clamp(1, terrain::chunk_manager::MAX_VIEW_RADIUS);
+        let seed_mode = if config.chunk_seeds { SeedMode::PerChunk } else { SeedMode::Shared };
+        let mut chunk_manager = ChunkManager::new(chunk.terrain, view_radius, seed_mode);
+        chunk_manager.registry = block_registry.clone();
+        if let Some(budget) = config.generation_budget {
+            chunk_manager.generation_budget = budget;
+        }
+        let render_mode = if config.multi_chunk { RenderMode::MultiChunk } else { RenderMode::SingleChunk };
+        let mesh_mode = terrain::chunk::MeshMode::Blocky;
+        let dedup_vertices = true;
+        let mut mesh_cache = terrain::mesh_cache::MeshCache::new(terrain::mesh_cache::DEFAULT_MESH_CACHE_CAPACITY);
 
         // buffers
-        let vertex_buffer = device.create_buffer_init(
-            &wgpu::util::BufferInitDescriptor{
-                label: Some("Vertex Buffer"),
-                contents: bytemuck::cast_slice(vertices),
-                usage: wgpu::BufferUsage::VERTEX,
-            }
-        );
-        let index_buffer = device.create_buffer_init(
-            &wgpu::util::BufferInitDescriptor{
-                label: Some("Indices Buffer"),
-                contents: bytemuck::cast_slice(indices),
-                usage: wgpu::BufferUsage::INDEX,
-            }
-        );
-        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Uniform Buffer"),
-            contents: bytemuck::cast_slice(&[uniforms]),
-            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
-        });
-        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Light VB"),
-            contents: bytemuck::cast_slice(&[light]),
-            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        let mut chunk_buffers = HashMap::new();
+        chunk_buffers.insert(chunk.position, ChunkBuffers::new(&device, &queue, &mut mesh_cache, &chunk, mesh_mode, dedup_vertices));
+        chunk.dirty = false;
+        let light_marker = terrain::chunk::cube_mesh([1.0, 1.0, 1.0]).upload(&device, "Light Marker");
+        let outline_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Block Outline VB"),
+            size: (terrain::chunk::OUTLINE_VERTICES * std::mem::size_of::<vertex::ColorVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
         });
 
         // bind groups layouts
-        // let texture_bind_group_layout = texture::create_bind_group_layout(&device);
-        let uniform_bind_group_layout = uniform::create_bind_group_layout(&device);
-        let light_bind_group_layout = light::create_bind_group_layout(&device);
+        let layouts = BindGroupLayouts::new(&device);
 
         // bind groups
-        let uniform_bind_group = uniform::create_bind_group(
-            &device, 
-            &uniform_bind_group_layout, 
-            &uniform_buffer
-        );
-        let light_bind_group = light::create_bind_group(
-            &device, 
-            &light_bind_group_layout, 
-            &light_buffer
-        );
+        let uniform_ring = UniformRing::new(&device, &layouts.uniform, "Uniform Buffer", bytemuck::cast_slice(&[uniforms]), uniform::create_bind_group);
+        let light_ring = UniformRing::new(&device, &layouts.light, "Light VB", bytemuck::cast_slice(&[light]), light::create_bind_group);
 
         // texture
-        let depth_texture = texture::Texture::create_depth_texture(&device, &swap_chain_desc, "depth_texture");
+        let depth_texture = texture::Texture::create_depth_texture(&device, &swap_chain_desc, depth_format, "depth_texture");
+        let block_atlas = texture::Texture::from_image_with_mips(
+            &device,
+            &queue,
+            &terrain::atlas::create_atlas_image(),
+            Some("block_atlas"),
+            // the texels are brightness factors, not colors
+            wgpu::TextureFormat::Rgba8Unorm,
+            texture::mip_level_count(terrain::atlas::TILE_SIZE, terrain::atlas::TILE_SIZE),
+            config.anisotropy.and_then(|a| texture::clamp_anisotropy(a, texture::MAX_ANISOTROPY)),
+        );
+        // the bind group keeps the texture alive
+        let atlas_bind_group = texture::create_diffuse_bind_group(&device, &layouts.diffuse, &block_atlas);
+        // the materials never change, their buffer is only written once
+        let material_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Materials Buffer"),
+            contents: bytemuck::cast_slice(&[material::Materials::from_registry(&block_registry)]),
+            usage: wgpu::BufferUsage::UNIFORM,
+        });
+        let material_bind_group = material::create_bind_group(&device, &layouts.material, &material_buffer);
+
+        let tone_mapper = hdr_format.map(|format| tonemap::ToneMapper::new(&device, &swap_chain_desc, format));
+        let scene_format = scene_format(hdr_format, swap_chain_desc.format);
 
         // rendering pipelines
         let render_pipeline = {
             let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[
-                    //&texture_bind_group_layout,
-                    &uniform_bind_group_layout,
-                    //&light_bind_group_layout,
-                ],
+                bind_group_layouts: &layouts.for_groups(&BLOCK_PIPELINE_GROUPS),
                 push_constant_ranges: &[],
             });
 
             State::create_render_pipeline(
                 &device,
+                "Render Pipeline",
                 &layout,
-                swap_chain_desc.format,
-                Some(texture::Texture::DEPTH_FORMAT),
-                &[vertex::ColorVertex::desc()],
+                scene_format,
+                Some(depth_texture.format),
+                &[vertex::ColorVertex::desc(), block_tint::TintVertex::desc()],
+                wgpu::PrimitiveTopology::TriangleList,
                 wgpu::include_spirv!("shaders/simple.vert.spv"),
                 wgpu::include_spirv!("shaders/simple.frag.spv"),
             )
@@ -164,23 +385,173 @@ impl State {
         let light_render_pipeline = {
             let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Light Pipeline Layout"),
-                bind_group_layouts: &[
-                    &uniform_bind_group_layout, 
-                    &light_bind_group_layout
-                ],
+                bind_group_layouts: &layouts.for_groups(&LIGHT_PIPELINE_GROUPS),
                 push_constant_ranges: &[],
             });
 
             State::create_render_pipeline(
                 &device,
+                "Light Pipeline",
                 &layout,
-                swap_chain_desc.format,
-                Some(texture::Texture::DEPTH_FORMAT),
+                scene_format,
+                Some(depth_texture.format),
                 &[vertex::ColorVertex::desc()],
+                wgpu::PrimitiveTopology::TriangleList,
                 wgpu::include_spirv!("shaders/light.vert.spv"),
                 wgpu::include_spirv!("shaders/light.frag.spv"),
             )
         };
+        let water_render_pipeline = {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Water Pipeline Layout"),
+                bind_group_layouts: &layouts.for_groups(&BLOCK_PIPELINE_GROUPS),
+                push_constant_ranges: &[],
+            });
+
+            State::create_render_pipeline(
+                &device,
+                "Water Pipeline",
+                &layout,
+                scene_format,
+                Some(depth_texture.format),
+                &[vertex::ColorVertex::desc(), block_tint::TintVertex::desc()],
+                wgpu::PrimitiveTopology::TriangleList,
+                wgpu::include_spirv!("shaders/water.vert.spv"),
+                wgpu::include_spirv!("shaders/simple.frag.spv"),
+            )
+        };
+
+        // the terrain pipeline, blended over what was drawn before it
+        let glass_render_pipeline = {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Glass Pipeline Layout"),
+                bind_group_layouts: &layouts.for_groups(&BLOCK_PIPELINE_GROUPS),
+                push_constant_ranges: &[],
+            });
+
+            State::create_render_pipeline_with(
+                &device,
+                "Glass Pipeline",
+                &layout,
+                scene_format,
+                Some(depth_texture.format),
+                &[vertex::ColorVertex::desc(), block_tint::TintVertex::desc()],
+                wgpu::PrimitiveTopology::TriangleList,
+                wgpu::PolygonMode::Fill,
+                GLASS_BLEND,
+                wgpu::DepthBiasState::default(),
+                wgpu::include_spirv!("shaders/simple.vert.spv"),
+                wgpu::include_spirv!("shaders/simple.frag.spv"),
+            )
+        };
+
+        let line_render_pipeline = {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Line Pipeline Layout"),
+                bind_group_layouts: &[
+                    &layouts.uniform,
+                ],
+                push_constant_ranges: &[],
+            });
+
+            State::create_render_pipeline(
+                &device,
+                "Line Pipeline",
+                &layout,
+                scene_format,
+                Some(depth_texture.format),
+                &[vertex::ColorVertex::desc()],
+                wgpu::PrimitiveTopology::LineList,
+                wgpu::include_spirv!("shaders/line.vert.spv"),
+                wgpu::include_spirv!("shaders/line.frag.spv"),
+            )
+        };
+
+        // same layout as the lines, one point for each vertex of the meshes
+        let point_render_pipeline = {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Point Pipeline Layout"),
+                bind_group_layouts: &[
+                    &layouts.uniform,
+                ],
+                push_constant_ranges: &[],
+            });
+
+            State::create_render_pipeline(
+                &device,
+                "Point Pipeline",
+                &layout,
+                scene_format,
+                Some(depth_texture.format),
+                &[vertex::ColorVertex::desc()],
+                POINT_TOPOLOGY,
+                wgpu::include_spirv!("shaders/point.vert.spv"),
+                wgpu::include_spirv!("shaders/line.frag.spv"),
+            )
+        };
+
+        // The lines of the block outline. The depth bias only moves them on the backends
+        // offsetting lines, the inflation of the outline keeps them in front of the faces.
+        let outline_render_pipeline = {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Outline Pipeline Layout"),
+                bind_group_layouts: &[
+                    &layouts.uniform,
+                ],
+                push_constant_ranges: &[],
+            });
+
+            State::create_render_pipeline_with(
+                &device,
+                "Outline Pipeline",
+                &layout,
+                scene_format,
+                Some(depth_texture.format),
+                &[vertex::ColorVertex::desc()],
+                wgpu::PrimitiveTopology::LineList,
+                wgpu::PolygonMode::Fill,
+                wgpu::BlendState::REPLACE,
+                WIREFRAME_DEPTH_BIAS,
+                wgpu::include_spirv!("shaders/line.vert.spv"),
+                wgpu::include_spirv!("shaders/line.frag.spv"),
+            )
+        };
+
+        // the terrain triangles again, as lines drawn over the filled ones
+        let wireframe_render_pipeline = if device.features().contains(wgpu::Features::NON_FILL_POLYGON_MODE) {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Wireframe Pipeline Layout"),
+                bind_group_layouts: &[
+                    &layouts.uniform,
+                ],
+                push_constant_ranges: &[],
+            });
+
+            Some(State::create_render_pipeline_with(
+                &device,
+                "Wireframe Pipeline",
+                &layout,
+                scene_format,
+                Some(depth_texture.format),
+                &[vertex::ColorVertex::desc()],
+                wgpu::PrimitiveTopology::TriangleList,
+                wgpu::PolygonMode::Line,
+                wgpu::BlendState::REPLACE,
+                WIREFRAME_DEPTH_BIAS,
+                wgpu::include_spirv!("shaders/line.vert.spv"),
+                wgpu::include_spirv!("shaders/wireframe.frag.spv"),
+            ))
+        } else {
+            warn!("Polygon modes are not supported, the wireframe overlay is disabled");
+            None
+        };
+
+        let (command_sender, commands) = std::sync::mpsc::channel();
+
+        // debug overlay
+        let text_renderer = text::TextRenderer::new(&device, &queue, swap_chain_desc.format, size.width, size.height);
+        // drawn into the frame after the tone mapping, like the text
+        let panel_renderer = settings::PanelRenderer::new(&device, swap_chain_desc.format);
 
         // returning the new state
         State {
@@ -191,65 +562,269 @@ impl State {
             swap_chain_desc,
             swap_chain,
             size,
+            scale_factor: 1.0,
             // rendering pipeline
             render_pipeline,
             light_render_pipeline,
+            water_render_pipeline,
+            glass_render_pipeline,
+            line_render_pipeline,
+            point_render_pipeline,
+            outline_render_pipeline,
+            wireframe_render_pipeline,
+            layouts,
+            tone_mapper,
+            color_format: scene_format,
             // buffers
-            vertex_buffer,
-            index_buffer,
-            uniform_buffer,
-            light_buffer,
+            chunk_buffers,
+            uniform_ring,
+            light_ring,
             // bind groups
-            uniform_bind_group,
-            light_bind_group,
+            atlas_bind_group,
+            material_bind_group,
+            light_marker,
             // uniforms
             uniforms,
+            ambient_color,
             // textures & materials
             depth_texture,
+            atlas_memory_bytes: block_atlas.memory_bytes,
             // debug_material,
             // lights
             light,
+            light_orbit,
             // camera
             camera,
             projection,
             camera_controller,
+            // debug overlay
+            text_renderer,
+            debug_text: vec![],
+            show_debug: false,
+            frozen_frustum: None,
+            frame_stats: stats::FrameStats::new(),
+            frame_index: 0,
+            debug_labels: DebugLabels::new(config.debug_labels),
+            frame_histogram: None,
+            draw_stats: stats::FrameDrawStats::default(),
+            normal_lines: None,
+            outline_buffer,
+            outline_target: None,
+            point_mode: false,
+            wireframe: false,
+            walker: None,
+            benchmark: config.benchmark.map(|seconds| benchmark::Benchmark::new(std::time::Duration::from_secs_f32(seconds))),
             // states,
             //mouse_pressed: false,
             mouse_capture: false,
+            cursor_position: None,
+            cursor_grab: config.window.cursor_grab,
+            paused: false,
+            time_scale: 1.0,
+            autosave: config.autosave,
+            config: config.clone(),
+            build_mode: false,
+            hotbar: hotbar::Hotbar::with_registry(&block_registry),
+            settings: settings::SettingsPanel::new(size, 1.0),
+            panel_renderer,
+            fog: true,
+            ao_strength: config.ao_strength.unwrap_or(1.0).clamp(0.0, 1.0),
+            commands,
+            command_sender,
             // data
-            num_index,
+            chunk,
+            chunk_manager,
+            block_registry,
+            render_mode,
+            mesh_mode,
+            dedup_vertices,
+            mesh_cache,
+            lod_threshold: config.lod_threshold.unwrap_or(terrain::lod::DEFAULT_LOD_THRESHOLD),
         }
     }
 
-    pub fn create_swap_chain(size: &winit::dpi::PhysicalSize<u32>, surface: &wgpu::Surface, device: &wgpu::Device, adapter: &wgpu::Adapter) -> (wgpu::SwapChainDescriptor, wgpu::SwapChain) {
-        let swap_chain_desc = wgpu::SwapChainDescriptor {
+    // uploads the chunks edited since the last frame, the ones around only when they are drawn
+    fn remesh_chunks(&mut self) {
+        if self.chunk.take_dirty() {
+            self.upload_chunk(None);
+            if self.normal_lines.is_some() {
+                self.normal_lines = Some(self.create_normal_lines());
+            }
+        }
+        if self.render_mode == RenderMode::MultiChunk {
+            for position in self.chunk_manager.take_dirty() {
+                self.upload_chunk(Some(position));
+            }
+        }
+    }
+
+    // The chunks in the view get the level of detail their distance calls for, the others
+    // keep theirs until they are seen again. A new level is meshed by the next update.
+    fn update_lods(&mut self) {
+        let fov_y = self.projection.fov_y();
+        let camera = self.camera.position.to_vec();
+        for (position, buffers) in self.chunk_buffers.iter().filter(|(_, buffers)| buffers.visible) {
+            let lod = terrain::lod::select_lod(buffers.bounds.distance_to(camera), fov_y, self.size.height, self.lod_threshold);
+            let chunk = if *position == self.chunk.position {
+                Some(&mut self.chunk)
+            } else {
+                self.chunk_manager.chunks.get_mut(position)
+            };
+            if let Some(chunk) = chunk {
+                chunk.set_lod(lod);
+            }
+        }
+    }
+
+    // the lines follow the blocky faces, whatever the mesh mode
+    fn create_normal_lines(&self) -> (wgpu::Buffer, u32) {
+        let lines = self.chunk.create_normal_lines(&Default::default(), NORMAL_LINE_LENGTH);
+        let lines = chunk_buffers::translate(&lines, self.chunk.world_offset());
+        let buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Normal Lines VB"),
+            contents: bytemuck::cast_slice(&lines),
+            usage: wgpu::BufferUsage::VERTEX,
+        });
+        (buffer, lines.len() as u32)
+    }
+
+    // the frustum is frozen where the camera is now
+    pub fn toggle_frozen_frustum(&mut self) {
+        self.frozen_frustum = match self.frozen_frustum {
+            Some(_) => None,
+            None => Some(camera::Frustum::from_camera(&self.camera, &self.projection)),
+        };
+        info!("Frustum culling {}", if self.frozen_frustum.is_some() { "frozen" } else { "follows the camera" });
+    }
+
+    pub fn toggle_wireframe(&mut self) {
+        if self.wireframe_render_pipeline.is_none() {
+            warn!("The wireframe overlay is not supported by this device");
+            return;
+        }
+        self.wireframe = !self.wireframe;
+        info!("Wireframe {}", if self.wireframe { "on" } else { "off" });
+    }
+
+    pub fn toggle_normal_lines(&mut self) {
+        self.normal_lines = match self.normal_lines {
+            Some(_) => None,
+            None => Some(self.create_normal_lines()),
+        };
+    }
+
+    // reuses the buffers of the chunk when it already has some, the main chunk is None
+    fn upload_chunk(&mut self, manager_position: Option<(i32, i32)>) {
+        let chunk = match manager_position {
+            Some(position) => self.chunk_manager.chunks.get_mut(&position).unwrap(),
+            None => &mut self.chunk,
+        };
+        match self.chunk_buffers.get_mut(&chunk.position) {
+            Some(buffers) => {
+                let reallocated = buffers.update(&self.device, &self.queue, &mut self.mesh_cache, chunk, self.mesh_mode, self.dedup_vertices);
+                if reallocated > 0 {
+                    info!("Reallocated {} buffers of the chunk {:?}", reallocated, chunk.position);
+                }
+            }
+            None => {
+                let buffers = ChunkBuffers::new(&self.device, &self.queue, &mut self.mesh_cache, chunk, self.mesh_mode, self.dedup_vertices);
+                self.chunk_buffers.insert(chunk.position, buffers);
+            }
+        }
+        chunk.dirty = false;
+    }
+
+    // Recolors a block without meshing its chunk again, the default tint gives it back its
+    // colors. False when the chunk has no buffers.
+    pub fn set_block_tint(&mut self, chunk: (i32, i32), block: [usize; 3], tint: [f32; 3]) -> bool {
+        match self.chunk_buffers.get_mut(&chunk) {
+            Some(buffers) => {
+                buffers.set_block_tint(&self.queue, block, tint);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bind_group(&self, slot: BindGroupSlot) -> &wgpu::BindGroup {
+        match slot {
+            BindGroupSlot::Uniforms => self.uniform_ring.bind_group(),
+            BindGroupSlot::Light => self.light_ring.bind_group(),
+            BindGroupSlot::Atlas => &self.atlas_bind_group,
+            BindGroupSlot::Materials => &self.material_bind_group,
+        }
+    }
+
+    // the chunks drawn with the current render mode
+    fn drawn_chunks(&self) -> impl Iterator<Item = &ChunkBuffers> {
+        let (mode, main) = (self.render_mode, self.chunk.position);
+        self.chunk_buffers.iter()
+            .filter(move |(position, _)| mode.draws(**position, main))
+            .map(|(_, buffers)| buffers)
+    }
+
+    pub fn create_swap_chain_desc(size: &winit::dpi::PhysicalSize<u32>, surface: &wgpu::Surface, adapter: &wgpu::Adapter) -> wgpu::SwapChainDescriptor {
+        wgpu::SwapChainDescriptor {
             usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
-            format: adapter.get_swap_chain_preferred_format(surface),
+            format: swap_chain_format(Some(adapter.get_swap_chain_preferred_format(surface))),
             width: size.width,
             height: size.height,
             present_mode: wgpu::PresentMode::Fifo,
-        };
-        let swap_chain = device.create_swap_chain(surface, &swap_chain_desc);
-
-        (swap_chain_desc, swap_chain)
+        }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn create_render_pipeline(
         device: &wgpu::Device,
+        label: &str,
+        layout: &wgpu::PipelineLayout,
+        color_format: wgpu::TextureFormat,
+        depth_format: Option<wgpu::TextureFormat>,
+        vertex_layouts: &[wgpu::VertexBufferLayout],
+        topology: wgpu::PrimitiveTopology,
+        vs_src: wgpu::ShaderModuleDescriptor,
+        fs_src: wgpu::ShaderModuleDescriptor,
+    ) -> wgpu::RenderPipeline {
+        State::create_render_pipeline_with(
+            device,
+            label,
+            layout,
+            color_format,
+            depth_format,
+            vertex_layouts,
+            topology,
+            wgpu::PolygonMode::Fill,
+            wgpu::BlendState::REPLACE,
+            wgpu::DepthBiasState::default(),
+            vs_src,
+            fs_src,
+        )
+    }
+
+    // A polygon mode other than Fill requires Features::NON_FILL_POLYGON_MODE. The alpha is
+    // always replaced, the color_blend only applies to the colors.
+    #[allow(clippy::too_many_arguments)]
+    fn create_render_pipeline_with(
+        device: &wgpu::Device,
+        label: &str,
         layout: &wgpu::PipelineLayout,
         color_format: wgpu::TextureFormat,
         depth_format: Option<wgpu::TextureFormat>,
         vertex_layouts: &[wgpu::VertexBufferLayout],
+        topology: wgpu::PrimitiveTopology,
+        polygon_mode: wgpu::PolygonMode,
+        color_blend: wgpu::BlendState,
+        bias: wgpu::DepthBiasState,
         vs_src: wgpu::ShaderModuleDescriptor,
         fs_src: wgpu::ShaderModuleDescriptor,
     ) -> wgpu::RenderPipeline {
         // loading shaders
-        let vs_module = device.create_shader_module(&vs_src);
-        let fs_module = device.create_shader_module(&fs_src);
+        let vs_module = shader::create_module(device, label, vs_src);
+        let fs_module = shader::create_module(device, label, fs_src);
     
         // returning the pipeling
         device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
+            label: Some(label),
             layout: Some(&layout),
             // vertex shader
             vertex: wgpu::VertexState {
@@ -264,29 +839,14 @@ impl State {
                 targets: &[wgpu::ColorTargetState {
                     format: color_format,
                     alpha_blend: wgpu::BlendState::REPLACE,
-                    color_blend: wgpu::BlendState::REPLACE,
+                    color_blend,
                     write_mask: wgpu::ColorWrite::ALL,
                 }],
             }),
             // creating faces from triangles
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Cw,
-                cull_mode: wgpu::CullMode::Back,
-                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
-                polygon_mode: wgpu::PolygonMode::Fill,
-            },
+            primitive: primitive_state(topology, polygon_mode),
             // setting the depth stencil
-            depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
-                format,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-                // Setting this to true requires Features::DEPTH_CLAMPING
-                clamp_depth: false,
-            }),
+            depth_stencil: depth_format.map(|format| depth_stencil_state(format, bias)),
             // multisampling
             multisample: wgpu::MultisampleState {
                 count: 1,
@@ -297,13 +857,21 @@ impl State {
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        // a minimized window has a null size, which is not a valid swap chain
+        if new_size.width == 0 || new_size.height == 0 {
+            return;
+        }
         self.size = new_size;
         self.projection.resize(new_size.width, new_size.height);
         self.swap_chain_desc.width = new_size.width;
         self.swap_chain_desc.height = new_size.height;
-        self.swap_chain = self.device.create_swap_chain(&self.surface, &self.swap_chain_desc);
+        self.recreate_swap_chain();
         self.depth_texture =
-            texture::Texture::create_depth_texture(&self.device, &self.swap_chain_desc, "depth_texture");
+            texture::Texture::create_depth_texture(&self.device, &self.swap_chain_desc, self.depth_texture.format, "depth_texture");
+        if let Some(tone_mapper) = &mut self.tone_mapper {
+            tone_mapper.resize(&self.device, &self.swap_chain_desc);
+        }
+        self.text_renderer.resize(&self.queue, new_size.width, new_size.height);
     }
 
     pub fn window_input(&mut self, window: &winit::window::Window, event: &WindowEvent) -> bool {
@@ -312,14 +880,28 @@ impl State {
                 button: MouseButton::Left,
                 ..
             } => {
-                if !self.mouse_capture {
-                    window.set_cursor_grab(true).unwrap();
-                    window.set_cursor_visible(false);
-                    self.mouse_capture = true;
+                if !self.mouse_capture && !self.settings.wants_pointer() {
+                    self.mouse_capture = window::grab_captures(window::grab_cursor(window, self.cursor_grab));
                     return true;
                 }
                 false
             }
+            WindowEvent::MouseInput {
+                button: MouseButton::Right,
+                state: ElementState::Pressed,
+                ..
+            } if self.build_mode && self.mouse_capture => {
+                self.place_block();
+                true
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = Some(*position);
+                false
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor_position = None;
+                false
+            }
             _ => false,
         }
     }
@@ -331,19 +913,167 @@ impl State {
                 state,
                 ..
             }) => {
+                if *key == VirtualKeyCode::F1 && *state == ElementState::Pressed {
+                    self.toggle_settings(window);
+                    return true;
+                }
+                // typed in a field of the panel
+                if self.settings.captures(event) {
+                    return true;
+                }
                 if !self.camera_controller.process_keyboard(*key, *state) {
+                    if *key == VirtualKeyCode::M && *state == ElementState::Pressed {
+                        self.toggle_mesh_mode();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::C && *state == ElementState::Pressed {
+                        self.toggle_render_mode();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::O && *state == ElementState::Pressed {
+                        let free = !self.camera.is_free();
+                        self.camera.set_free(free);
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F5 && *state == ElementState::Pressed {
+                        self.save_session(session::DEFAULT_SESSION_PATH);
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F6 && *state == ElementState::Pressed {
+                        self.push_command(EngineCommand::ReloadConfig);
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F7 && *state == ElementState::Pressed {
+                        self.toggle_frozen_frustum();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F8 && *state == ElementState::Pressed {
+                        self.toggle_frame_histogram();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F9 && *state == ElementState::Pressed {
+                        self.load_session(session::DEFAULT_SESSION_PATH);
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::N && *state == ElementState::Pressed {
+                        self.push_command(EngineCommand::SetSeed(self.chunk.terrain.seed.wrapping_add(1)));
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::R && *state == ElementState::Pressed {
+                        self.push_command(EngineCommand::RegenerateVisible);
+                        return true;
+                    }
+                    if *state == ElementState::Pressed {
+                        match key {
+                            VirtualKeyCode::Plus | VirtualKeyCode::Equals | VirtualKeyCode::NumpadAdd => {
+                                self.projection.set_fov_y(self.projection.fov_y() + Rad::from(FOV_STEP));
+                                return true;
+                            }
+                            VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => {
+                                self.projection.set_fov_y(self.projection.fov_y() - Rad::from(FOV_STEP));
+                                return true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if *key == VirtualKeyCode::F && *state == ElementState::Pressed {
+                        self.frame_terrain();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F2 && *state == ElementState::Pressed {
+                        self.save_screenshot(SCREENSHOT_PATH);
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F3 && *state == ElementState::Pressed {
+                        self.show_debug = !self.show_debug;
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::K && *state == ElementState::Pressed {
+                        self.toggle_normal_lines();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::V && *state == ElementState::Pressed {
+                        self.point_mode = !self.point_mode;
+                        info!("Point mode {}", if self.point_mode { "on" } else { "off" });
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::L && *state == ElementState::Pressed {
+                        self.toggle_wireframe();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::G && *state == ElementState::Pressed {
+                        self.walker = match self.walker {
+                            Some(_) => None,
+                            None => Some(camera::collision::Walker::new()),
+                        };
+                        info!("Walk mode {}", if self.walker.is_some() { "on" } else { "off" });
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::F4 && *state == ElementState::Pressed {
+                        self.cycle_present_mode();
+                        return true;
+                    }
+                    if *key == VirtualKeyCode::B && *state == ElementState::Pressed {
+                        self.build_mode = !self.build_mode;
+                        info!("Build mode {}", if self.build_mode { "on" } else { "off" });
+                        return true;
+                    }
+                    if self.build_mode && *state == ElementState::Pressed {
+                        let slot = match key {
+                            VirtualKeyCode::Key1 => Some(0),
+                            VirtualKeyCode::Key2 => Some(1),
+                            VirtualKeyCode::Key3 => Some(2),
+                            VirtualKeyCode::Key4 => Some(3),
+                            VirtualKeyCode::Key5 => Some(4),
+                            VirtualKeyCode::Key6 => Some(5),
+                            _ => None,
+                        };
+                        if let Some(slot) = slot {
+                            self.hotbar.select(slot);
+                            return true;
+                        }
+                    }
+                    if *state == ElementState::Pressed {
+                        match key {
+                            VirtualKeyCode::PageUp => {
+                                self.set_time_scale(self.time_scale * 2.0);
+                                return true;
+                            }
+                            VirtualKeyCode::PageDown => {
+                                self.set_time_scale(self.time_scale / 2.0);
+                                return true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if *key == VirtualKeyCode::P && *state == ElementState::Pressed {
+                        self.push_command(EngineCommand::SetPaused(!self.paused));
+                        return true;
+                    }
                     if *key == VirtualKeyCode::Escape && self.mouse_capture {
                         self.mouse_capture = false;
-                        window.set_cursor_grab(false).unwrap();
-                        window.set_cursor_visible(true);
-                        println!("Only ungrabbing cursor from window");
+                        window::release_cursor(window);
+                        info!("Only ungrabbing cursor from window");
                         return true;
                     } 
                 }
                 false
             },
             DeviceEvent::MouseWheel { delta, .. } => {
-                self.camera_controller.process_scroll(delta);
+                if self.settings.captures(event) {
+                    return true;
+                }
+                if self.build_mode {
+                    let scroll = match delta {
+                        MouseScrollDelta::LineDelta(_, scroll) => *scroll as f64,
+                        MouseScrollDelta::PixelDelta(position) => position.y,
+                    };
+                    if scroll != 0.0 {
+                        self.hotbar.scroll(if scroll > 0.0 { 1 } else { -1 });
+                    }
+                } else {
+                    self.camera_controller.process_scroll(delta);
+                }
                 true
             }
             DeviceEvent::Button {
@@ -355,8 +1085,12 @@ impl State {
                 true
             }
             DeviceEvent::MouseMotion { delta } => {
-                if self.mouse_capture {
-                    self.camera_controller.process_mouse(delta.0, delta.1);
+                if self.mouse_capture && !self.settings.captures(event) {
+                    // working in logical pixels keeps the sensitivity the same on high-DPI screens
+                    self.camera_controller.process_mouse(delta.0 / self.scale_factor, delta.1 / self.scale_factor);
+                    if self.cursor_grab == window::CursorGrab::Lock {
+                        window::recenter_cursor(window);
+                    }
                 }
                 true
             }
@@ -364,51 +1098,524 @@ impl State {
         }
     }
 
+    // The held keys are released so the camera does not keep moving behind the panel, and
+    // the cursor is given back to point at it
+    pub fn toggle_settings(&mut self, window: &winit::window::Window) {
+        self.settings.toggle();
+        self.camera_controller.stop();
+        if self.settings.open && self.mouse_capture {
+            self.mouse_capture = false;
+            window::release_cursor(window);
+        }
+        info!("Settings panel {}", if self.settings.open { "open" } else { "closed" });
+    }
+
+    pub fn settings_values(&self) -> settings::Settings {
+        settings::Settings {
+            fov: cgmath::Deg::from(self.projection.fov_y()).0,
+            light_color: self.light.color,
+            render_distance: self.chunk_manager.radius,
+            seed: self.chunk.terrain.seed,
+            sea_level: self.chunk.terrain.sea_level,
+            fog: self.fog,
+            wireframe: self.wireframe,
+            ao_strength: self.ao_strength,
+            exposure: self.tone_mapper.as_ref().map(|tone_mapper| tone_mapper.exposure),
+        }
+    }
+
+    // only what changed is applied, the world is not generated again for a new light color
+    pub fn apply_settings(&mut self, before: settings::Settings, after: settings::Settings) {
+        if after.fov != before.fov {
+            self.projection.set_fov_y(cgmath::Deg(after.fov));
+        }
+        // written with the next update
+        self.light.color = after.light_color;
+        if after.render_distance != before.render_distance {
+            self.chunk_manager.set_radius(after.render_distance.clamp(1, terrain::chunk_manager::MAX_VIEW_RADIUS));
+            self.prune_chunk_buffers();
+        }
+        if after.seed != before.seed {
+            self.regenerate(after.seed);
+        }
+        if after.sea_level != before.sea_level {
+            let sea_level = after.sea_level.min(terrain::chunk::CHUNK_HEIGHT);
+            self.set_terrain(terrain::chunk::TerrainConfig { sea_level, ..self.chunk.terrain });
+        }
+        self.fog = after.fog;
+        if after.wireframe != before.wireframe {
+            self.toggle_wireframe();
+        }
+        self.ao_strength = after.ao_strength.clamp(0.0, 1.0);
+        if let (Some(exposure), Some(tone_mapper)) = (after.exposure, &mut self.tone_mapper) {
+            if after.exposure != before.exposure {
+                tone_mapper.set_exposure(&self.queue, exposure);
+            }
+        }
+    }
+
+    fn screen_descriptor(&self) -> egui_wgpu_backend::ScreenDescriptor {
+        egui_wgpu_backend::ScreenDescriptor {
+            physical_width: self.swap_chain_desc.width,
+            physical_height: self.swap_chain_desc.height,
+            scale_factor: self.scale_factor as f32,
+        }
+    }
+
+    // drops the buffers of the chunks the manager let go of
+    fn prune_chunk_buffers(&mut self) {
+        let main = self.chunk.position;
+        let chunks = &self.chunk_manager.chunks;
+        self.chunk_buffers.retain(|position, _| *position == main || chunks.contains_key(position));
+    }
+
+    // the offscreen renderers have none to recreate
+    fn recreate_swap_chain(&mut self) {
+        if let Some(surface) = &self.surface {
+            self.swap_chain = Some(self.device.create_swap_chain(surface, &self.swap_chain_desc));
+        }
+    }
+
+    // goes to the next present mode and recreates the swap chain with it
+    pub fn cycle_present_mode(&mut self) {
+        // wgpu cannot list the modes of the surface yet, it goes back to Fifo itself
+        // when the mode is missing
+        let present_mode = next_present_mode(self.swap_chain_desc.present_mode, &PRESENT_MODES);
+        self.set_present_mode(present_mode);
+    }
+
+    fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        self.swap_chain_desc.present_mode = present_mode;
+        self.recreate_swap_chain();
+        info!("Present mode: {:?}", present_mode);
+    }
+
+    // The frame times are gathered with the vsync off until FRAME_HISTOGRAM_WINDOW went by
+    // or the key is pressed again, the percentiles are then logged
+    pub fn toggle_frame_histogram(&mut self) {
+        match self.frame_histogram {
+            Some(_) => self.finish_frame_histogram(),
+            None => {
+                self.frame_histogram = Some((stats::FrameTimeHistogram::new(), self.swap_chain_desc.present_mode));
+                self.set_present_mode(wgpu::PresentMode::Immediate);
+                info!("Gathering the frame times for {}s", FRAME_HISTOGRAM_WINDOW.as_secs());
+            }
+        }
+    }
+
+    fn finish_frame_histogram(&mut self) {
+        if let Some((histogram, present_mode)) = self.frame_histogram.take() {
+            info!("{}", histogram.summary());
+            self.set_present_mode(present_mode);
+        }
+    }
+
+    // the real frame time, before it is clamped for the update
+    pub fn record_frame(&mut self, dt: std::time::Duration) {
+        self.frame_stats.record(dt);
+        if let Some((histogram, _)) = &mut self.frame_histogram {
+            histogram.record(dt);
+            if histogram.total >= FRAME_HISTOGRAM_WINDOW {
+                self.finish_frame_histogram();
+            }
+        }
+    }
+
+    // switches between the blocky and the smooth terrain
+    pub fn toggle_mesh_mode(&mut self) {
+        self.mesh_mode = match self.mesh_mode {
+            terrain::chunk::MeshMode::Blocky => terrain::chunk::MeshMode::MarchingCubes,
+            terrain::chunk::MeshMode::MarchingCubes => terrain::chunk::MeshMode::Blocky,
+        };
+        self.chunk.dirty = true;
+        self.chunk_manager.mark_dirty();
+    }
+
+    // switches between drawing the main chunk alone and with the chunks around it
+    pub fn toggle_render_mode(&mut self) {
+        self.render_mode = match self.render_mode {
+            RenderMode::SingleChunk => RenderMode::MultiChunk,
+            RenderMode::MultiChunk => RenderMode::SingleChunk,
+        };
+        info!("Render mode: {:?}", self.render_mode);
+    }
+
+    // the sender can be cloned and moved to other threads
+    pub fn command_sender(&self) -> std::sync::mpsc::Sender<EngineCommand> {
+        self.command_sender.clone()
+    }
+
+    // the receiver lives as long as the state, the send cannot fail
+    fn push_command(&self, command: EngineCommand) {
+        self.command_sender.send(command).unwrap();
+    }
+
+    // applies the commands received since the last update, in the order they were sent
+    fn apply_commands(&mut self) {
+        let commands: Vec<EngineCommand> = self.commands.try_iter().collect();
+        for command in commands {
+            match command {
+                EngineCommand::SetBlock { position, block_type } => {
+                    if !command::set_block(&mut self.chunk, position, block_type) {
+                        warn!("Ignoring the block set out of the chunk at {:?}", position);
+                    }
+                }
+                EngineCommand::TeleportCamera { position } => self.camera.position = position,
+                EngineCommand::ShakeCamera { intensity, duration } => self.camera.add_shake(intensity, duration),
+                EngineCommand::SetSeed(seed) => self.regenerate(seed),
+                EngineCommand::RegenerateVisible => self.regenerate_visible(),
+                EngineCommand::ReloadConfig => self.reload_config(),
+                EngineCommand::SetPaused(paused) => {
+                    self.paused = paused;
+                    info!("Simulation {}", if self.paused { "paused" } else { "resumed" });
+                }
+            }
+        }
+    }
+
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+        info!("Time scale: {}", self.time_scale);
+    }
+
+    // Moves the camera back along its view direction until the drawn terrain fits in the
+    // view, the orientation is kept
+    pub fn frame_terrain(&mut self) {
+        let mut chunks = vec![&self.chunk];
+        if self.render_mode == RenderMode::MultiChunk {
+            chunks.extend(self.chunk_manager.chunks.values());
+        }
+        let bounds = chunks.iter()
+            .filter_map(|chunk| chunk.bounds())
+            .reduce(|a, b| a.union(&b));
+
+        if let Some(bounds) = bounds {
+            let radius = bounds.size().magnitude() / 2.0;
+            self.camera.position = Point3::from_vec(bounds.center()) - self.camera.direction() * self.projection.fit_distance(radius);
+        }
+    }
+
+    // builds a new world from another seed, the camera stays where it is
+    pub fn regenerate(&mut self, seed: u32) {
+        self.set_terrain(terrain::chunk::TerrainConfig { seed, ..self.chunk.terrain });
+        info!("Regenerating the world with seed {}", seed);
+    }
+
+    // the whole world is generated again with the new parameters
+    pub fn set_terrain(&mut self, terrain: terrain::chunk::TerrainConfig) {
+        // the new chunk is dirty so it gets re-meshed on the next update
+        self.chunk = terrain::chunk::Chunk::generate(terrain).with_registry(self.block_registry.clone());
+        self.chunk_manager.regenerate(terrain);
+    }
+
+    // Generates again the drawn chunks which were in the view on the last update, with the
+    // current terrain. The others keep their blocks and their meshes.
+    pub fn regenerate_visible(&mut self) {
+        let main = self.chunk.position;
+        let visible: Vec<(i32, i32)> = self.chunk_buffers.iter()
+            .filter(|(position, buffers)| buffers.visible && self.render_mode.draws(**position, main))
+            .map(|(position, _)| *position)
+            .collect();
+
+        let terrain = self.chunk.terrain;
+        let mut count = 0;
+        if visible.contains(&main) {
+            // the new chunk is dirty so it gets re-meshed on the next update
+            self.chunk = terrain::chunk::Chunk::generate(terrain).with_registry(self.block_registry.clone());
+            count += 1;
+        }
+        count += self.chunk_manager.regenerate_chunks(terrain, &visible);
+        info!("Regenerated {} visible chunks", count);
+    }
+
+    pub fn save_session(&self, path: &str) {
+        match session::Session::capture(&self.chunk, &self.camera).save(path) {
+            Ok(_) => info!("Session saved to {}", path),
+            Err(e) => error!("{:?}", e),
+        }
+    }
+
+    pub fn load_session(&mut self, path: &str) {
+        match session::Session::load(path).and_then(|session| session.check_blocks(&self.block_registry).map(|_| session)) {
+            Ok(session) => {
+                // the restored chunk is dirty so it gets re-meshed on the next update
+                let (chunk, camera) = session.restore();
+                self.chunk_manager.regenerate(chunk.terrain);
+                self.chunk = chunk.with_registry(self.block_registry.clone());
+                self.camera = camera.with_up_axis(self.camera.up_axis);
+                info!("Session loaded from {}", path);
+            }
+            Err(e) => error!("{:?}", e),
+        }
+    }
+
+    // The view and the terrain of the config file are applied, the world is only generated
+    // again when its terrain changed. The window and the renderer wait for a restart.
+    pub fn reload_config(&mut self) {
+        let config = match self.config.reload() {
+            Ok(config) => config,
+            Err(e) => {
+                error!("{:?}", e);
+                warn!("Keeping the current config");
+                return;
+            }
+        };
+
+        config.view.apply(&mut self.projection, &mut self.camera_controller);
+        let radius = config.view.view_radius.clamp(1, terrain::chunk_manager::MAX_VIEW_RADIUS);
+        if radius != self.chunk_manager.radius {
+            self.chunk_manager.set_radius(radius);
+            self.prune_chunk_buffers();
+        }
+        if let Some(budget) = config.generation_budget {
+            self.chunk_manager.generation_budget = budget;
+        }
+        self.lod_threshold = config.lod_threshold.unwrap_or(terrain::lod::DEFAULT_LOD_THRESHOLD);
+        // the light goes on from where it is on the new orbit
+        let orbit = light_orbit(&config);
+        self.light_orbit.center = orbit.center;
+        self.light_orbit.radius = orbit.radius;
+        // compared with the file and not the chunk, a loaded scene is kept otherwise
+        if config.terrain != self.config.terrain {
+            self.set_terrain(config.terrain);
+        }
+
+        info!("Config reloaded from {}", config.path.as_deref().unwrap_or_default());
+        self.config = config;
+    }
+
+    pub fn save_config(&mut self, path: &str) {
+        self.config.view = ViewConfig::current(&self.projection, &self.camera_controller, self.chunk_manager.radius);
+        match self.config.save(path) {
+            Ok(_) => info!("Config saved to {}", path),
+            Err(e) => error!("{:?}", e),
+        }
+    }
+
+    // called once before the engine exits
+    pub fn on_exit(&mut self) {
+        if let Some((histogram, _)) = &self.frame_histogram {
+            info!("{}", histogram.summary());
+        }
+        if self.autosave {
+            self.save_session(session::DEFAULT_SESSION_PATH);
+        }
+        if let Some(path) = self.config.path.clone() {
+            self.save_config(&path);
+        }
+        // the submitted work has to be done before the resources get dropped
+        self.device.poll(wgpu::Maintain::Wait);
+        info!("Exiting");
+    }
+
+    // replaces the lines shown by the debug overlay
+    pub fn set_debug_text(&mut self, lines: &[String]) {
+        if self.debug_text == lines {
+            return;
+        }
+        self.debug_text = lines.to_vec();
+        self.text_renderer.set_text(&self.device, lines);
+    }
+
+    // world ray through the cursor, see camera::cursor_ray
+    pub fn cursor_ray(&self, cursor: winit::dpi::PhysicalPosition<f64>) -> (Point3<f32>, Vector3<f32>) {
+        camera::cursor_ray(&self.camera, &self.projection, cursor, self.size)
+    }
+
+    // the blocks are picked at the center of the screen, or under the cursor when it is shown
+    fn picking_ray(&self) -> (Point3<f32>, Vector3<f32>) {
+        match self.cursor_position {
+            Some(cursor) if !self.mouse_capture => self.cursor_ray(cursor),
+            _ => (self.camera.position, self.camera.direction()),
+        }
+    }
+
+    // follows the block picked by the camera, the buffer is only written when it changes
+    fn update_outline(&mut self) {
+        let (origin, direction) = self.picking_ray();
+        let target = self.chunk.raycast(origin, direction, TARGET_DISTANCE);
+        if target == self.outline_target {
+            return;
+        }
+        if let Some(block) = target {
+            let lines = self.chunk.block_outline(block, OUTLINE_INFLATION, OUTLINE_COLOR);
+            self.queue.write_buffer(&self.outline_buffer, 0, bytemuck::cast_slice(&lines));
+        }
+        self.outline_target = target;
+    }
+
+    // puts the selected block against the face the camera looks at
+    pub fn place_block(&mut self) {
+        let (origin, direction) = self.picking_ray();
+        let hit = self.chunk.raycast_with_previous(origin, direction, TARGET_DISTANCE);
+        if let Some((_, [x, y, z])) = hit {
+            if self.chunk.block_at(x, y, z) == Some(terrain::block::BlockType::AIR) {
+                let position = [x as usize, y as usize, z as usize];
+                self.push_command(EngineCommand::SetBlock { position, block_type: self.hotbar.selected() });
+            }
+        }
+    }
+
+    // everything allocated on the GPU by the engine, the swap chain excepted
+    pub fn gpu_memory_bytes(&self) -> u64 {
+        let chunks: u64 = self.chunk_buffers.values().map(|buffers| buffers.memory_bytes()).sum();
+        let uniforms = self.uniform_ring.memory_bytes();
+        let light = self.light_ring.memory_bytes() + self.light_marker.memory_bytes();
+        let hdr = self.tone_mapper.as_ref().map_or(0, |tone_mapper| tone_mapper.memory_bytes());
+        chunks + uniforms + light + hdr + self.depth_texture.memory_bytes + self.atlas_memory_bytes + self.text_renderer.memory_bytes()
+    }
+
+    fn hud_lines(&self) -> Vec<String> {
+        let mut lines = if self.show_debug { self.debug_lines() } else { vec![] };
+        if self.build_mode {
+            lines.push(format!("BLOCK: {}", self.block_registry.get(self.hotbar.selected()).name.to_uppercase()));
+        }
+        lines
+    }
+
+    fn show_overlay(&self) -> bool {
+        self.show_debug || self.build_mode
+    }
+
+    fn debug_lines(&self) -> Vec<String> {
+        let (origin, direction) = self.picking_ray();
+        let target = match self.chunk.raycast(origin, direction, TARGET_DISTANCE) {
+            Some([x, y, z]) => format!("{} {} {} {:?}", x, y, z, self.chunk.blocks[x][y][z].block_type),
+            None => String::from("NONE"),
+        };
+
+        vec![
+            format!("FPS: {:.0}", self.frame_stats.fps()),
+            format!("POS: {:.1} {:.1} {:.1}", self.camera.position.x, self.camera.position.y, self.camera.position.z),
+            format!("CHUNKS: {}/{} QUEUED: {}{}", self.drawn_chunks().filter(|b| b.visible).count(), self.drawn_chunks().count(), self.chunk_manager.pending_count(),
+                if self.frozen_frustum.is_some() { " FROZEN" } else { "" }),
+            format!("GPU: {}", memory::format_bytes(self.gpu_memory_bytes())),
+            format!("TERRAIN DRAWS: {} TRIS: {}", self.draw_stats.terrain.draw_calls, self.draw_stats.terrain.triangles),
+            format!("OVERLAY DRAWS: {} TRIS: {}", self.draw_stats.overlay.draw_calls, self.draw_stats.overlay.triangles),
+            format!("FOV: {:.0}", cgmath::Deg::from(self.projection.fov_y()).0),
+            format!("TIME: X{}", self.time_scale),
+            format!("PRESENT: {:?}", self.swap_chain_desc.present_mode),
+            format!("TARGET: {}", target),
+        ]
+    }
+
+    // draw calls, vertices and triangles of the last rendered frame
+    pub fn draw_stats(&self) -> stats::FrameDrawStats {
+        self.draw_stats
+    }
+
+    pub fn benchmark_finished(&self) -> bool {
+        matches!(&self.benchmark, Some(b) if b.finished())
+    }
+
     // updating loop
     pub fn update(&mut self, dt: std::time::Duration) {
+        self.apply_commands();
+
+        // the meshes are only rebuilt when a block was edited since the last upload
+        self.remesh_chunks();
+
         // updating the camera
-        self.camera_controller.update_camera(&mut self.camera, dt);
+        let previous = self.camera.position;
+        match &mut self.benchmark {
+            Some(benchmark) => benchmark.update_camera(&mut self.camera, dt),
+            None => self.camera_controller.update_camera(&mut self.camera, dt),
+        }
+        if let (Some(walker), None) = (&mut self.walker, &self.benchmark) {
+            let jump = self.camera_controller.amount_up > 0.0;
+            walker.update(&self.chunk, previous, &mut self.camera.position, jump, dt);
+        }
+        self.camera.update(dt);
         self.uniforms
             .update_view_proj(&self.camera, &self.projection);
-        self.queue.write_buffer(
-            &self.uniform_buffer,
-            0,
-            bytemuck::cast_slice(&[self.uniforms]),
-        );
+        self.uniforms.set_ambient_color(self.ambient_color);
+        self.uniforms.set_fog(self.fog);
+        self.uniforms.set_ao_strength(self.ao_strength);
+        self.update_outline();
+        let frustum = camera::Frustum::culling(self.frozen_frustum.as_ref(), &self.camera, &self.projection);
+        for buffers in self.chunk_buffers.values_mut() {
+            buffers.visible = frustum.contains_box(&buffers.bounds);
+        }
+        self.update_lods();
+        // meshed by the next update, like the edited chunks
+        self.chunk_manager.generate_pending(self.camera.position, |space| frustum.contains_box(space));
+        // the waves stop with the other animations
+        let animation_dt = stats::animation_step(dt, self.time_scale, self.paused);
+        self.uniforms.advance_time(animation_dt);
+        self.uniform_ring.advance();
+        self.uniform_ring.write(&self.queue, bytemuck::cast_slice(&[self.uniforms]));
 
-        // Update the light
-        let old_position: cgmath::Vector3<_> = self.light.position.into();
-        self.light.position =
-            (cgmath::Quaternion::from_axis_angle((0.0, 1.0, 0.0).into(), cgmath::Deg(1.0))
-                * old_position)
-                .into();
-        self.queue
-            .write_buffer(&self.light_buffer, 0, bytemuck::cast_slice(&[self.light]));
+        if self.show_overlay() {
+            let lines = self.hud_lines();
+            self.set_debug_text(&lines);
+        }
+
+        if self.settings.open {
+            let before = self.settings_values();
+            let mut after = before;
+            let paint_jobs = self.settings.run(dt, &mut after);
+            self.apply_settings(before, after);
+            let screen = self.screen_descriptor();
+            self.panel_renderer.prepare(&self.device, &self.queue, &self.settings.texture(), paint_jobs, &screen);
+        }
+
+        // Update the light, the new copy of the ring is written even when it stands still
+        self.light_orbit.update(animation_dt);
+        self.light.set_position(self.light_orbit.position().into());
+        self.light_ring.advance();
+        self.light_ring.write(&self.queue, bytemuck::cast_slice(&[self.light]));
     }
 
     pub fn render(&mut self) -> Result<(), wgpu::SwapChainError> {
-        // full rendering process
-        let frame = self.swap_chain.get_current_frame()?.output;
+        // full rendering process, without a swap chain the frame goes to a texture of its own
+        let (frame, offscreen);
+        let view = match &self.swap_chain {
+            Some(swap_chain) => {
+                frame = swap_chain.get_current_frame()?.output;
+                &frame.view
+            }
+            None => {
+                offscreen = self.create_frame_texture("Offscreen Frame").create_view(&wgpu::TextureViewDescriptor::default());
+                &offscreen
+            }
+        };
 
         // commands encoder to send to the gpu
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Render Encoder"),
+            label: Some(&self.debug_labels.label("Render Encoder", self.frame_index)),
         });
+        self.draw_stats = self.encode_frame(&mut encoder, view);
+        // over the tone mapped scene and the text, it is left out of the captured frames
+        if self.settings.open {
+            let screen = self.screen_descriptor();
+            self.panel_renderer.draw(&mut encoder, view, &screen);
+        }
+
+        // send the command encoded to the queue
+        // submit will accept anything that implements IntoIter
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.frame_index += 1;
+
+        Ok(())
+    }
 
+    // the scene then the overlay, drawn into the given view
+    fn encode_frame(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) -> stats::FrameDrawStats {
+        let mut draw_stats = stats::FrameDrawStats::default();
+        let scene_view = match &self.tone_mapper {
+            Some(tone_mapper) => &tone_mapper.target.view,
+            None => view,
+        };
         // creating a render pass
+        let chunks = || format!("{} chunks", self.drawn_chunks().filter(|b| b.visible).count());
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Render Pass"),
+            label: Some(&self.debug_labels.detailed_label("Render Pass", self.frame_index, chunks)),
             color_attachments: &[
                 wgpu::RenderPassColorAttachmentDescriptor {
-                    attachment: &frame.view,
+                    attachment: scene_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.078,
-                            g: 0.078,
-                            b: 0.121,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(clear_color(self.ambient_color)),
                         store: true,
                     }
                 }
@@ -423,22 +1630,286 @@ impl State {
             }),
         });
 
-        // rendering things
-        render_pass.set_pipeline(&self.render_pipeline);
-        render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-        render_pass.draw_indexed(0..self.num_index, 0, 0..1);
+        if self.point_mode {
+            render_pass.set_pipeline(&self.point_render_pipeline);
+            render_pass.set_bind_group(0, self.uniform_ring.bind_group(), &[]);
+            for buffers in self.drawn_chunks().filter(|b| b.visible) {
+                render_pass.set_vertex_buffer(0, buffers.vertex_buffer.buffer.slice(..));
+                render_pass.draw(0..buffers.num_vertices, 0..1);
+                draw_stats.terrain.record_points(buffers.num_vertices);
+                if buffers.num_water_vertices > 0 {
+                    render_pass.set_vertex_buffer(0, buffers.water_vertex_buffer.buffer.slice(..));
+                    render_pass.draw(0..buffers.num_water_vertices, 0..1);
+                    draw_stats.terrain.record_points(buffers.num_water_vertices);
+                }
+            }
+        } else {
+            // rendering things
+            render_pass.set_pipeline(&self.render_pipeline);
+            for (index, &slot) in BLOCK_PIPELINE_GROUPS.iter().enumerate() {
+                render_pass.set_bind_group(index as u32, self.bind_group(slot), &[]);
+            }
+            for buffers in self.drawn_chunks().filter(|b| b.visible) {
+                render_pass.set_vertex_buffer(0, buffers.vertex_buffer.buffer.slice(..));
+                render_pass.set_vertex_buffer(1, buffers.tint_buffer.buffer.slice(..));
+                render_pass.set_index_buffer(buffers.index_buffer.buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..buffers.num_index, 0, 0..1);
+                draw_stats.terrain.record(buffers.num_index);
+            }
+
+            // render water
+            render_pass.set_pipeline(&self.water_render_pipeline);
+            for buffers in self.drawn_chunks().filter(|b| b.visible && b.num_water_index > 0) {
+                render_pass.set_vertex_buffer(0, buffers.water_vertex_buffer.buffer.slice(..));
+                render_pass.set_vertex_buffer(1, buffers.water_tint_buffer.buffer.slice(..));
+                render_pass.set_index_buffer(buffers.water_index_buffer.buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..buffers.num_water_index, 0, 0..1);
+                draw_stats.terrain.record(buffers.num_water_index);
+            }
+
+            // last of the blocks, the glass is blended over everything behind it
+            render_pass.set_pipeline(&self.glass_render_pipeline);
+            render_pass.set_blend_color(wgpu::Color { r: GLASS_OPACITY, g: GLASS_OPACITY, b: GLASS_OPACITY, a: GLASS_OPACITY });
+            for buffers in self.drawn_chunks().filter(|b| b.visible && b.num_glass_index > 0) {
+                render_pass.set_vertex_buffer(0, buffers.glass_vertex_buffer.buffer.slice(..));
+                render_pass.set_vertex_buffer(1, buffers.glass_tint_buffer.buffer.slice(..));
+                render_pass.set_index_buffer(buffers.glass_index_buffer.buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..buffers.num_glass_index, 0, 0..1);
+                draw_stats.terrain.record(buffers.num_glass_index);
+            }
+
+            // the edges of the terrain, the depth bias keeps them over the faces
+            if let (true, Some(pipeline)) = (self.wireframe, &self.wireframe_render_pipeline) {
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_bind_group(0, self.uniform_ring.bind_group(), &[]);
+                for buffers in self.drawn_chunks().filter(|b| b.visible) {
+                    render_pass.set_vertex_buffer(0, buffers.vertex_buffer.buffer.slice(..));
+                    render_pass.set_index_buffer(buffers.index_buffer.buffer.slice(..), wgpu::IndexFormat::Uint16);
+                    render_pass.draw_indexed(0..buffers.num_index, 0, 0..1);
+                    draw_stats.overlay.record(buffers.num_index);
+                }
+            }
+        }
 
-        // render lightt
+        if self.outline_target.is_some() {
+            render_pass.set_pipeline(&self.outline_render_pipeline);
+            render_pass.set_bind_group(0, self.uniform_ring.bind_group(), &[]);
+            render_pass.set_vertex_buffer(0, self.outline_buffer.slice(..));
+            render_pass.draw(0..terrain::chunk::OUTLINE_VERTICES as u32, 0..1);
+            draw_stats.overlay.record_lines(terrain::chunk::OUTLINE_VERTICES as u32);
+        }
+
+        if let Some((buffer, num_vertices)) = &self.normal_lines {
+            render_pass.set_pipeline(&self.line_render_pipeline);
+            render_pass.set_bind_group(0, self.uniform_ring.bind_group(), &[]);
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..*num_vertices, 0..1);
+            draw_stats.overlay.record_lines(*num_vertices);
+        }
+
+        // render the light, its pipeline reads the light from the group 1 instead of the atlas
+        render_pass.set_pipeline(&self.light_render_pipeline);
+        for (index, &slot) in LIGHT_PIPELINE_GROUPS.iter().enumerate() {
+            render_pass.set_bind_group(index as u32, self.bind_group(slot), &[]);
+        }
+        render_pass.set_vertex_buffer(0, self.light_marker.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.light_marker.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..self.light_marker.num_index, 0, 0..1);
+        draw_stats.overlay.record(self.light_marker.num_index);
 
         // we need to drop the render pass in order to avoid a memory leak
         drop(render_pass); // the commands has already be sent to the encoder
-    
-        // send the command encoded to the queue
-        // submit will accept anything that implements IntoIter
+
+        if let Some(tone_mapper) = &self.tone_mapper {
+            tone_mapper.draw(encoder, view, &self.debug_labels.label("Tone Map Pass", self.frame_index));
+            draw_stats.overlay.record(3);
+        }
+
+        // the overlay is drawn over the finished scene
+        if self.show_overlay() {
+            let mut text_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(&self.debug_labels.label("Text Pass", self.frame_index)),
+                color_attachments: &[
+                    wgpu::RenderPassColorAttachmentDescriptor {
+                        attachment: view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: true,
+                        }
+                    }
+                ],
+                depth_stencil_attachment: None,
+            });
+            // the scene around the text is left as is
+            let surface = [self.swap_chain_desc.width, self.swap_chain_desc.height];
+            ScissorRect::corner(Corner::TopLeft, self.text_renderer.size(), 0, surface).apply(&mut text_pass);
+            self.text_renderer.draw(&mut text_pass);
+            drop(text_pass);
+            if self.text_renderer.num_index() > 0 {
+                draw_stats.overlay.record(self.text_renderer.num_index());
+            }
+        }
+        draw_stats
+    }
+
+    // Renders a frame into an offscreen texture and reads it back, the swap chain is left
+    // untouched so it also works for the tests and the screenshots
+    pub fn capture_frame(&self) -> anyhow::Result<image::RgbaImage> {
+        let (width, height) = (self.swap_chain_desc.width, self.swap_chain_desc.height);
+        let format = self.swap_chain_desc.format;
+        let size = wgpu::Extent3d { width, height, depth: 1 };
+        let texture = self.create_frame_texture("Capture Texture");
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // the copied rows have to be aligned, the padding is dropped when reading back
+        let unpadded_bytes_per_row = 4 * width;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row + (align - unpadded_bytes_per_row % align) % align;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::COPY_DST | wgpu::BufferUsage::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some(&self.debug_labels.label("Capture Encoder", self.frame_index)),
+        });
+        self.encode_frame(&mut encoder, &view);
+        encoder.copy_texture_to_buffer(
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                layout: wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: padded_bytes_per_row,
+                    rows_per_image: height,
+                },
+            },
+            size,
+        );
         self.queue.submit(std::iter::once(encoder.finish()));
-    
-        Ok(())
+
+        let slice = buffer.slice(..);
+        let mapping = slice.map_async(wgpu::MapMode::Read);
+        self.device.poll(wgpu::Maintain::Wait);
+        block_on(mapping).context("Unable to read the captured frame back")?;
+
+        let bgra = matches!(format, wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb);
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_bytes_per_row as usize) {
+                for texel in row[..unpadded_bytes_per_row as usize].chunks(4) {
+                    if bgra {
+                        pixels.extend_from_slice(&[texel[2], texel[1], texel[0], texel[3]]);
+                    } else {
+                        pixels.extend_from_slice(texel);
+                    }
+                }
+            }
+        }
+        buffer.unmap();
+
+        image::RgbaImage::from_raw(width, height, pixels).context("The captured frame does not match its size")
+    }
+
+    // a target of the size and format of the swap chain frames, which can be copied from
+    fn create_frame_texture(&self, label: &str) -> wgpu::Texture {
+        self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d { width: self.swap_chain_desc.width, height: self.swap_chain_desc.height, depth: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.swap_chain_desc.format,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+        })
+    }
+
+    pub fn save_screenshot(&self, path: &str) {
+        match self.capture_frame().and_then(|image| image.save(path).with_context(|| format!("Unable to write {}", path))) {
+            Ok(_) => info!("Screenshot saved to {}", path),
+            Err(e) => error!("{:?}", e),
+        }
+    }
+}
+
+// first mode after current in PRESENT_MODES that is supported, current when there is none
+pub fn next_present_mode(current: wgpu::PresentMode, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+    let start = PRESENT_MODES.iter().position(|&mode| mode == current).unwrap_or(0);
+    (1..=PRESENT_MODES.len())
+        .map(|offset| PRESENT_MODES[(start + offset) % PRESENT_MODES.len()])
+        .find(|mode| supported.contains(mode))
+        .unwrap_or(current)
+}
+
+// the orbit of the light given by the config, with the defaults for what it leaves out
+pub fn light_orbit(config: &Config) -> light::LightOrbit {
+    let mut orbit = light::LightOrbit::new(
+        config.light_center.unwrap_or(light::DEFAULT_ORBIT_CENTER).into(),
+        config.light_radius.unwrap_or(light::DEFAULT_ORBIT_RADIUS),
+    );
+    orbit.up_axis = config.up_axis;
+    orbit
+}
+
+// the sky is the same color as the fog, both come from the ambient color
+pub fn clear_color(ambient_color: [f32; 3]) -> wgpu::Color {
+    wgpu::Color {
+        r: ambient_color[0] as f64,
+        g: ambient_color[1] as f64,
+        b: ambient_color[2] as f64,
+        a: 1.0,
     }
-}
\ No newline at end of file
+}
+
+// the faces are clockwise, the culling is left out by the points and the lines
+pub fn primitive_state(topology: wgpu::PrimitiveTopology, polygon_mode: wgpu::PolygonMode) -> wgpu::PrimitiveState {
+    wgpu::PrimitiveState {
+        topology,
+        strip_index_format: None,
+        front_face: wgpu::FrontFace::Cw,
+        cull_mode: wgpu::CullMode::Back,
+        polygon_mode,
+    }
+}
+
+// depth test of the scene pipelines, the format has to be the one of the depth texture
+pub fn depth_stencil_state(format: wgpu::TextureFormat, bias: wgpu::DepthBiasState) -> wgpu::DepthStencilState {
+    wgpu::DepthStencilState {
+        format,
+        depth_write_enabled: true,
+        depth_compare: wgpu::CompareFunction::Less,
+        stencil: wgpu::StencilState::default(),
+        bias,
+        // Setting this to true requires Features::DEPTH_CLAMPING
+        clamp_depth: false,
+    }
+}
+
+// the scene goes straight to the swap chain without a HDR format to tone map from
+pub fn scene_format(hdr_format: Option<wgpu::TextureFormat>, swap_chain_format: wgpu::TextureFormat) -> wgpu::TextureFormat {
+    hdr_format.unwrap_or(swap_chain_format)
+}
+
+// The preferred format of the surface when the engine can draw to it, the fallback otherwise.
+// wgpu 0.7 always gives a format, the None is kept for the versions which may not.
+pub fn swap_chain_format(preferred: Option<wgpu::TextureFormat>) -> wgpu::TextureFormat {
+    match preferred {
+        Some(format) if SWAP_CHAIN_FORMATS.contains(&format) => format,
+        Some(format) => {
+            warn!("Unsupported swap chain format {:?}, falling back to {:?}", format, FALLBACK_SWAP_CHAIN_FORMAT);
+            FALLBACK_SWAP_CHAIN_FORMAT
+        }
+        None => {
+            warn!("No preferred swap chain format, falling back to {:?}", FALLBACK_SWAP_CHAIN_FORMAT);
+            FALLBACK_SWAP_CHAIN_FORMAT
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+// longest step given to the update, a stalled frame would teleport everything otherwise
+pub const DEFAULT_MAX_FRAME_TIME: Duration = Duration::from_millis(100);
+// the fps is averaged over this period so it stays readable
+const FPS_PERIOD: Duration = Duration::from_millis(500);
+
+// Frame time statistics gathered since the start
+pub struct FrameStats {
+    pub frames: u32,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    fps: f32,
+    period_frames: u32,
+    period_elapsed: Duration,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        FrameStats::new()
+    }
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        FrameStats {
+            frames: 0,
+            total: Duration::from_secs(0),
+            min: Duration::from_secs(u64::MAX),
+            max: Duration::from_secs(0),
+            fps: 0.0,
+            period_frames: 0,
+            period_elapsed: Duration::from_secs(0),
+        }
+    }
+
+    pub fn record(&mut self, dt: Duration) {
+        self.frames += 1;
+        self.total += dt;
+        self.min = self.min.min(dt);
+        self.max = self.max.max(dt);
+
+        self.period_frames += 1;
+        self.period_elapsed += dt;
+        if self.period_elapsed >= FPS_PERIOD {
+            self.fps = self.period_frames as f32 / self.period_elapsed.as_secs_f32();
+            self.period_frames = 0;
+            self.period_elapsed = Duration::from_secs(0);
+        }
+    }
+
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.frames == 0 {
+            return Duration::from_secs(0);
+        }
+        self.total / self.frames
+    }
+}
+
+// width of the buckets of the frame time histogram, and the longest time they cover
+pub const HISTOGRAM_BUCKET: Duration = Duration::from_micros(100);
+pub const HISTOGRAM_RANGE: Duration = Duration::from_millis(100);
+
+// Frame times counted in buckets, the percentiles are read from them without keeping
+// every frame. The longer frames all land in the last bucket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameTimeHistogram {
+    counts: Vec<u32>,
+    pub frames: u32,
+    pub total: Duration,
+}
+
+impl Default for FrameTimeHistogram {
+    fn default() -> Self {
+        FrameTimeHistogram::new()
+    }
+}
+
+impl FrameTimeHistogram {
+    pub fn new() -> Self {
+        let buckets = (HISTOGRAM_RANGE.as_micros() / HISTOGRAM_BUCKET.as_micros()) as usize;
+        FrameTimeHistogram {
+            counts: vec![0; buckets + 1],
+            frames: 0,
+            total: Duration::from_secs(0),
+        }
+    }
+
+    pub fn record(&mut self, dt: Duration) {
+        let bucket = ((dt.as_micros() / HISTOGRAM_BUCKET.as_micros()) as usize).min(self.counts.len() - 1);
+        self.counts[bucket] += 1;
+        self.frames += 1;
+        self.total += dt;
+    }
+
+    // Upper bound of the bucket holding the frame at that rank, so the time is at most
+    // one bucket too long. p goes from 0 to 100, zero without frames.
+    pub fn percentile(&self, p: f32) -> Duration {
+        if self.frames == 0 {
+            return Duration::from_secs(0);
+        }
+        let rank = ((p.clamp(0.0, 100.0) / 100.0 * self.frames as f32).ceil() as u32).max(1);
+        let mut seen = 0;
+        for (bucket, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return HISTOGRAM_BUCKET * (bucket as u32 + 1);
+            }
+        }
+        HISTOGRAM_BUCKET * self.counts.len() as u32
+    }
+
+    pub fn summary(&self) -> String {
+        let ms = |p: f32| self.percentile(p).as_secs_f32() * 1000.0;
+        format!(
+            "Frame times over {} frames: p50 {:.1}ms, p95 {:.1}ms, p99 {:.1}ms",
+            self.frames, ms(50.0), ms(95.0), ms(99.0),
+        )
+    }
+}
+
+// Counts the frames drawn until the limit given with --frames
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameLimit {
+    pub limit: u32,
+    pub drawn: u32,
+}
+
+impl FrameLimit {
+    pub fn new(limit: u32) -> Self {
+        FrameLimit { limit, drawn: 0 }
+    }
+
+    // true once the last frame was drawn
+    pub fn record(&mut self) -> bool {
+        self.drawn = (self.drawn + 1).min(self.limit);
+        self.finished()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.drawn >= self.limit
+    }
+}
+
+// the step given with --max-frame-time, in seconds, or the default one
+pub fn max_frame_time(seconds: Option<f32>) -> Duration {
+    seconds.map(Duration::from_secs_f32).unwrap_or(DEFAULT_MAX_FRAME_TIME)
+}
+
+// the frame stats keep the real frame time, only the simulation is slowed down
+pub fn clamp_frame_time(dt: Duration, max: Duration) -> Duration {
+    dt.min(max)
+}
+
+// the step given to the animations, scaled by PageUp and PageDown and none while paused
+pub fn animation_step(dt: Duration, time_scale: f32, paused: bool) -> Duration {
+    if paused {
+        Duration::from_secs(0)
+    } else {
+        dt.mul_f32(time_scale)
+    }
+}
+
+// What the last frame submitted to the GPU, every draw is indexed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DrawStats {
+    pub draw_calls: u32,
+    // each index runs the vertex shader once
+    pub vertices: u32,
+    pub triangles: u32,
+}
+
+impl DrawStats {
+    pub fn record(&mut self, num_index: u32) {
+        self.draw_calls += 1;
+        self.vertices += num_index;
+        self.triangles += num_index / 3;
+    }
+
+    // two vertices per line, none of them makes a triangle
+    pub fn record_lines(&mut self, num_vertices: u32) {
+        self.draw_calls += 1;
+        self.vertices += num_vertices;
+    }
+
+    // one vertex per point
+    pub fn record_points(&mut self, num_vertices: u32) {
+        self.draw_calls += 1;
+        self.vertices += num_vertices;
+    }
+}
+
+impl std::ops::Add for DrawStats {
+    type Output = DrawStats;
+
+    fn add(self, other: DrawStats) -> DrawStats {
+        DrawStats {
+            draw_calls: self.draw_calls + other.draw_calls,
+            vertices: self.vertices + other.vertices,
+            triangles: self.triangles + other.triangles,
+        }
+    }
+}
+
+// The draws of a frame, the chunks apart from what is drawn over them: the wireframe,
+// the outline, the normal lines, the light marker, the tone map and the text
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameDrawStats {
+    pub terrain: DrawStats,
+    pub overlay: DrawStats,
+}
+
+impl FrameDrawStats {
+    pub fn total(&self) -> DrawStats {
+        self.terrain + self.overlay
+    }
+}
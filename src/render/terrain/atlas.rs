@@ -0,0 +1,79 @@
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use super::block::{Faces, HALF_BLOCK_SIZE};
+use super::noise::hash_rng;
+
+// Procedural textures of the blocks, square tiles in a single row. The first ones have
+// the index of the builtin block types, the extra faces come after them, and the blocks
+// pick theirs in their registry definition. They only hold a brightness that darkens the
+// block colors a bit.
+pub const TILE_SIZE: u32 = 16;
+pub const TILE_COUNT: u32 = 10;
+// white, the tile of the air and of the blocks without tiles of their own
+pub const PLAIN_TILE: u32 = 0;
+// the sides of the grass, the top rows hold the grass hanging over the dirt
+pub const GRASS_SIDE_TILE: u32 = 9;
+const GRASS_FRINGE_ROWS: u32 = 4;
+// the plain material samples the white tile of the air, anywhere in it
+pub const PLAIN_TEX_COORDS: [f32; 2] = [0.5, 0.5];
+// lowest brightness of a texel
+const DETAIL_DARKEST: f32 = 0.8;
+
+// Tiles of the faces of a block type, the front, back, left and right faces are sides
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockTextures {
+    pub top: u32,
+    pub bottom: u32,
+    pub side: u32,
+}
+
+impl BlockTextures {
+    // the same tile on every face
+    pub const fn all(tile: u32) -> Self {
+        BlockTextures { top: tile, bottom: tile, side: tile }
+    }
+
+    pub fn tile(&self, face: Faces) -> u32 {
+        match face {
+            Faces::TOP => self.top,
+            Faces::BOTTOM => self.bottom,
+            _ => self.side,
+        }
+    }
+}
+
+// Tex coords of a face in blocks, from its position in the chunk. They go on from one
+// block to the next so the corners shared by coplanar faces are the same and can be
+// deduplicated, simple.frag wraps them into the tile of the face.
+pub fn face_tex_coords(face: Faces, position: [f32; 3]) -> [f32; 2] {
+    let block = position.map(|p| p / (2.0 * HALF_BLOCK_SIZE) + 0.5);
+    // the image rows go down, the sides are flipped to stay upright
+    match face {
+        Faces::TOP | Faces::BOTTOM => [block[0], block[2]],
+        Faces::FRONT | Faces::BACK => [block[0], 1.0 - block[1]],
+        Faces::LEFT | Faces::RIGHT => [block[2], 1.0 - block[1]],
+    }
+}
+
+// Place of the wrapped tex coords in the atlas, the same as simple.frag. They are kept half
+// a texel away from the border of the tile so the filtering does not reach into the next one.
+pub fn tile_tex_coords(tile: u32, [u, v]: [f32; 2]) -> [f32; 2] {
+    let inset = 0.5 / TILE_SIZE as f32;
+    let u = inset + u.rem_euclid(1.0) * (1.0 - 2.0 * inset);
+    let v = inset + v.rem_euclid(1.0) * (1.0 - 2.0 * inset);
+    [(tile as f32 + u) / TILE_COUNT as f32, v]
+}
+
+pub fn create_atlas_image() -> RgbaImage {
+    RgbaImage::from_fn(TILE_SIZE * TILE_COUNT, TILE_SIZE, |x, y| {
+        let tile = x / TILE_SIZE;
+        if tile == PLAIN_TILE {
+            return Rgba([255, 255, 255, 255]);
+        }
+        let noise = hash_rng(tile, (x % TILE_SIZE) as i32, y as i32, 0);
+        // the fringe stays bright, under the darker dirt
+        let darkest = if tile == GRASS_SIDE_TILE && y < GRASS_FRINGE_ROWS { 1.0 - (1.0 - DETAIL_DARKEST) / 2.0 } else { DETAIL_DARKEST };
+        let value = ((darkest + (1.0 - darkest) * noise) * 255.0) as u8;
+        Rgba([value, value, value, 255])
+    })
+}
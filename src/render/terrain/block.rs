@@ -1,9 +1,62 @@
 pub const HALF_BLOCK_SIZE: f32 = 0.25;
 
-#[derive(Clone, Copy, std::cmp::PartialEq)]
-pub enum BlockType {
-    AIR = 0,
-    STONE = 1,
+// Fixed directional ambient baked in the vertex colors, as if lit from above
+pub const TOP_BRIGHTNESS: f32 = 1.0;
+pub const FRONT_BACK_BRIGHTNESS: f32 = 0.8;
+pub const LEFT_RIGHT_BRIGHTNESS: f32 = 0.65;
+pub const BOTTOM_BRIGHTNESS: f32 = 0.5;
+
+// Id of a block in the BlockRegistry, its behavior and looks are read from its definition.
+// The engine generates the ones below, a file of definitions can add others after them.
+#[derive(Clone, Copy, Debug, std::cmp::PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct BlockType(pub u32);
+
+impl BlockType {
+    pub const AIR: BlockType = BlockType(0);
+    pub const STONE: BlockType = BlockType(1);
+    pub const DIRT: BlockType = BlockType(2);
+    pub const GRASS: BlockType = BlockType(3);
+    pub const WATER: BlockType = BlockType(4);
+    // found in veins deep in the stone
+    pub const COAL: BlockType = BlockType(5);
+    pub const IRON: BlockType = BlockType(6);
+    // lights the blocks around it
+    pub const LAMP: BlockType = BlockType(7);
+    // solid, the blocks behind it can be seen through
+    pub const GLASS: BlockType = BlockType(8);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Faces {
+    FRONT = 0,
+    BACK = 1,
+    TOP = 2,
+    BOTTOM = 3,
+    LEFT = 4,
+    RIGHT = 5,
+}
+
+impl Faces {
+    pub fn brightness(&self) -> f32 {
+        match self {
+            Faces::TOP => TOP_BRIGHTNESS,
+            Faces::FRONT | Faces::BACK => FRONT_BACK_BRIGHTNESS,
+            Faces::LEFT | Faces::RIGHT => LEFT_RIGHT_BRIGHTNESS,
+            Faces::BOTTOM => BOTTOM_BRIGHTNESS,
+        }
+    }
+
+    pub fn normal(&self) -> [f32; 3] {
+        match self {
+            Faces::FRONT => [0.0, 0.0, -1.0],
+            Faces::BACK => [0.0, 0.0, 1.0],
+            Faces::TOP => [0.0, 1.0, 0.0],
+            Faces::BOTTOM => [0.0, -1.0, 0.0],
+            Faces::LEFT => [-1.0, 0.0, 0.0],
+            Faces::RIGHT => [1.0, 0.0, 0.0],
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -12,6 +65,12 @@ pub struct Block {
     pub block_type: BlockType,
 }
 
+impl Default for Block {
+    fn default() -> Self {
+        Block::new()
+    }
+}
+
 impl Block {
     pub fn new() -> Self {
         Block {
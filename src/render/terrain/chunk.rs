@@ -1,134 +1,707 @@
-use crate::render::vertex::ColorVertex;
-use super::block::{Block, HALF_BLOCK_SIZE, BlockType};
+use std::sync::Arc;
+use cgmath::*;
+use log::info;
+use rayon::prelude::*;
+use crate::render::{material, vertex::ColorVertex};
+use crate::render::math::Aabb;
+use super::block::{Block, HALF_BLOCK_SIZE, BlockType, Faces};
 use super::noise;
+use super::ore;
+use super::lighting::{self, LightMap};
+use super::lod;
+use super::registry::{BlockMesh, BlockRegistry};
+use super::atlas;
+use super::mesh::{self, Mesh, MeshBuilder};
+use super::marching_cubes::{self, DensityField};
 
 pub const CHUNK_WIDTH: usize = 16;
 pub const CHUNK_HEIGHT: usize = 32;
+pub const DEFAULT_SEED: u32 = 1337;
+// one noise period every 16 blocks
+pub const DEFAULT_FREQUENCY: f64 = 1.0 / 16.0;
+pub const DEFAULT_AMPLITUDE: f64 = CHUNK_HEIGHT as f64;
+// layers of dirt under the grass before reaching the stone
+const DIRT_DEPTH: f64 = 4.0;
+// brightness of a vertex with 0 to 3 solid blocks around it, the shader scales it by ao_strength
+pub const AO_BRIGHTNESS: [f32; 4] = [0.45, 0.65, 0.82, 1.0];
+// parameters of the 64 bits FNV-1a hash, see Chunk::content_hash
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+// keeps the color jitter apart from the other uses of the seed
+const JITTER_SEED: u32 = 0x7157_C010;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MeshMode {
+    // one cube per block
+    Blocky,
+    // smooth surface going through the blocks
+    MarchingCubes,
+}
+
+// Parameters of the terrain generation
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TerrainConfig {
+    pub seed: u32,
+    // the higher the more jagged the terrain
+    pub frequency: f64,
+    // height of the terrain peaks, in blocks
+    pub amplitude: f64,
+    // the sessions saved before it existed used OpenSimplex
+    #[serde(default)]
+    pub noise: noise::NoiseKind,
+    // the air under this height is filled with water, 0 leaves the terrain dry
+    #[serde(default)]
+    pub sea_level: usize,
+    // brightness of each block moved by up to this part of its color, 0 keeps them flat
+    #[serde(default)]
+    pub color_jitter: f32,
+}
+
+impl Default for TerrainConfig {
+    fn default() -> Self {
+        TerrainConfig {
+            seed: DEFAULT_SEED,
+            frequency: DEFAULT_FREQUENCY,
+            amplitude: DEFAULT_AMPLITUDE,
+            noise: noise::NoiseKind::OpenSimplex,
+            sea_level: 0,
+            color_jitter: 0.0,
+        }
+    }
+}
+
+// Chunks stacked on top and under a chunk, their blocks hide the faces on its boundaries
+#[derive(Default)]
+pub struct VerticalNeighbours<'a> {
+    pub above: Option<&'a Chunk>,
+    pub below: Option<&'a Chunk>,
+}
 
 pub struct Chunk {
     pub width: usize,
     pub height: usize,
     pub blocks: [[[Block; CHUNK_WIDTH]; CHUNK_HEIGHT]; CHUNK_WIDTH],
+    // generating a chunk from the same config gives back the same blocks
+    pub terrain: TerrainConfig,
+    // the mesh no longer matches the blocks and has to be rebuilt
+    pub dirty: bool,
+    // nothing can be seen under the chunk, so its bottom faces are never drawn
+    pub world_floor: bool,
+    // in chunks along x and z, the main chunk is at (0, 0)
+    pub position: (i32, i32),
+    // level of detail of the blocky mesh, see lod::select_lod
+    pub lod: u32,
+    // definitions of the blocks, shared by the chunks of the world
+    pub registry: Arc<BlockRegistry>,
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Chunk::new()
+    }
 }
 
 impl Chunk {
     pub fn new() -> Self {
-        let noise_gen = noise::NoiseGenerator::from_seed(1337);
+        Chunk::generate(TerrainConfig::default())
+    }
+
+    pub fn generate(terrain: TerrainConfig) -> Self {
+        Chunk::generate_at(terrain, (0, 0))
+    }
+
+    // The noise is sampled at the world coordinates of the blocks, so the terrain goes
+    // on across the chunk boundaries instead of repeating in every chunk
+    pub fn generate_at(terrain: TerrainConfig, position: (i32, i32)) -> Self {
+        let noise_gen = noise::NoiseGenerator::from_seed(terrain.seed, terrain.noise);
+        Chunk::generate_with(&noise_gen, terrain, position)
+    }
+
+    // Terrain of the default config shaped by the given generator instead of one built
+    // from the seed, for the tests needing a known noise. The ores still use the seed.
+    pub fn with_noise(noise: noise::NoiseGenerator) -> Self {
+        Chunk::generate_with(&noise, TerrainConfig::default(), (0, 0))
+    }
+
+    fn generate_with(noise_gen: &noise::NoiseGenerator, terrain: TerrainConfig, position: (i32, i32)) -> Self {
+        let origin_x = position.0 * CHUNK_WIDTH as i32;
+        let origin_z = position.1 * CHUNK_WIDTH as i32;
         let mut blocks = [[[Block::new(); CHUNK_WIDTH]; CHUNK_HEIGHT]; CHUNK_WIDTH];
         for x in 0..CHUNK_WIDTH {
             for z in 0..CHUNK_WIDTH {
-                let noise_value = noise_gen.get(x as f64 / 16.0, z as f64 / 16.0) * CHUNK_HEIGHT as f64;
+                let world_x = (origin_x + x as i32) as f64;
+                let world_z = (origin_z + z as i32) as f64;
+                let noise_value = noise_gen.get(world_x * terrain.frequency, world_z * terrain.frequency) * terrain.amplitude;
                 for y in 0..CHUNK_HEIGHT {
-                    blocks[x][y][z].block_type = if y as f64 > noise_value {
+                    let depth = noise_value - y as f64;
+                    blocks[x][y][z].block_type = if depth < 0.0 && y < terrain.sea_level {
+                        BlockType::WATER
+                    } else if depth < 0.0 {
                         BlockType::AIR
+                    } else if depth < 1.0 {
+                        BlockType::GRASS
+                    } else if depth < DIRT_DEPTH {
+                        BlockType::DIRT
                     } else { BlockType::STONE };
                 }
             }
         }
-        Chunk {
+        let mut chunk = Chunk {
             width: CHUNK_WIDTH,
             height: CHUNK_HEIGHT,
             blocks,
+            terrain,
+            dirty: true,
+            world_floor: true,
+            position,
+            lod: 0,
+            registry: Arc::new(BlockRegistry::builtin()),
+        };
+        chunk.place_ores(&ore::DEFAULT_ORES);
+        chunk
+    }
+
+    // where the chunk blocks start in the world
+    pub fn world_offset(&self) -> Vector3<f32> {
+        let block_size = 2.0 * HALF_BLOCK_SIZE;
+        Vector3::new(
+            (self.position.0 * CHUNK_WIDTH as i32) as f32 * block_size,
+            0.0,
+            (self.position.1 * CHUNK_WIDTH as i32) as f32 * block_size,
+        )
+    }
+
+    // Every block with its position in the world, in blocks. The chunks of a ChunkManager
+    // are moved by their position so their blocks never share coordinates.
+    pub fn iter_blocks(&self) -> impl Iterator<Item = (i32, i32, i32, BlockType)> + '_ {
+        let origin_x = self.position.0 * CHUNK_WIDTH as i32;
+        let origin_z = self.position.1 * CHUNK_WIDTH as i32;
+        self.blocks.iter().enumerate().flat_map(move |(x, column)| {
+            column.iter().enumerate().flat_map(move |(y, row)| {
+                row.iter().enumerate().map(move |(z, block)| {
+                    (origin_x + x as i32, y as i32, origin_z + z as i32, block.block_type)
+                })
+            })
+        })
+    }
+
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block_type: BlockType) {
+        self.blocks[x][y][z].block_type = block_type;
+        self.dirty = true;
+    }
+
+    // true once after each edit, the caller meshes the chunk again
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::replace(&mut self.dirty, false)
+    }
+
+    // Corners of the box around the blocks which are not air, in world space. None when
+    // the chunk is empty.
+    pub fn bounds(&self) -> Option<Aabb> {
+        let mut bounds: Option<([usize; 3], [usize; 3])> = None;
+        for x in 0..CHUNK_WIDTH {
+            for y in 0..CHUNK_HEIGHT {
+                for z in 0..CHUNK_WIDTH {
+                    if self.blocks[x][y][z].block_type == BlockType::AIR {
+                        continue;
+                    }
+                    bounds = Some(match bounds {
+                        Some((min, max)) => (
+                            [min[0].min(x), min[1].min(y), min[2].min(z)],
+                            [max[0].max(x), max[1].max(y), max[2].max(z)],
+                        ),
+                        None => ([x, y, z], [x, y, z]),
+                    });
+                }
+            }
         }
+
+        let offset = self.world_offset();
+        bounds.map(|(min, max)| {
+            // the blocks are centered on their position
+            let corner = |block: [usize; 3], side: f32| Vector3::new(
+                block[0] as f32 * 2.0 * HALF_BLOCK_SIZE + side * HALF_BLOCK_SIZE,
+                block[1] as f32 * 2.0 * HALF_BLOCK_SIZE + side * HALF_BLOCK_SIZE,
+                block[2] as f32 * 2.0 * HALF_BLOCK_SIZE + side * HALF_BLOCK_SIZE,
+            ) + offset;
+            Aabb::new(corner(min, -1.0), corner(max, 1.0))
+        })
     }
 
-    pub fn create_mesh(&self) -> (Vec<ColorVertex>, Vec<u16>) {
-        let mut vertices: Vec<ColorVertex> = vec![];
-        let mut indices: Vec<u16> = vec![];
-        
+    // Hash of the block types only, chunks holding the same blocks share it. FNV-1a over
+    // one byte per block, unlike DefaultHasher and the derived Hash it does not depend on
+    // the version of Rust or the size of isize so the tests can compare it with a constant.
+    pub fn content_hash(&self) -> u64 {
+        let mut hash = FNV_OFFSET_BASIS;
+        for column in self.blocks.iter() {
+            for row in column.iter() {
+                for block in row.iter() {
+                    hash = (hash ^ block.block_type.0 as u64).wrapping_mul(FNV_PRIME);
+                }
+            }
+        }
+        hash
+    }
+
+    // None outside of the chunk
+    pub fn block_at(&self, x: i32, y: i32, z: i32) -> Option<BlockType> {
+        if x < 0 || y < 0 || z < 0 || x >= CHUNK_WIDTH as i32 || y >= CHUNK_HEIGHT as i32 || z >= CHUNK_WIDTH as i32 {
+            return None;
+        }
+        Some(self.blocks[x as usize][y as usize][z as usize].block_type)
+    }
+
+    // First solid block hit by the ray
+    pub fn raycast(&self, origin: Point3<f32>, direction: Vector3<f32>, max_distance: f32) -> Option<[usize; 3]> {
+        self.raycast_with_previous(origin, direction, max_distance).map(|(hit, _)| hit)
+    }
+
+    // Also gives the cell the ray went through just before the hit, where a block placed
+    // against the hit face goes. It can be outside of the chunk.
+    // The grid is walked one block at a time (Amanatides & Woo).
+    pub fn raycast_with_previous(&self, origin: Point3<f32>, direction: Vector3<f32>, max_distance: f32) -> Option<([usize; 3], [i32; 3])> {
+        let block_size = 2.0 * HALF_BLOCK_SIZE;
+        // in grid units the block (i, j, k) spans [i, i+1[ on each axis
+        let start = origin.to_vec() / block_size + Vector3::new(0.5, 0.5, 0.5);
+        let direction = direction.normalize();
+
+        let mut cell = [start.x.floor() as i32, start.y.floor() as i32, start.z.floor() as i32];
+        let mut step = [0; 3];
+        let mut t_max = [f32::INFINITY; 3];
+        let mut t_delta = [f32::INFINITY; 3];
+        for axis in 0..3 {
+            if direction[axis] > 0.0 {
+                step[axis] = 1;
+                t_delta[axis] = 1.0 / direction[axis];
+                t_max[axis] = (cell[axis] as f32 + 1.0 - start[axis]) * t_delta[axis];
+            } else if direction[axis] < 0.0 {
+                step[axis] = -1;
+                t_delta[axis] = -1.0 / direction[axis];
+                t_max[axis] = (start[axis] - cell[axis] as f32) * t_delta[axis];
+            }
+        }
+
+        let max_t = max_distance / block_size;
+        let mut t = 0.0;
+        let mut previous = cell;
+        while t <= max_t {
+            match self.block_at(cell[0], cell[1], cell[2]) {
+                Some(block_type) if block_type != BlockType::AIR => {
+                    return Some(([cell[0] as usize, cell[1] as usize, cell[2] as usize], previous));
+                }
+                _ => {}
+            }
+            previous = cell;
+            let axis = if t_max[0] < t_max[1] {
+                if t_max[0] < t_max[2] { 0 } else { 2 }
+            } else if t_max[1] < t_max[2] { 1 } else { 2 };
+            t = t_max[axis];
+            cell[axis] += step[axis];
+            t_max[axis] += t_delta[axis];
+        }
+
+        None
+    }
+
+    pub fn create_mesh_with(&self, mode: MeshMode) -> Mesh {
+        match mode {
+            MeshMode::Blocky if self.lod > 0 => self.create_lod_mesh(self.lod),
+            MeshMode::Blocky => self.create_mesh(&VerticalNeighbours::default()),
+            MeshMode::MarchingCubes => self.create_smooth_mesh(),
+        }
+    }
+
+    pub fn create_smooth_mesh(&self) -> Mesh {
+        let mut mesh = marching_cubes::marching_cubes(&self.density_field(), 0.5, [0.5, 0.5, 0.5]);
+        mesh::compute_smooth_normals(&mut mesh.vertices, &mesh.indices);
+
+        info!("Sending to GPU: {} vertices and {} indices", mesh.vertex_count(), mesh.indices.len());
+
+        mesh
+    }
+
+    // Density on the block corners: the ratio of solid blocks around it, blocks
+    // outside of the chunk count as air so the surface is closed
+    pub fn density_field(&self) -> DensityField {
+        let block_size = 2.0 * HALF_BLOCK_SIZE;
+        let size = [CHUNK_WIDTH + 1, CHUNK_HEIGHT + 1, CHUNK_WIDTH + 1];
+        let origin = [-HALF_BLOCK_SIZE; 3];
+
+        DensityField::from_fn(size, block_size, origin, |cx, cy, cz| {
+            let mut solid = 0;
+            for x in cx.saturating_sub(1)..(cx + 1).min(CHUNK_WIDTH) {
+                for y in cy.saturating_sub(1)..(cy + 1).min(CHUNK_HEIGHT) {
+                    for z in cz.saturating_sub(1)..(cz + 1).min(CHUNK_WIDTH) {
+                        if !self.registry.get(self.blocks[x][y][z].block_type).is_transparent() {
+                            solid += 1;
+                        }
+                    }
+                }
+            }
+            solid as f32 / 8.0
+        })
+    }
+
+    pub fn create_mesh(&self, neighbours: &VerticalNeighbours) -> Mesh {
+        let builder = self.build_cubes(neighbours);
+        info!("Sending to GPU: {} vertices and {} indices", builder.vertices.len(), builder.indices.len());
+
+        builder.build()
+    }
+
+    // the same mesh as create_mesh, built on the calling thread only
+    pub fn create_mesh_single_threaded(&self, neighbours: &VerticalNeighbours) -> Mesh {
+        let meshed = |block_type: BlockType| !self.registry.get(block_type).is_transparent();
+        let light = LightMap::compute(self);
+        let mut builder = MeshBuilder::new();
+        for x in 0..CHUNK_WIDTH {
+            builder.append(self.build_slice(x, neighbours, &light, &meshed));
+        }
+        builder.build()
+    }
+
+    // a line along the normal of each visible face of the blocky mesh, for debugging
+    pub fn create_normal_lines(&self, neighbours: &VerticalNeighbours, length: f32) -> Vec<ColorVertex> {
+        self.build_cubes(neighbours).normal_lines(length)
+    }
+
+    // the glass blocks are blended over the rest, they get their own mesh
+    pub fn create_glass_mesh(&self) -> Mesh {
+        self.build_cubes_of(&VerticalNeighbours::default(), |block_type| self.registry.get(block_type).mesh == BlockMesh::Glass).build()
+    }
+
+    // the blocks are meshed with the definitions of this registry from now on
+    pub fn with_registry(mut self, registry: Arc<BlockRegistry>) -> Self {
+        self.registry = registry;
+        self.dirty = true;
+        self
+    }
+
+    // the mesh is only rebuilt when the level changes
+    pub fn set_lod(&mut self, lod: u32) {
+        let lod = lod.min(lod::MAX_LOD);
+        if lod != self.lod {
+            self.lod = lod;
+            self.dirty = true;
+        }
+    }
+
+    // Blocky mesh with the blocks merged in cubes of lod::cell_size blocks. A cell takes the
+    // highest solid block in it so the grass stays on top. The water and glass are left out.
+    pub fn create_lod_mesh(&self, lod: u32) -> Mesh {
+        let size = lod::cell_size(lod);
+        let cells = [CHUNK_WIDTH / size, CHUNK_HEIGHT / size, CHUNK_WIDTH / size];
+        let mut builder = MeshBuilder::new();
+
+        let cell_at = |x: i32, y: i32, z: i32| -> BlockType {
+            if y < 0 && self.world_floor {
+                return BlockType::STONE;
+            }
+            if x < 0 || y < 0 || z < 0 || x >= cells[0] as i32 || y >= cells[1] as i32 || z >= cells[2] as i32 {
+                return BlockType::AIR;
+            }
+            self.cell_block(x as usize * size, y as usize * size, z as usize * size, size)
+        };
+
+        for x in 0..cells[0] as i32 {
+            for y in 0..cells[1] as i32 {
+                for z in 0..cells[2] as i32 {
+                    let block_type = cell_at(x, y, z);
+                    if block_type == BlockType::AIR {
+                        continue;
+                    }
+                    let min = block_corners(x as usize * size, y as usize * size, z as usize * size)[0];
+                    let max = block_corners((x + 1) as usize * size - 1, (y + 1) as usize * size - 1, (z + 1) as usize * size - 1)[7];
+                    let corners = box_corners(min, max);
+                    for &face in [Faces::BACK, Faces::FRONT, Faces::RIGHT, Faces::LEFT, Faces::TOP, Faces::BOTTOM].iter() {
+                        let n = face.normal();
+                        if cell_at(x + n[0] as i32, y + n[1] as i32, z + n[2] as i32) == BlockType::AIR {
+                            add_face(&mut builder, &self.registry, face, block_type, &corners, 1.0);
+                        }
+                    }
+                }
+            }
+        }
+
+        builder.build()
+    }
+
+    // the highest block of the cell that is not transparent, air when there is none
+    fn cell_block(&self, x: usize, y: usize, z: usize, size: usize) -> BlockType {
+        (y..y + size).rev()
+            .flat_map(|y| (x..x + size).flat_map(move |x| (z..z + size).map(move |z| (x, y, z))))
+            .map(|(x, y, z)| self.blocks[x][y][z].block_type)
+            .find(|&block_type| !self.registry.get(block_type).is_transparent())
+            .unwrap_or(BlockType::AIR)
+    }
+
+    // water is meshed on its own so it can be animated, and the glass is drawn last
+    fn build_cubes(&self, neighbours: &VerticalNeighbours) -> MeshBuilder {
+        self.build_cubes_of(neighbours, |block_type| !self.registry.get(block_type).is_transparent())
+    }
+
+    // The slices along x are meshed on the rayon thread pool then joined back in order, the
+    // mesh is the same as when built on a single thread
+    fn build_cubes_of<F: Fn(BlockType) -> bool + Sync>(&self, neighbours: &VerticalNeighbours, meshed: F) -> MeshBuilder {
+        let light = LightMap::compute(self);
+        let slices: Vec<MeshBuilder> = (0..CHUNK_WIDTH).into_par_iter()
+            .map(|x| self.build_slice(x, neighbours, &light, &meshed))
+            .collect();
+
+        let mut builder = MeshBuilder::new();
+        for slice in slices {
+            builder.append(slice);
+        }
+        builder
+    }
+
+    // the blocks of a slice do not depend on each other, only on the light map
+    fn build_slice<F: Fn(BlockType) -> bool>(&self, x: usize, neighbours: &VerticalNeighbours, light: &LightMap, meshed: &F) -> MeshBuilder {
+        let mut builder = MeshBuilder::new();
+        // same order as the blocks array, z is contiguous in memory
         for y in 0..CHUNK_HEIGHT {
-            for x in 0..CHUNK_WIDTH {
+            for z in 0..CHUNK_WIDTH {
+                if meshed(self.blocks[x][y][z].block_type) {
+                    self.create_cube(&mut builder, x, y, z, neighbours, light);
+                }
+            }
+        }
+        builder
+    }
+
+    // Only the surface of the water is drawn, its vertices are moved by the water shader
+    pub fn create_water_mesh(&self) -> Mesh {
+        let mut builder = MeshBuilder::new();
+
+        for x in 0..CHUNK_WIDTH {
+            for y in 0..CHUNK_HEIGHT {
                 for z in 0..CHUNK_WIDTH {
-                    if self.blocks[x][y][z].block_type != BlockType::AIR {
-                        let (mut v_cube, mut i_cube) = self.create_cube(vertices.len(), x, y, z);
-                        vertices.append(&mut v_cube);
-                        indices.append(&mut i_cube);
+                    let block_type = self.blocks[x][y][z].block_type;
+                    let above = self.block_at(x as i32, y as i32 + 1, z as i32).unwrap_or(BlockType::AIR);
+                    if self.registry.get(block_type).mesh == BlockMesh::Water && self.registry.get(above).mesh == BlockMesh::Empty {
+                        add_face(&mut builder, &self.registry, Faces::TOP, block_type, &block_corners(x, y, z), 1.0);
                     }
                 }
             }
         }
 
-        println!("Sending to GPU: {} vertices and {} indices", vertices.len(), indices.len());
+        builder.build()
+    }
+
+    // The block a face looking at this position sees, the position may be out of the chunk.
+    // The world floor is seen as stone and the open sides as air.
+    fn neighbour_at(&self, x: i32, y: i32, z: i32, neighbours: &VerticalNeighbours) -> BlockType {
+        if y < 0 {
+            return match neighbours.below {
+                Some(below) => below.block_at(x, CHUNK_HEIGHT as i32 - 1, z).unwrap_or(BlockType::AIR),
+                None if self.world_floor => BlockType::STONE,
+                None => BlockType::AIR,
+            };
+        }
+        if y >= CHUNK_HEIGHT as i32 {
+            return match neighbours.above {
+                Some(above) => above.block_at(x, 0, z).unwrap_or(BlockType::AIR),
+                None => BlockType::AIR,
+            };
+        }
+        // there is no horizontal neighbour yet, the sides of the chunk are always visible
+        self.block_at(x, y, z).unwrap_or(BlockType::AIR)
+    }
+
+    // Ambient occlusion of the corners of a face, in the order of FACE_CORNERS. A corner is
+    // darkened by the solid blocks touching it in front of the face: the two along the
+    // edges and the one in the corner, which no longer counts once both edges are solid.
+    fn face_ao(&self, x: i32, y: i32, z: i32, face: Faces, neighbours: &VerticalNeighbours) -> [f32; 4] {
+        let n = face.normal().map(|v| v as i32);
+        let axis = n.iter().position(|&v| v != 0).unwrap_or(0);
+        let solid = |offset: [i32; 3]| {
+            let block_type = self.neighbour_at(x + n[0] + offset[0], y + n[1] + offset[1], z + n[2] + offset[2], neighbours);
+            !self.registry.get(block_type).is_transparent()
+        };
 
-        (vertices, indices)
+        FACE_CORNERS[face as usize].map(|corner| {
+            let sign = CORNER_SIGNS[corner];
+            // the corner seen from the face, along the two axes of the face only
+            let along = |a: usize| {
+                let mut offset = [0; 3];
+                offset[a] = sign[a];
+                offset
+            };
+            let (first, second) = ((axis + 1) % 3, (axis + 2) % 3);
+            let edge_a = solid(along(first));
+            let edge_b = solid(along(second));
+            let diagonal = {
+                let mut offset = along(first);
+                offset[second] = sign[second];
+                solid(offset)
+            };
+            let open = if edge_a && edge_b { 0 } else { 3 - (edge_a as usize + edge_b as usize + diagonal as usize) };
+            AO_BRIGHTNESS[open]
+        })
     }
 
-    fn create_cube(&self, idx_offset: usize, x: usize, y: usize, z: usize) -> (Vec<ColorVertex>, Vec<u16>) {
+    // Brightness factor of the block, from the world coordinates so it is the same for each
+    // mesh of the block and does not repeat from one chunk to the next
+    pub fn color_jitter(&self, x: usize, y: usize, z: usize) -> f32 {
+        if self.terrain.color_jitter == 0.0 {
+            return 1.0;
+        }
+        let world_x = self.position.0 * CHUNK_WIDTH as i32 + x as i32;
+        let world_z = self.position.1 * CHUNK_WIDTH as i32 + z as i32;
+        let random = noise::hash_rng(self.terrain.seed ^ JITTER_SEED, world_x, y as i32, world_z);
+        1.0 + self.terrain.color_jitter * (random * 2.0 - 1.0)
+    }
+
+    // the visible faces of one block, the mesh of the chunk is made of them in the order of the blocks array
+    pub fn create_cube(&self, builder: &mut MeshBuilder, x: usize, y: usize, z: usize, neighbours: &VerticalNeighbours, light: &LightMap) {
         //println!("Block: x: {} y: {} z: {}", x, y, z);
-        let color: f32 = idx_offset as f32 / (CHUNK_HEIGHT * CHUNK_WIDTH * 36 * 8) as f32;
-    
-        let px = x as f32 * 2.0 * HALF_BLOCK_SIZE;
-        let py = y as f32 * 2.0 * HALF_BLOCK_SIZE;
-        let pz = z as f32 * 2.0 * HALF_BLOCK_SIZE;
-
-        let v_cube = vec![
-            // front
-            ColorVertex { position: [px-HALF_BLOCK_SIZE, py-HALF_BLOCK_SIZE, pz-HALF_BLOCK_SIZE], color: [color, 0.0, 0.0] },
-            ColorVertex { position: [px+HALF_BLOCK_SIZE, py-HALF_BLOCK_SIZE, pz-HALF_BLOCK_SIZE], color: [color, 0.0, 0.0] },
-            ColorVertex { position: [px+HALF_BLOCK_SIZE, py+HALF_BLOCK_SIZE, pz-HALF_BLOCK_SIZE], color: [color, 0.0, 0.0] },
-            ColorVertex { position: [px-HALF_BLOCK_SIZE, py+HALF_BLOCK_SIZE, pz-HALF_BLOCK_SIZE], color: [color, 0.0, 0.0] },
-            // Back
-            ColorVertex { position: [px+HALF_BLOCK_SIZE, py-HALF_BLOCK_SIZE, pz+HALF_BLOCK_SIZE], color: [0.0, 0.0, color] },
-            ColorVertex { position: [px-HALF_BLOCK_SIZE, py-HALF_BLOCK_SIZE, pz+HALF_BLOCK_SIZE], color: [0.0, 0.0, color] },
-            ColorVertex { position: [px-HALF_BLOCK_SIZE, py+HALF_BLOCK_SIZE, pz+HALF_BLOCK_SIZE], color: [0.0, 0.0, color] },
-            ColorVertex { position: [px+HALF_BLOCK_SIZE, py+HALF_BLOCK_SIZE, pz+HALF_BLOCK_SIZE], color: [0.0, 0.0, color] },
-        ];
-    
-        let mut i_cube: Vec<u16> = vec![];
-        // culling
-        if z == CHUNK_WIDTH - 1 || (z < CHUNK_WIDTH - 1 && self.blocks[x][y][z+1].block_type == BlockType::AIR) {
-            i_cube.append(&mut add_face_indices(&Faces::BACK, idx_offset));
-        }
-        if z == 0 || (z > 0 && self.blocks[x][y][z-1].block_type == BlockType::AIR) {
-            i_cube.append(&mut add_face_indices(&Faces::FRONT, idx_offset));
-        }
-        if x == CHUNK_WIDTH - 1 || (x < CHUNK_WIDTH - 1 && self.blocks[x+1][y][z].block_type == BlockType::AIR) {
-            i_cube.append(&mut add_face_indices(&Faces::RIGHT, idx_offset));
-        }
-        if x == 0 || (x > 0 && self.blocks[x-1][y][z].block_type == BlockType::AIR) {
-            i_cube.append(&mut add_face_indices(&Faces::LEFT, idx_offset));
-        }
-        if y == CHUNK_HEIGHT - 1 || (y < CHUNK_HEIGHT - 1 && self.blocks[x][y+1][z].block_type == BlockType::AIR) {
-            i_cube.append(&mut add_face_indices(&Faces::TOP, idx_offset));
-        }
-        if y == 0 || (y > 0 && self.blocks[x][y-1][z].block_type == BlockType::AIR) {
-            i_cube.append(&mut add_face_indices(&Faces::BOTTOM, idx_offset));
-        }
-
-        // Extreme culling
-        if i_cube.len() == 0 {
-            return (vec![], vec![]);
-        }
-    
-        //println!("IDX: {:?}", i_cube);
-    
-        (v_cube, i_cube)
+        let block_type = self.blocks[x][y][z].block_type;
+        let corners = block_corners(x, y, z);
+        let jitter = self.color_jitter(x, y, z);
+        let (x, y, z) = (x as i32, y as i32, z as i32);
+
+        // a face is lit by the block it looks at, the jitter darkens or brightens it like the light
+        let mut push_face = |face: Faces| {
+            let n = face.normal();
+            let level = light.level(x + n[0] as i32, y + n[1] as i32, z + n[2] as i32);
+            let ao = if self.registry.get(block_type).emissive { [1.0; 4] } else { self.face_ao(x, y, z, face, neighbours) };
+            add_shaded_face(builder, &self.registry, face, block_type, &corners, lighting::light_factor(level) * jitter, ao);
+        };
+        // culling, a face is kept when it can be seen through its neighbour. The faces
+        // between two glass blocks are left out so a wall of glass only shows its outside.
+        let visible = |x: i32, y: i32, z: i32| {
+            let neighbour = self.neighbour_at(x, y, z, neighbours);
+            self.registry.get(neighbour).is_transparent() && neighbour != block_type
+        };
+        if visible(x, y, z + 1) {
+            push_face(Faces::BACK);
+        }
+        if visible(x, y, z - 1) {
+            push_face(Faces::FRONT);
+        }
+        if visible(x + 1, y, z) {
+            push_face(Faces::RIGHT);
+        }
+        if visible(x - 1, y, z) {
+            push_face(Faces::LEFT);
+        }
+        if visible(x, y + 1, z) {
+            push_face(Faces::TOP);
+        }
+        if visible(x, y - 1, z) {
+            push_face(Faces::BOTTOM);
+        }
     }
 }
 
-#[derive(Clone, Copy)]
-enum Faces {
-    FRONT = 0,
-    BACK = 1,
-    TOP = 2,
-    BOTTOM = 3,
-    LEFT = 4,
-    RIGHT = 5,
+// the edges of a box as a line list, indexing its corners
+const BOX_EDGES: [[usize; 2]; 12] = [
+    [0, 1], [1, 2], [2, 3], [3, 0],
+    [4, 5], [5, 6], [6, 7], [7, 4],
+    [0, 5], [1, 4], [2, 7], [3, 6],
+];
+pub const OUTLINE_VERTICES: usize = BOX_EDGES.len() * 2;
+
+impl Chunk {
+    // Edges of a block in world coordinates, as a line list. The box is grown by inflation
+    // on every side so its lines are not hidden by the faces of the block.
+    pub fn block_outline(&self, [x, y, z]: [usize; 3], inflation: f32, color: [f32; 3]) -> Vec<ColorVertex> {
+        let corners = block_corners(x, y, z);
+        let offset = self.world_offset();
+        let (min, max) = (corners[0], corners[7]);
+        let corners = box_corners(
+            [min[0] - inflation + offset.x, min[1] - inflation + offset.y, min[2] - inflation + offset.z],
+            [max[0] + inflation + offset.x, max[1] + inflation + offset.y, max[2] + inflation + offset.z],
+        );
+        BOX_EDGES.iter()
+            .flat_map(|edge| edge.iter().map(|&corner| corners[corner]))
+            .map(|position| ColorVertex { position, color, normal: [0.0; 3], tex_coords: atlas::PLAIN_TEX_COORDS, ao: 1.0, material_id: material::PLAIN_MATERIAL })
+            .collect()
+    }
+}
+
+// A lone block centered on the origin with all of its faces, used for the light marker
+pub fn cube_mesh(color: [f32; 3]) -> Mesh {
+    let corners = block_corners(0, 0, 0);
+    let mut builder = MeshBuilder::new();
+    for &face in [Faces::FRONT, Faces::BACK, Faces::TOP, Faces::BOTTOM, Faces::LEFT, Faces::RIGHT].iter() {
+        builder.add_quad(FACE_CORNERS[face as usize].map(|corner| corners[corner]), color, face.normal());
+    }
+    builder.build()
+}
+
+// Corners of the block, the front face looks to -z:
+//
+//      6-------7
+//     /|      /|      y
+//    3-------2 |      | z
+//    | 5-----|-4      |/
+//    |/      |/       +--x
+//    0-------1
+fn block_corners(x: usize, y: usize, z: usize) -> [[f32; 3]; 8] {
+    let px = x as f32 * 2.0 * HALF_BLOCK_SIZE;
+    let py = y as f32 * 2.0 * HALF_BLOCK_SIZE;
+    let pz = z as f32 * 2.0 * HALF_BLOCK_SIZE;
+
+    box_corners(
+        [px-HALF_BLOCK_SIZE, py-HALF_BLOCK_SIZE, pz-HALF_BLOCK_SIZE],
+        [px+HALF_BLOCK_SIZE, py+HALF_BLOCK_SIZE, pz+HALF_BLOCK_SIZE],
+    )
 }
 
-const BASE_INDICES: [[u16; 6]; 6] = [
-    [0,1,3,  3,1,2], // Front
-    [4,5,7,  7,5,6], // Back
-    [3,2,6,  6,2,7], // Top
-    [5,4,0,  0,4,1], // Bottom
-    [5,0,6,  6,0,3], // Left
-    [1,4,2,  2,4,7], // Right
+// same corners as block_corners for any box, 0 is min and 7 is max
+fn box_corners(min: [f32; 3], max: [f32; 3]) -> [[f32; 3]; 8] {
+    let ([x0, y0, z0], [x1, y1, z1]) = (min, max);
+    [
+        // front
+        [x0, y0, z0],
+        [x1, y0, z0],
+        [x1, y1, z0],
+        [x0, y1, z0],
+        // Back
+        [x1, y0, z1],
+        [x0, y0, z1],
+        [x0, y1, z1],
+        [x1, y1, z1],
+    ]
+}
+
+// side of the block each corner is on, along each axis
+const CORNER_SIGNS: [[i32; 3]; 8] = [
+    [-1, -1, -1],
+    [1, -1, -1],
+    [1, 1, -1],
+    [-1, 1, -1],
+    [1, -1, 1],
+    [-1, -1, 1],
+    [-1, 1, 1],
+    [1, 1, 1],
 ];
 
-fn add_face_indices(face: &Faces, idx_offset: usize) -> Vec<u16> {
-    let mut res: Vec<u16> = vec![];
-    for i in 0..6 {
-        res.push(BASE_INDICES[*face as usize][i] + idx_offset as u16);
-    }
-    res
-}
\ No newline at end of file
+// Corners of each face, indexing the corners above. They go clockwise when the face is
+// seen from outside of the block, which is the front side for FrontFace::Cw, so culling
+// the back faces only hides the faces turned away from the camera.
+const FACE_CORNERS: [[usize; 4]; 6] = [
+    [1, 2, 3, 0], // Front
+    [5, 6, 7, 4], // Back
+    [2, 7, 6, 3], // Top
+    [4, 1, 0, 5], // Bottom
+    [0, 3, 6, 5], // Left
+    [4, 7, 2, 1], // Right
+];
+
+// a quad of its own for each face, its color multiplied by the light unless the block is emissive
+fn add_face(builder: &mut MeshBuilder, registry: &BlockRegistry, face: Faces, block_type: BlockType, corners: &[[f32; 3]; 8], light: f32) {
+    add_shaded_face(builder, registry, face, block_type, corners, light, [1.0; 4]);
+}
+
+// same as add_face with the ambient occlusion of the corners, see Chunk::face_ao
+fn add_shaded_face(builder: &mut MeshBuilder, registry: &BlockRegistry, face: Faces, block_type: BlockType, corners: &[[f32; 3]; 8], light: f32, ao: [f32; 4]) {
+    let brightness = if registry.get(block_type).emissive { 1.0 } else { face.brightness() * light };
+    let [r, g, b] = registry.face_color(block_type, face);
+    let color = [r * brightness, g * brightness, b * brightness];
+
+    let positions = FACE_CORNERS[face as usize].map(|corner| corners[corner]);
+    let tex_coords = positions.map(|position| atlas::face_tex_coords(face, position));
+    builder.add_shaded_quad(positions, tex_coords, color, face.normal(), ao, material::material_id(block_type));
+}
@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+use cgmath::*;
+use crate::render::math::Aabb;
+use super::block::HALF_BLOCK_SIZE;
+use super::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+use super::noise;
+use super::registry::BlockRegistry;
+
+// chunks loaded on each side of the main one
+pub const DEFAULT_VIEW_RADIUS: i32 = 1;
+pub const MAX_VIEW_RADIUS: i32 = 4;
+// chunks generated by each update, the others wait in the queue
+pub const DEFAULT_GENERATION_BUDGET: usize = 2;
+// a chunk in the view goes before the ones out of it up to that many chunks closer
+const IN_VIEW_BONUS: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RenderMode {
+    // only the main chunk, as before the chunk manager
+    SingleChunk,
+    // the main chunk and the ones around it
+    MultiChunk,
+}
+
+impl RenderMode {
+    // whether the chunk at position is drawn, main is the position of the main chunk
+    pub fn draws(&self, position: (i32, i32), main: (i32, i32)) -> bool {
+        *self == RenderMode::MultiChunk || position == main
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedMode {
+    // one world seed, the terrain goes on from a chunk to the next
+    Shared,
+    // each chunk gets its own seed, the seams between chunks stand out
+    PerChunk,
+}
+
+// Chunks around the main one. The main chunk stays at (0, 0) in the State, where the
+// edits and the sessions go, so the manager never holds that position.
+pub struct ChunkManager {
+    pub terrain: TerrainConfig,
+    pub radius: i32,
+    pub seed_mode: SeedMode,
+    pub chunks: HashMap<(i32, i32), Chunk>,
+    // positions still to generate, see generate_pending
+    pending: Vec<(i32, i32)>,
+    pub generation_budget: usize,
+    // given to the generated chunks
+    pub registry: Arc<BlockRegistry>,
+}
+
+// a queued position, the lowest priority comes out of the heap first
+struct QueuedChunk {
+    priority: f32,
+    position: (i32, i32),
+}
+
+impl PartialEq for QueuedChunk {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedChunk {}
+
+impl PartialOrd for QueuedChunk {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedChunk {
+    // reversed, BinaryHeap is a max heap
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.partial_cmp(&self.priority).unwrap_or(Ordering::Equal)
+            .then_with(|| other.position.cmp(&self.position))
+    }
+}
+
+impl ChunkManager {
+    pub fn new(terrain: TerrainConfig, radius: i32, seed_mode: SeedMode) -> Self {
+        let mut manager = ChunkManager {
+            terrain,
+            radius,
+            seed_mode,
+            chunks: HashMap::new(),
+            pending: vec![],
+            generation_budget: DEFAULT_GENERATION_BUDGET,
+            registry: Arc::new(BlockRegistry::builtin()),
+        };
+        manager.regenerate(terrain);
+        manager
+    }
+
+    // the chunks are dropped and queued, generate_pending builds them again a few at a time
+    pub fn regenerate(&mut self, terrain: TerrainConfig) {
+        self.terrain = terrain;
+        self.chunks.clear();
+        self.pending.clear();
+        for x in -self.radius..=self.radius {
+            for z in -self.radius..=self.radius {
+                if (x, z) != (0, 0) {
+                    self.pending.push((x, z));
+                }
+            }
+        }
+    }
+
+    // Generates at most generation_budget of the queued chunks, the ones in the view and
+    // the closest to the camera first. The new chunks are dirty so they get meshed before
+    // being drawn, gives how many were generated.
+    pub fn generate_pending<F: Fn(&Aabb) -> bool>(&mut self, camera: Point3<f32>, in_view: F) -> usize {
+        // the positions the radius left since they were queued are dropped, not generated
+        let radius = self.radius;
+        self.pending.retain(|&position| in_radius(position, radius));
+        if self.pending.is_empty() {
+            return 0;
+        }
+        let mut queue: BinaryHeap<QueuedChunk> = self.pending.drain(..)
+            .map(|position| QueuedChunk { priority: chunk_priority(position, camera, &in_view), position })
+            .collect();
+
+        let mut count = 0;
+        while count < self.generation_budget {
+            let position = match queue.pop() {
+                Some(queued) => queued.position,
+                None => break,
+            };
+            let chunk = Chunk::generate_at(self.chunk_terrain(self.terrain, position), position).with_registry(self.registry.clone());
+            self.chunks.insert(position, chunk);
+            count += 1;
+        }
+        self.pending = queue.into_iter().map(|queued| queued.position).collect();
+        count
+    }
+
+    // chunks queued and not generated yet
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    // Generates again the chunks at these positions only, the others keep their blocks.
+    // The positions not held by the manager are ignored, gives how many were generated.
+    pub fn regenerate_chunks(&mut self, terrain: TerrainConfig, positions: &[(i32, i32)]) -> usize {
+        let mut count = 0;
+        for position in positions {
+            if !self.chunks.contains_key(position) {
+                continue;
+            }
+            let chunk = Chunk::generate_at(self.chunk_terrain(terrain, *position), *position).with_registry(self.registry.clone());
+            self.chunks.insert(*position, chunk);
+            count += 1;
+        }
+        count
+    }
+
+    // the terrain of the chunk at position, the world seed is only kept when it is shared
+    pub fn chunk_terrain(&self, terrain: TerrainConfig, position: (i32, i32)) -> TerrainConfig {
+        match self.seed_mode {
+            SeedMode::Shared => terrain,
+            SeedMode::PerChunk => TerrainConfig {
+                seed: terrain.seed.wrapping_add(noise::hash_seed(0, position.0, 0, position.1)),
+                ..terrain
+            },
+        }
+    }
+
+    // The chunks still in the new radius are kept, the ones it adds are queued. The chunks
+    // and the queued positions out of it are dropped, the State then frees their buffers.
+    pub fn set_radius(&mut self, radius: i32) {
+        let radius = radius.max(0);
+        self.radius = radius;
+        self.chunks.retain(|&position, _| in_radius(position, radius));
+        self.pending.retain(|&position| in_radius(position, radius));
+        for x in -radius..=radius {
+            for z in -radius..=radius {
+                let position = (x, z);
+                if position != (0, 0) && !self.chunks.contains_key(&position) && !self.pending.contains(&position) {
+                    self.pending.push(position);
+                }
+            }
+        }
+    }
+
+    // the chunks edited or generated since the last call, sorted so they mesh in the same order
+    pub fn take_dirty(&mut self) -> Vec<(i32, i32)> {
+        let mut dirty: Vec<(i32, i32)> = self.chunks.iter_mut()
+            .filter_map(|(position, chunk)| if chunk.take_dirty() { Some(*position) } else { None })
+            .collect();
+        dirty.sort_unstable();
+        dirty
+    }
+
+    pub fn mark_dirty(&mut self) {
+        for chunk in self.chunks.values_mut() {
+            chunk.dirty = true;
+        }
+    }
+}
+
+// whether the chunk at position is one of the chunks the manager holds for that radius
+pub fn in_radius(position: (i32, i32), radius: i32) -> bool {
+    position.0.abs() <= radius && position.1.abs() <= radius
+}
+
+// the space a chunk takes in the world, before it is generated
+fn chunk_space(position: (i32, i32)) -> Aabb {
+    let size = Vector3::new(CHUNK_WIDTH as f32, CHUNK_HEIGHT as f32, CHUNK_WIDTH as f32) * 2.0 * HALF_BLOCK_SIZE;
+    let min = Vector3::new(position.0 as f32 * size.x, 0.0, position.1 as f32 * size.z) - Vector3::new(HALF_BLOCK_SIZE, HALF_BLOCK_SIZE, HALF_BLOCK_SIZE);
+    Aabb::new(min, min + size)
+}
+
+// distance from the camera to the chunk center, less the bonus when it is in the view
+fn chunk_priority<F: Fn(&Aabb) -> bool>(position: (i32, i32), camera: Point3<f32>, in_view: &F) -> f32 {
+    let space = chunk_space(position);
+    let distance = (space.center() - camera.to_vec()).magnitude();
+    if in_view(&space) {
+        distance - IN_VIEW_BONUS * space.size().x
+    } else {
+        distance
+    }
+}
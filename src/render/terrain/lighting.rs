@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+use super::chunk::{Chunk, CHUNK_HEIGHT, CHUNK_WIDTH};
+
+// level of the emissive blocks, it goes down by one for each block away from them
+pub const MAX_LIGHT: u8 = 15;
+// how much brighter a face gets next to a light, at MAX_LIGHT
+const LIGHT_BOOST: f32 = 1.0;
+
+const STEPS: [[i32; 3]; 6] = [
+    [1, 0, 0], [-1, 0, 0],
+    [0, 1, 0], [0, -1, 0],
+    [0, 0, 1], [0, 0, -1],
+];
+
+// Light level of every block of a chunk, spread from the emissive blocks through the
+// transparent ones. The light does not go through the chunk borders.
+pub struct LightMap {
+    levels: Vec<u8>,
+}
+
+impl LightMap {
+    // breadth first from every light, each block keeps the level of its closest one
+    pub fn compute(chunk: &Chunk) -> Self {
+        let mut map = LightMap { levels: vec![0; CHUNK_WIDTH * CHUNK_HEIGHT * CHUNK_WIDTH] };
+        let mut queue = VecDeque::new();
+        for (x, y, z, block_type) in chunk.iter_blocks() {
+            if chunk.registry.get(block_type).emissive {
+                let local = [x - chunk.position.0 * CHUNK_WIDTH as i32, y, z - chunk.position.1 * CHUNK_WIDTH as i32];
+                map.levels[LightMap::index(local)] = MAX_LIGHT;
+                queue.push_back(local);
+            }
+        }
+
+        while let Some(cell) = queue.pop_front() {
+            let level = map.levels[LightMap::index(cell)];
+            if level <= 1 {
+                continue;
+            }
+            for step in STEPS.iter() {
+                let next = [cell[0] + step[0], cell[1] + step[1], cell[2] + step[2]];
+                match chunk.block_at(next[0], next[1], next[2]) {
+                    Some(block_type) if chunk.registry.get(block_type).is_transparent() => {}
+                    _ => continue,
+                }
+                let index = LightMap::index(next);
+                if map.levels[index] < level - 1 {
+                    map.levels[index] = level - 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        map
+    }
+
+    fn index(cell: [i32; 3]) -> usize {
+        (cell[0] as usize * CHUNK_HEIGHT + cell[1] as usize) * CHUNK_WIDTH + cell[2] as usize
+    }
+
+    // the level of a position of the chunk, no light outside
+    pub fn level(&self, x: i32, y: i32, z: i32) -> u8 {
+        if x < 0 || y < 0 || z < 0 || x >= CHUNK_WIDTH as i32 || y >= CHUNK_HEIGHT as i32 || z >= CHUNK_WIDTH as i32 {
+            return 0;
+        }
+        self.levels[LightMap::index([x, y, z])]
+    }
+}
+
+// multiplies the color of a face lit at that level, the faces far from the lights keep their color
+pub fn light_factor(level: u8) -> f32 {
+    1.0 + LIGHT_BOOST * level as f32 / MAX_LIGHT as f32
+}
+
+// what simple.frag multiplies the color by for the ambient occlusion of a vertex, 0 ignores
+// it and 1 applies all of it. The shader does the same with mix(1.0, v_ao, u_ao_strength).
+pub fn ao_shade(ao: f32, strength: f32) -> f32 {
+    1.0 + (ao - 1.0) * strength.clamp(0.0, 1.0)
+}
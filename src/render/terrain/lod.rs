@@ -0,0 +1,34 @@
+use cgmath::*;
+use super::block::HALF_BLOCK_SIZE;
+
+// the coarsest level merges the blocks 4 by 4
+pub const MAX_LOD: u32 = 2;
+// largest error a level can show on screen, in pixels
+pub const DEFAULT_LOD_THRESHOLD: f32 = 1.0;
+
+// width of the cells of a level, in blocks
+pub fn cell_size(lod: u32) -> usize {
+    1 << lod
+}
+
+// a cell is drawn as one cube, the surface moves at most by its width less one block
+pub fn geometric_error(lod: u32) -> f32 {
+    (cell_size(lod) - 1) as f32 * 2.0 * HALF_BLOCK_SIZE
+}
+
+// pixels covered by a length at that distance, looking at it from the front
+pub fn screen_size(length: f32, distance: f32, fov_y: Rad<f32>, screen_height: u32) -> f32 {
+    let view_height = 2.0 * distance * (fov_y.0 / 2.0).tan();
+    if view_height <= f32::EPSILON {
+        return f32::INFINITY;
+    }
+    length * screen_height as f32 / view_height
+}
+
+// The coarsest level whose error stays under the threshold, the nearest chunks keep their
+// blocks. A threshold of 0 always gives the full level.
+pub fn select_lod(distance: f32, fov_y: Rad<f32>, screen_height: u32, threshold: f32) -> u32 {
+    (0..=MAX_LOD).rev()
+        .find(|&lod| screen_size(geometric_error(lod), distance, fov_y, screen_height) <= threshold)
+        .unwrap_or(0)
+}
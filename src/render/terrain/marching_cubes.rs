@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use cgmath::*;
+use crate::render::{material, vertex::ColorVertex};
+use super::atlas;
+use super::mesh::Mesh;
+
+// Scalar field sampled on the corners of a regular grid, values above the iso level are solid
+pub struct DensityField {
+    pub size: [usize; 3],
+    pub spacing: f32,
+    pub origin: [f32; 3],
+    values: Vec<f32>,
+}
+
+impl DensityField {
+    pub fn new(size: [usize; 3], spacing: f32, origin: [f32; 3]) -> Self {
+        DensityField {
+            size,
+            spacing,
+            origin,
+            values: vec![0.0; size[0] * size[1] * size[2]],
+        }
+    }
+
+    pub fn from_fn<F: Fn(usize, usize, usize) -> f32>(size: [usize; 3], spacing: f32, origin: [f32; 3], f: F) -> Self {
+        let mut field = DensityField::new(size, spacing, origin);
+        for x in 0..size[0] {
+            for y in 0..size[1] {
+                for z in 0..size[2] {
+                    field.set(x, y, z, f(x, y, z));
+                }
+            }
+        }
+        field
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        (x * self.size[1] + y) * self.size[2] + z
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> f32 {
+        self.values[self.index(x, y, z)]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, value: f32) {
+        let idx = self.index(x, y, z);
+        self.values[idx] = value;
+    }
+
+    fn position(&self, x: usize, y: usize, z: usize) -> Vector3<f32> {
+        Vector3::new(
+            self.origin[0] + x as f32 * self.spacing,
+            self.origin[1] + y as f32 * self.spacing,
+            self.origin[2] + z as f32 * self.spacing,
+        )
+    }
+}
+
+// cube corners, in grid offsets
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0], [1, 0, 0], [1, 1, 0], [0, 1, 0],
+    [0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1],
+];
+
+// Each cube is split in 6 tetrahedra around its 0-6 diagonal. Neighbouring cubes then
+// split their shared face along the same diagonal, which keeps the surface watertight
+// and avoids the ambiguous cases of the classic 256 entries lookup table.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 5, 1, 6],
+    [0, 1, 2, 6],
+    [0, 2, 3, 6],
+    [0, 3, 7, 6],
+    [0, 7, 4, 6],
+    [0, 4, 5, 6],
+];
+
+// Polygonises the iso surface of the field, the vertices lying on the same grid edge are shared
+pub fn marching_cubes(field: &DensityField, iso_level: f32, color: [f32; 3]) -> Mesh {
+    let mut vertices: Vec<ColorVertex> = vec![];
+    let mut indices: Vec<u16> = vec![];
+    let mut edge_vertices: HashMap<(usize, usize), u16> = HashMap::new();
+
+    let [size_x, size_y, size_z] = field.size;
+    if size_x < 2 || size_y < 2 || size_z < 2 {
+        return Mesh::new(vertices, indices);
+    }
+
+    for x in 0..size_x - 1 {
+        for y in 0..size_y - 1 {
+            for z in 0..size_z - 1 {
+                let corners: Vec<[usize; 3]> = CORNERS.iter()
+                    .map(|c| [x + c[0], y + c[1], z + c[2]])
+                    .collect();
+                for tetrahedron in TETRAHEDRA.iter() {
+                    let points: Vec<[usize; 3]> = tetrahedron.iter().map(|&c| corners[c]).collect();
+                    polygonise_tetrahedron(field, iso_level, color, &points, &mut vertices, &mut indices, &mut edge_vertices);
+                }
+            }
+        }
+    }
+
+    Mesh::new(vertices, indices)
+}
+
+fn polygonise_tetrahedron(
+    field: &DensityField,
+    iso_level: f32,
+    color: [f32; 3],
+    points: &[[usize; 3]],
+    vertices: &mut Vec<ColorVertex>,
+    indices: &mut Vec<u16>,
+    edge_vertices: &mut HashMap<(usize, usize), u16>,
+) {
+    let (inside, outside): (Vec<[usize; 3]>, Vec<[usize; 3]>) = points.iter()
+        .partition(|p| field.get(p[0], p[1], p[2]) > iso_level);
+
+    // vertex on the edge between an inside and an outside point
+    let mut edge_vertex = |a: [usize; 3], b: [usize; 3]| -> u16 {
+        let key_a = field.index(a[0], a[1], a[2]);
+        let key_b = field.index(b[0], b[1], b[2]);
+        let key = (key_a.min(key_b), key_a.max(key_b));
+        *edge_vertices.entry(key).or_insert_with(|| {
+            let density_a = field.get(a[0], a[1], a[2]);
+            let density_b = field.get(b[0], b[1], b[2]);
+            let t = (iso_level - density_a) / (density_b - density_a);
+            let position = field.position(a[0], a[1], a[2]).lerp(field.position(b[0], b[1], b[2]), t);
+            vertices.push(ColorVertex { position: position.into(), color, normal: [0.0; 3], tex_coords: atlas::PLAIN_TEX_COORDS, ao: 1.0, material_id: material::PLAIN_MATERIAL });
+            (vertices.len() - 1) as u16
+        })
+    };
+
+    let triangles: Vec<[u16; 3]> = match (inside.len(), outside.len()) {
+        (1, 3) => vec![[
+            edge_vertex(inside[0], outside[0]),
+            edge_vertex(inside[0], outside[1]),
+            edge_vertex(inside[0], outside[2]),
+        ]],
+        (3, 1) => vec![[
+            edge_vertex(inside[0], outside[0]),
+            edge_vertex(inside[1], outside[0]),
+            edge_vertex(inside[2], outside[0]),
+        ]],
+        (2, 2) => {
+            // the section is a quad going around the tetrahedron
+            let a = edge_vertex(inside[0], outside[0]);
+            let b = edge_vertex(inside[0], outside[1]);
+            let c = edge_vertex(inside[1], outside[1]);
+            let d = edge_vertex(inside[1], outside[0]);
+            vec![[a, b, c], [a, c, d]]
+        }
+        _ => vec![],
+    };
+
+    // the winding has to be clockwise when looking from the empty side
+    let centroid = |points: &[[usize; 3]]| -> Vector3<f32> {
+        points.iter().fold(Vector3::zero(), |acc, p| acc + field.position(p[0], p[1], p[2])) / points.len() as f32
+    };
+    let outward = centroid(&outside) - centroid(&inside);
+    for triangle in triangles {
+        let a = Vector3::from(vertices[triangle[0] as usize].position);
+        let b = Vector3::from(vertices[triangle[1] as usize].position);
+        let c = Vector3::from(vertices[triangle[2] as usize].position);
+        if (c - a).cross(b - a).dot(outward) >= 0.0 {
+            indices.extend_from_slice(&triangle);
+        } else {
+            indices.extend_from_slice(&[triangle[0], triangle[2], triangle[1]]);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use cgmath::*;
+use wgpu::util::DeviceExt;
+use crate::render::{material, memory, vertex::ColorVertex};
+use crate::render::gpu_mesh::GpuMesh;
+use super::atlas;
+
+// Triangles indexing into their vertices, as given by the mesh generation
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<ColorVertex>,
+    pub indices: Vec<u16>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<ColorVertex>, indices: Vec<u16>) -> Self {
+        Mesh { vertices, indices }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    // the indices of other are moved past the vertices already there
+    pub fn append(&mut self, other: &Mesh) {
+        let base = self.vertices.len() as u16;
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices.extend(other.indices.iter().map(|&i| i + base));
+    }
+
+    // the buffers are not meant to be written again, see GrowableBuffer for the chunks
+    pub fn upload(&self, device: &wgpu::Device, label: &str) -> GpuMesh {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} VB", label)),
+            contents: bytemuck::cast_slice(&self.vertices),
+            usage: wgpu::BufferUsage::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} IB", label)),
+            contents: bytemuck::cast_slice(&self.indices),
+            usage: wgpu::BufferUsage::INDEX,
+        });
+        GpuMesh {
+            vertex_buffer,
+            index_buffer,
+            num_index: self.indices.len() as u32,
+            memory_bytes: memory::init_buffer_size(std::mem::size_of_val(self.vertices.as_slice()))
+                + memory::init_buffer_size(std::mem::size_of_val(self.indices.as_slice())),
+        }
+    }
+}
+
+// Accumulates the vertices and indices of a mesh, one quad at a time
+#[derive(Default)]
+pub struct MeshBuilder {
+    pub vertices: Vec<ColorVertex>,
+    pub indices: Vec<u16>,
+}
+
+impl MeshBuilder {
+    pub fn new() -> Self {
+        MeshBuilder::default()
+    }
+
+    // untextured quad, the corners go clockwise when looking at its front side
+    pub fn add_quad(&mut self, corners: [[f32; 3]; 4], color: [f32; 3], normal: [f32; 3]) {
+        self.add_textured_quad(corners, [atlas::PLAIN_TEX_COORDS; 4], color, normal);
+    }
+
+    // Each quad gets its own 4 vertices and is split along its 0-2 diagonal, both
+    // triangles keep the clockwise winding of the corners
+    pub fn add_textured_quad(&mut self, corners: [[f32; 3]; 4], tex_coords: [[f32; 2]; 4], color: [f32; 3], normal: [f32; 3]) {
+        self.add_shaded_quad(corners, tex_coords, color, normal, [1.0; 4], material::PLAIN_MATERIAL);
+    }
+
+    // textured quad with the ambient occlusion of each corner and the material of its block
+    pub fn add_shaded_quad(&mut self, corners: [[f32; 3]; 4], tex_coords: [[f32; 2]; 4], color: [f32; 3], normal: [f32; 3], ao: [f32; 4], material_id: u32) {
+        let base = self.vertices.len() as u16;
+        for ((&position, &tex_coords), &ao) in corners.iter().zip(tex_coords.iter()).zip(ao.iter()) {
+            self.vertices.push(ColorVertex { position, color, normal, tex_coords, ao, material_id });
+        }
+        self.indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    // A line from the center of each quad along its normal, as pairs of vertices for a
+    // line list. The color of a line tells the axis of the normal.
+    pub fn normal_lines(&self, length: f32) -> Vec<ColorVertex> {
+        self.vertices.chunks_exact(4).flat_map(|quad| {
+            let center = quad.iter().fold(Vector3::zero(), |sum, v| sum + Vector3::from(v.position)) / 4.0;
+            let normal = Vector3::from(quad[0].normal);
+            let color = [normal.x.abs(), normal.y.abs(), normal.z.abs()];
+            let vertex = |position: Vector3<f32>| ColorVertex { position: position.into(), color, normal: normal.into(), tex_coords: atlas::PLAIN_TEX_COORDS, ao: 1.0, material_id: material::PLAIN_MATERIAL };
+            vec![vertex(center), vertex(center + normal * length)]
+        }).collect()
+    }
+
+    // same as Mesh::append, the quads of other come after the ones already there
+    pub fn append(&mut self, other: MeshBuilder) {
+        let base = self.vertices.len() as u16;
+        self.vertices.extend(other.vertices);
+        self.indices.extend(other.indices.into_iter().map(|i| i + base));
+    }
+
+    pub fn build(self) -> Mesh {
+        Mesh::new(self.vertices, self.indices)
+    }
+}
+
+// Why the vertices and indices cannot be drawn as a triangle list, None when they can.
+// A mesh with more vertices than a u16 can index would otherwise wrap around silently.
+pub fn mesh_error(vertices: &[ColorVertex], indices: &[u16]) -> Option<String> {
+    if vertices.len() > u16::MAX as usize + 1 {
+        return Some(format!("{} vertices cannot be indexed with u16", vertices.len()));
+    }
+    if indices.len() % 3 != 0 {
+        return Some(format!("{} indices do not make whole triangles", indices.len()));
+    }
+    indices.iter().position(|&i| i as usize >= vertices.len())
+        .map(|position| format!("index {} at {} is out of the {} vertices", indices[position], position, vertices.len()))
+}
+
+// Panics on the meshes mesh_error finds wrong, only checked in the debug builds
+pub fn debug_validate_mesh(vertices: &[ColorVertex], indices: &[u16]) {
+    if cfg!(debug_assertions) {
+        if let Some(error) = mesh_error(vertices, indices) {
+            panic!("Invalid mesh: {}", error);
+        }
+    }
+}
+
+// Averages the normals of every triangle sharing a vertex, giving a smooth shading.
+// Triangles are wound clockwise (see FrontFace::Cw in the pipeline) and bigger
+// triangles weigh more since the cross product is left unnormalized.
+pub fn compute_smooth_normals(vertices: &mut [ColorVertex], indices: &[u16]) {
+    let mut normals = vec![Vector3::<f32>::zero(); vertices.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let a = Vector3::from(vertices[triangle[0] as usize].position);
+        let b = Vector3::from(vertices[triangle[1] as usize].position);
+        let c = Vector3::from(vertices[triangle[2] as usize].position);
+        let face_normal = (c - a).cross(b - a);
+        for &i in triangle {
+            normals[i as usize] += face_normal;
+        }
+    }
+
+    for (vertex, normal) in vertices.iter_mut().zip(normals) {
+        // vertices unused by any triangle keep a null normal
+        vertex.normal = if normal.magnitude2() > 0.0 {
+            normal.normalize().into()
+        } else {
+            [0.0; 3]
+        };
+    }
+}
+
+// Merges the vertices sharing all of their attributes and points the indices
+// to the kept ones. The faces of a block differ by their color and normal so only the
+// corners of neighbouring coplanar faces end up shared, the triangles are left as is.
+pub fn deduplicate_vertices(mesh: &Mesh) -> Mesh {
+    let mut unique: Vec<ColorVertex> = vec![];
+    let mut remap: HashMap<[u32; 13], u16> = HashMap::new();
+
+    let new_indices = mesh.indices.iter().map(|&i| {
+        let vertex = mesh.vertices[i as usize];
+        *remap.entry(vertex_key(&vertex)).or_insert_with(|| {
+            unique.push(vertex);
+            (unique.len() - 1) as u16
+        })
+    }).collect();
+
+    Mesh::new(unique, new_indices)
+}
+
+fn vertex_key(vertex: &ColorVertex) -> [u32; 13] {
+    let mut key = [0; 13];
+    let values = vertex.position.iter()
+        .chain(vertex.color.iter())
+        .chain(vertex.normal.iter())
+        .chain(vertex.tex_coords.iter())
+        .chain(std::iter::once(&vertex.ao));
+    for (k, v) in key.iter_mut().zip(values) {
+        // adding 0 turns -0 into 0, they would have different bits otherwise
+        *k = (v + 0.0).to_bits();
+    }
+    key[12] = vertex.material_id;
+    key
+}
@@ -0,0 +1,55 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use super::chunk::{Chunk, MeshMode};
+use super::mesh::Mesh;
+
+pub const DEFAULT_MESH_CACHE_CAPACITY: usize = 16;
+
+// Everything the mesh of a chunk depends on. An edit changes the content hash, so
+// the mesh built before it is never handed out for the edited chunk again. The jitter
+// follows the world coordinates and the seed, a jittered mesh is only shared by its chunk.
+pub fn mesh_key(chunk: &Chunk, mode: MeshMode, dedup: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    chunk.content_hash().hash(&mut hasher);
+    chunk.world_floor.hash(&mut hasher);
+    chunk.lod.hash(&mut hasher);
+    chunk.terrain.color_jitter.to_bits().hash(&mut hasher);
+    if chunk.terrain.color_jitter != 0.0 {
+        chunk.position.hash(&mut hasher);
+        chunk.terrain.seed.hash(&mut hasher);
+    }
+    mode.hash(&mut hasher);
+    dedup.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Meshes of the last chunks built, the oldest one is dropped when it is full
+pub struct MeshCache {
+    meshes: HashMap<u64, Mesh>,
+    order: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl MeshCache {
+    pub fn new(capacity: usize) -> Self {
+        MeshCache {
+            meshes: HashMap::new(),
+            order: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn get_or_insert_with<F: FnOnce() -> Mesh>(&mut self, key: u64, build: F) -> &Mesh {
+        if !self.meshes.contains_key(&key) {
+            if self.order.len() >= self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.meshes.remove(&oldest);
+                }
+            }
+            self.meshes.insert(key, build());
+            self.order.push_back(key);
+        }
+        &self.meshes[&key]
+    }
+}
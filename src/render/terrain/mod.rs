@@ -1,3 +1,13 @@
+pub mod atlas;
 pub mod block;
 pub mod chunk;
-pub mod noise;
\ No newline at end of file
+pub mod chunk_manager;
+pub mod lighting;
+pub mod lod;
+pub mod marching_cubes;
+pub mod mesh;
+pub mod mesh_cache;
+pub mod noise;
+pub mod ore;
+pub mod registry;
+pub mod voxel_file;
\ No newline at end of file
@@ -1,18 +1,84 @@
-use noise::{Seedable, NoiseFn, OpenSimplex};
+use anyhow::*;
+use std::str::FromStr;
+use noise::{Seedable, NoiseFn, Fbm, MultiFractal, OpenSimplex, Value};
+
+// Noise functions the terrain can be generated from, they all give values in [-1, 1]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseKind {
+    #[default]
+    OpenSimplex,
+    Perlin,
+    // blockier, interpolated between random values on a grid
+    Value,
+}
+
+impl FromStr for NoiseKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "opensimplex" => Ok(NoiseKind::OpenSimplex),
+            "perlin" => Ok(NoiseKind::Perlin),
+            "value" => Ok(NoiseKind::Value),
+            _ => bail!("expected opensimplex, perlin or value"),
+        }
+    }
+}
+
+// the terrain samples as well the plane as the volume
+trait Noise: NoiseFn<[f64; 2]> + NoiseFn<[f64; 3]> {}
+impl<T: NoiseFn<[f64; 2]> + NoiseFn<[f64; 3]>> Noise for T {}
 
 pub struct NoiseGenerator {
-    generator: OpenSimplex,
+    generator: Box<dyn Noise>,
 }
 
 impl NoiseGenerator {
-    pub fn from_seed(seed: u32) -> Self {
-        let generator = OpenSimplex::new();
-        generator.set_seed(seed);
+    pub fn from_seed(seed: u32, kind: NoiseKind) -> Self {
+        // set_seed gives back a new generator, the one it is called on is left as is
+        let generator: Box<dyn Noise> = match kind {
+            NoiseKind::OpenSimplex => Box::new(OpenSimplex::new().set_seed(seed)),
+            // a single octave of fbm is plain Perlin noise, the crate exports two Perlin types
+            NoiseKind::Perlin => Box::new(Fbm::new().set_octaves(1).set_seed(seed)),
+            NoiseKind::Value => Box::new(Value::new().set_seed(seed)),
+        };
 
         NoiseGenerator { generator }
     }
 
     pub fn get(&self, x: f64, z: f64) -> f64{
-        self.generator.get([x, z])
+        NoiseFn::<[f64; 2]>::get(&*self.generator, [x, z])
+    }
+
+    pub fn get_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        NoiseFn::<[f64; 3]>::get(&*self.generator, [x, y, z])
     }
-}
\ No newline at end of file
+}
+// Random value in [0, 1[ for a block, always the same for the same seed and world
+// coordinates so decorations do not depend on the order the chunks are generated in
+pub fn hash_rng(seed: u32, x: i32, y: i32, z: i32) -> f32 {
+    // the 24 top bits fit exactly in the mantissa of a f32
+    (hash_coords(seed, x, y, z) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+// same hash as hash_rng, as a whole number to derive other seeds from
+pub fn hash_seed(seed: u32, x: i32, y: i32, z: i32) -> u32 {
+    (hash_coords(seed, x, y, z) >> 32) as u32
+}
+
+fn hash_coords(seed: u32, x: i32, y: i32, z: i32) -> u64 {
+    let mut hash = mix(seed as u64);
+    for &value in [x, y, z].iter() {
+        hash = mix(hash ^ value as u32 as u64);
+    }
+    hash
+}
+
+// splitmix64 finalizer, every input bit changes about half of the output bits
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,80 @@
+use super::block::BlockType;
+use super::chunk::{Chunk, CHUNK_HEIGHT, CHUNK_WIDTH};
+use super::noise::{hash_rng, NoiseGenerator, NoiseKind};
+
+// the ores get their own noise and random values, apart from the terrain ones
+const ORE_SEED: u32 = 0x0DE5_EED5;
+// one cluster of veins every 8 blocks or so
+const ORE_FREQUENCY: f64 = 1.0 / 8.0;
+// directions a vein can grow in, one block at a time
+const VEIN_STEPS: [[i32; 3]; 6] = [
+    [1, 0, 0], [-1, 0, 0],
+    [0, 1, 0], [0, -1, 0],
+    [0, 0, 1], [0, 0, -1],
+];
+
+// How an ore is spread through the stone
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OreConfig {
+    pub block_type: BlockType,
+    // chance of a stone block to start a vein, on average
+    pub rarity: f32,
+    // the veins stay under this height, in blocks
+    pub max_height: usize,
+    // blocks visited by a vein, some of them may not be stone
+    pub vein_size: usize,
+}
+
+pub const DEFAULT_ORES: [OreConfig; 2] = [
+    OreConfig { block_type: BlockType::COAL, rarity: 0.004, max_height: 20, vein_size: 6 },
+    OreConfig { block_type: BlockType::IRON, rarity: 0.002, max_height: 12, vein_size: 4 },
+];
+
+impl Chunk {
+    // Turns some stone into ore veins. The veins start where a random value falls under the
+    // rarity scaled by a 3d noise, which gathers them in clusters, then walk randomly.
+    // Everything depends on the world coordinates so neighbouring chunks agree.
+    pub fn place_ores(&mut self, ores: &[OreConfig]) {
+        let seed = self.terrain.seed ^ ORE_SEED;
+        let noise = NoiseGenerator::from_seed(seed, NoiseKind::OpenSimplex);
+        let origin_x = self.position.0 * CHUNK_WIDTH as i32;
+        let origin_z = self.position.1 * CHUNK_WIDTH as i32;
+
+        for (ore_index, ore) in ores.iter().enumerate() {
+            let ore_seed = seed.wrapping_add(ore_index as u32);
+            for x in 0..CHUNK_WIDTH {
+                for y in 0..ore.max_height.min(CHUNK_HEIGHT) {
+                    for z in 0..CHUNK_WIDTH {
+                        if self.blocks[x][y][z].block_type != BlockType::STONE {
+                            continue;
+                        }
+                        let (wx, wz) = (origin_x + x as i32, origin_z + z as i32);
+                        let density = (noise.get_3d(wx as f64 * ORE_FREQUENCY, y as f64 * ORE_FREQUENCY, wz as f64 * ORE_FREQUENCY) + 1.0) as f32;
+                        if hash_rng(ore_seed, wx, y as i32, wz) < ore.rarity * density {
+                            self.grow_vein(ore, ore_seed, [x as i32, y as i32, z as i32]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // the vein only replaces stone, and stops wherever it would leave the chunk or its height
+    fn grow_vein(&mut self, ore: &OreConfig, seed: u32, start: [i32; 3]) {
+        let (origin_x, origin_z) = (self.position.0 * CHUNK_WIDTH as i32, self.position.1 * CHUNK_WIDTH as i32);
+        let mut position = start;
+        for step in 0..ore.vein_size {
+            let [x, y, z] = position;
+            if x < 0 || z < 0 || x >= CHUNK_WIDTH as i32 || z >= CHUNK_WIDTH as i32 || y < 0 || y >= ore.max_height.min(CHUNK_HEIGHT) as i32 {
+                return;
+            }
+            if self.blocks[x as usize][y as usize][z as usize].block_type == BlockType::STONE {
+                self.blocks[x as usize][y as usize][z as usize].block_type = ore.block_type;
+            }
+
+            let random = hash_rng(seed.wrapping_add(step as u32 + 1), origin_x + x, y, origin_z + z);
+            let direction = VEIN_STEPS[(random * VEIN_STEPS.len() as f32) as usize % VEIN_STEPS.len()];
+            position = [x + direction[0], y + direction[1], z + direction[2]];
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::Path;
+use anyhow::*;
+use serde::{Deserialize, Serialize};
+use crate::render::material::MAX_MATERIALS;
+use super::atlas::{self, BlockTextures};
+use super::block::{BlockType, Faces};
+
+// Block definitions read from JSON. The chunks store the id of a definition, the meshing,
+// the light and the collisions read the behavior of the block from it, and the materials
+// uniform its tiles and highlight. A file can change the blocks of the engine and add new
+// ones after them, there is room for one block per material of simple.frag.
+pub const MAX_BLOCK_TYPES: usize = MAX_MATERIALS;
+// the definitions of the engine, a file given with --blocks goes over them
+const BUILTIN_BLOCKS: &str = include_str!("blocks.json");
+
+// How the meshing draws a block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockMesh {
+    // nothing is drawn, like the air
+    Empty,
+    // the opaque cubes of the chunk mesh
+    #[default]
+    Cube,
+    // a solid cube blended over the rest, the blocks behind it can be seen through
+    Glass,
+    // only its surface is drawn, moved by the water shader
+    Water,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockDefinition {
+    pub name: String,
+    // the BlockType stored in the chunks for this block
+    pub id: u32,
+    pub color: [f32; 3],
+    // the top faces take the color when None
+    #[serde(default)]
+    pub top_color: Option<[f32; 3]>,
+    #[serde(default)]
+    pub mesh: BlockMesh,
+    // drawn at full brightness, and a source for the light propagation
+    #[serde(default)]
+    pub emissive: bool,
+    // the plain tile of the atlas when None
+    #[serde(default)]
+    pub tiles: Option<BlockTextures>,
+    // the highlight of simple.frag, a matte block when left out
+    #[serde(default)]
+    pub specular: f32,
+    #[serde(default = "default_shininess")]
+    pub shininess: f32,
+}
+
+fn default_shininess() -> f32 {
+    1.0
+}
+
+impl BlockDefinition {
+    pub fn face_color(&self, face: Faces) -> [f32; 3] {
+        match (face, self.top_color) {
+            (Faces::TOP, Some(color)) => color,
+            _ => self.color,
+        }
+    }
+
+    // the faces of the blocks behind can be seen through it
+    pub fn is_transparent(&self) -> bool {
+        self.mesh != BlockMesh::Cube
+    }
+
+    // stops the walking camera, the water and the air let it through
+    pub fn is_solid(&self) -> bool {
+        matches!(self.mesh, BlockMesh::Cube | BlockMesh::Glass)
+    }
+
+    pub fn textures(&self) -> BlockTextures {
+        self.tiles.unwrap_or(BlockTextures::all(atlas::PLAIN_TILE))
+    }
+}
+
+// The blocks the chunks can hold. A block of a file takes the place of the one with the
+// same id, the ones it leaves out keep their builtin definition, and the new ids follow
+// the last one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockRegistry {
+    // indexed by the ids
+    blocks: Vec<BlockDefinition>,
+}
+
+impl Default for BlockRegistry {
+    fn default() -> Self {
+        BlockRegistry::builtin()
+    }
+}
+
+impl BlockRegistry {
+    pub fn builtin() -> Self {
+        let mut registry = BlockRegistry { blocks: vec![] };
+        registry.add(parse_definitions(BUILTIN_BLOCKS).expect("Invalid builtin block definitions"))
+            .expect("Invalid builtin block definitions");
+        registry
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let mut registry = BlockRegistry::builtin();
+        registry.add(parse_definitions(text)?)?;
+        Ok(registry)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read the block definitions {}", path.display()))?;
+        BlockRegistry::from_json(&text).with_context(|| format!("Invalid block definitions {}", path.display()))
+    }
+
+    // in the order of the ids, so a new block can follow one given just before it
+    fn add(&mut self, mut blocks: Vec<BlockDefinition>) -> Result<()> {
+        blocks.sort_by_key(|block| block.id);
+        for block in blocks {
+            let id = block.id as usize;
+            if id < self.blocks.len() {
+                self.blocks[id] = block;
+            } else if id == self.blocks.len() {
+                self.blocks.push(block);
+            } else {
+                bail!("The block {} has the id {}, the next new id is {}", block.name, block.id, self.blocks.len());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, block_type: BlockType) -> &BlockDefinition {
+        &self.blocks[block_type.0 as usize]
+    }
+
+    // the ids past the last block are not in the registry
+    pub fn contains(&self, block_type: BlockType) -> bool {
+        (block_type.0 as usize) < self.blocks.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BlockDefinition> {
+        self.blocks.iter()
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&BlockDefinition> {
+        self.blocks.iter().find(|block| block.name == name)
+    }
+
+    pub fn face_color(&self, block_type: BlockType, face: Faces) -> [f32; 3] {
+        self.get(block_type).face_color(face)
+    }
+}
+
+fn parse_definitions(text: &str) -> Result<Vec<BlockDefinition>> {
+    // JSON has no comments, a file may put its blocks in an object next to a note
+    let mut file: serde_json::Value = serde_json::from_str(text)?;
+    let blocks = match file.get_mut("blocks") {
+        Some(blocks) => blocks.take(),
+        None => file,
+    };
+    let blocks: Vec<BlockDefinition> = serde_json::from_value(blocks)?;
+    for block in blocks.iter() {
+        if block.id as usize >= MAX_BLOCK_TYPES {
+            bail!("The block {} has the id {}, the ids go from 0 to {}", block.name, block.id, MAX_BLOCK_TYPES - 1);
+        }
+        let textures = block.textures();
+        if let Some(tile) = [textures.top, textures.bottom, textures.side].iter().find(|&&tile| tile >= atlas::TILE_COUNT) {
+            bail!("The block {} has the tile {}, the atlas goes from 0 to {}", block.name, tile, atlas::TILE_COUNT - 1);
+        }
+    }
+    Ok(blocks)
+}
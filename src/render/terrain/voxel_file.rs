@@ -0,0 +1,79 @@
+use anyhow::*;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use super::block::{Block, BlockType};
+use super::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+use super::registry::BlockRegistry;
+
+// Hand-written scenes, as a stack of layers going up from y = 0. A layer has one line per z
+// and one character per x, layers are separated by an empty line and lines starting with
+// '#' are comments. Anything left out of the file is air.
+//
+//   . air   s stone   d dirt   g grass   w water
+//   c coal   i iron   l lamp   x glass
+impl BlockType {
+    pub fn from_voxel_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(BlockType::AIR),
+            's' => Some(BlockType::STONE),
+            'd' => Some(BlockType::DIRT),
+            'g' => Some(BlockType::GRASS),
+            'w' => Some(BlockType::WATER),
+            'c' => Some(BlockType::COAL),
+            'i' => Some(BlockType::IRON),
+            'l' => Some(BlockType::LAMP),
+            'x' => Some(BlockType::GLASS),
+            _ => None,
+        }
+    }
+}
+
+impl Chunk {
+    pub fn from_voxel_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read the voxel file {}", path.display()))?;
+        Chunk::from_voxel_str(&text).with_context(|| format!("Invalid voxel file {}", path.display()))
+    }
+
+    pub fn from_voxel_str(text: &str) -> Result<Self> {
+        let mut chunk = Chunk {
+            width: CHUNK_WIDTH,
+            height: CHUNK_HEIGHT,
+            blocks: [[[Block::from(BlockType::AIR); CHUNK_WIDTH]; CHUNK_HEIGHT]; CHUNK_WIDTH],
+            // the scene is saved in the sessions as edits of the default terrain
+            terrain: TerrainConfig::default(),
+            dirty: true,
+            world_floor: true,
+            position: (0, 0),
+            lod: 0,
+            registry: Arc::new(BlockRegistry::builtin()),
+        };
+
+        let (mut y, mut z) = (0, 0);
+        for (line_number, line) in text.lines().enumerate().map(|(n, l)| (n + 1, l.trim_end())) {
+            if line.starts_with('#') {
+                continue;
+            }
+            if line.is_empty() {
+                // several empty lines still only end one layer
+                if z > 0 {
+                    y += 1;
+                    z = 0;
+                }
+                continue;
+            }
+            ensure!(y < CHUNK_HEIGHT, "line {}: more than {} layers", line_number, CHUNK_HEIGHT);
+            ensure!(z < CHUNK_WIDTH, "line {}: more than {} lines in the layer", line_number, CHUNK_WIDTH);
+            ensure!(line.chars().count() <= CHUNK_WIDTH, "line {}: more than {} blocks", line_number, CHUNK_WIDTH);
+            for (x, c) in line.chars().enumerate() {
+                chunk.blocks[x][y][z].block_type = BlockType::from_voxel_char(c)
+                    .with_context(|| format!("line {}: unknown block '{}'", line_number, c))?;
+            }
+            z += 1;
+        }
+
+        Ok(chunk)
+    }
+}
@@ -0,0 +1,390 @@
+use std::mem;
+use wgpu::util::DeviceExt;
+use super::{memory, shader, texture, vertex::Vertex};
+
+// Glyphs are 3x5 pixels, each row is stored on 3 bits with the leftmost pixel first
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+// an empty column between glyphs in the atlas prevents them from bleeding into each other
+const ATLAS_CELL_WIDTH: u32 = GLYPH_WIDTH + 1;
+// size of a font pixel on screen
+pub const TEXT_SCALE: f32 = 3.0;
+pub const TEXT_MARGIN: f32 = 8.0;
+const ADVANCE: f32 = (GLYPH_WIDTH + 1) as f32 * TEXT_SCALE;
+const LINE_HEIGHT: f32 = (GLYPH_HEIGHT + 1) as f32 * TEXT_SCALE;
+const TEXT_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+
+#[rustfmt::skip]
+const FONT: [(char, [u8; 5]); 53] = [
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('A', [0b010, 0b101, 0b111, 0b101, 0b101]),
+    ('B', [0b110, 0b101, 0b110, 0b101, 0b110]),
+    ('C', [0b011, 0b100, 0b100, 0b100, 0b011]),
+    ('D', [0b110, 0b101, 0b101, 0b101, 0b110]),
+    ('E', [0b111, 0b100, 0b110, 0b100, 0b111]),
+    ('F', [0b111, 0b100, 0b110, 0b100, 0b100]),
+    ('G', [0b011, 0b100, 0b101, 0b101, 0b011]),
+    ('H', [0b101, 0b101, 0b111, 0b101, 0b101]),
+    ('I', [0b111, 0b010, 0b010, 0b010, 0b111]),
+    ('J', [0b001, 0b001, 0b001, 0b101, 0b010]),
+    ('K', [0b101, 0b101, 0b110, 0b101, 0b101]),
+    ('L', [0b100, 0b100, 0b100, 0b100, 0b111]),
+    ('M', [0b101, 0b111, 0b111, 0b101, 0b101]),
+    ('N', [0b110, 0b101, 0b101, 0b101, 0b101]),
+    ('O', [0b010, 0b101, 0b101, 0b101, 0b010]),
+    ('P', [0b110, 0b101, 0b110, 0b100, 0b100]),
+    ('Q', [0b010, 0b101, 0b101, 0b110, 0b011]),
+    ('R', [0b110, 0b101, 0b110, 0b101, 0b101]),
+    ('S', [0b011, 0b100, 0b010, 0b001, 0b110]),
+    ('T', [0b111, 0b010, 0b010, 0b010, 0b010]),
+    ('U', [0b101, 0b101, 0b101, 0b101, 0b111]),
+    ('V', [0b101, 0b101, 0b101, 0b101, 0b010]),
+    ('W', [0b101, 0b101, 0b111, 0b111, 0b101]),
+    ('X', [0b101, 0b101, 0b010, 0b101, 0b101]),
+    ('Y', [0b101, 0b101, 0b010, 0b010, 0b010]),
+    ('Z', [0b111, 0b001, 0b010, 0b100, 0b111]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+    (',', [0b000, 0b000, 0b000, 0b010, 0b100]),
+    (':', [0b000, 0b010, 0b000, 0b010, 0b000]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+    ('+', [0b000, 0b010, 0b111, 0b010, 0b000]),
+    ('=', [0b000, 0b111, 0b000, 0b111, 0b000]),
+    ('/', [0b001, 0b001, 0b010, 0b100, 0b100]),
+    ('(', [0b010, 0b100, 0b100, 0b100, 0b010]),
+    (')', [0b010, 0b001, 0b001, 0b001, 0b010]),
+    ('[', [0b110, 0b100, 0b100, 0b100, 0b110]),
+    (']', [0b011, 0b001, 0b001, 0b001, 0b011]),
+    ('%', [0b101, 0b001, 0b010, 0b100, 0b101]),
+    ('_', [0b000, 0b000, 0b000, 0b000, 0b111]),
+    ('<', [0b001, 0b010, 0b100, 0b010, 0b001]),
+    ('>', [0b100, 0b010, 0b001, 0b010, 0b100]),
+    ('|', [0b010, 0b010, 0b010, 0b010, 0b010]),
+    // also used for the characters missing from the font
+    ('?', [0b111, 0b001, 0b010, 0b000, 0b010]),
+];
+
+fn glyph_index(c: char) -> usize {
+    let c = c.to_ascii_uppercase();
+    FONT.iter()
+        .position(|(glyph, _)| *glyph == c)
+        .unwrap_or(FONT.len() - 1)
+}
+
+/** Text Vertex **/
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TextVertex {
+    // in pixels from the top left corner of the screen
+    pub position: [f32; 2],
+    pub tex_coords: [f32; 2],
+    pub color: [f32; 3],
+}
+
+impl Vertex for TextVertex {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<TextVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                // Position
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float2,
+                },
+                // Tex_coords
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float2,
+                },
+                // Color
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float3,
+                },
+            ],
+        }
+    }
+}
+
+// Pixels taken by the lines drawn from the margin, the margin around them included
+pub fn text_size(lines: &[String]) -> [u32; 2] {
+    let columns = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let width = 2.0 * TEXT_MARGIN + columns as f32 * ADVANCE;
+    let height = 2.0 * TEXT_MARGIN + lines.len() as f32 * LINE_HEIGHT;
+    [width.ceil() as u32, height.ceil() as u32]
+}
+
+// One quad per visible character, lines are stacked from the origin downwards
+pub fn build_glyph_quads(lines: &[String], origin: [f32; 2], color: [f32; 3]) -> (Vec<TextVertex>, Vec<u16>) {
+    let mut vertices: Vec<TextVertex> = vec![];
+    let mut indices: Vec<u16> = vec![];
+    let atlas_width = (FONT.len() as u32 * ATLAS_CELL_WIDTH) as f32;
+
+    for (row, line) in lines.iter().enumerate() {
+        let y = origin[1] + row as f32 * LINE_HEIGHT;
+        for (column, c) in line.chars().enumerate() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let x = origin[0] + column as f32 * ADVANCE;
+            let w = GLYPH_WIDTH as f32 * TEXT_SCALE;
+            let h = GLYPH_HEIGHT as f32 * TEXT_SCALE;
+            let u0 = (glyph_index(c) as u32 * ATLAS_CELL_WIDTH) as f32 / atlas_width;
+            let u1 = u0 + GLYPH_WIDTH as f32 / atlas_width;
+
+            let base = vertices.len() as u16;
+            vertices.push(TextVertex { position: [x, y], tex_coords: [u0, 0.0], color });
+            vertices.push(TextVertex { position: [x + w, y], tex_coords: [u1, 0.0], color });
+            vertices.push(TextVertex { position: [x + w, y + h], tex_coords: [u1, 1.0], color });
+            vertices.push(TextVertex { position: [x, y + h], tex_coords: [u0, 1.0], color });
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+    }
+
+    (vertices, indices)
+}
+
+// white glyphs on a transparent background, laid out on a single row
+fn create_font_image() -> image::DynamicImage {
+    let mut img = image::RgbaImage::new(FONT.len() as u32 * ATLAS_CELL_WIDTH, GLYPH_HEIGHT);
+    for (i, (_, rows)) in FONT.iter().enumerate() {
+        for (y, row) in rows.iter().enumerate() {
+            for x in 0..GLYPH_WIDTH {
+                if row & (1 << (GLYPH_WIDTH - 1 - x)) != 0 {
+                    img.put_pixel(i as u32 * ATLAS_CELL_WIDTH + x, y as u32, image::Rgba([255, 255, 255, 255]));
+                }
+            }
+        }
+    }
+    image::DynamicImage::ImageRgba8(img)
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ScreenUniform {
+    size: [f32; 2],
+    _padding: [f32; 2],
+}
+
+// Draws the debug text on top of the scene, in screen space
+pub struct TextRenderer {
+    pipeline: wgpu::RenderPipeline,
+    screen_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    num_index: u32,
+    // the font and the screen size, which never change
+    fixed_memory_bytes: u64,
+    text_memory_bytes: u64,
+    // pixels taken by the text, from the top left corner
+    size: [u32; 2],
+}
+
+impl TextRenderer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, color_format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        let mut atlas = texture::Texture::from_image(device, queue, &create_font_image(), Some("font_atlas"), false).unwrap();
+        // the font is pixel art, it must not be smoothed
+        atlas.sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let screen_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Text Screen Buffer"),
+            contents: bytemuck::cast_slice(&[ScreenUniform { size: [width as f32, height as f32], _padding: [0.0; 2] }]),
+            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler {
+                        comparison: false,
+                        filtering: true,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStage::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("text_bind_group_layout"),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&atlas.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&atlas.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: screen_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("text_bind_group"),
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Text Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let vs_module = shader::create_module(device, "Text Pipeline", wgpu::include_spirv!("shaders/text.vert.spv"));
+        let fs_module = shader::create_module(device, "Text Pipeline", wgpu::include_spirv!("shaders/text.frag.spv"));
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Text Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &vs_module,
+                entry_point: "main",
+                buffers: &[TextVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &fs_module,
+                entry_point: "main",
+                targets: &[wgpu::ColorTargetState {
+                    format: color_format,
+                    alpha_blend: wgpu::BlendState::REPLACE,
+                    color_blend: wgpu::BlendState {
+                        src_factor: wgpu::BlendFactor::SrcAlpha,
+                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    write_mask: wgpu::ColorWrite::ALL,
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: wgpu::CullMode::None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+            },
+            // the text is always drawn over the scene
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        let (vertex_buffer, index_buffer) = create_text_buffers(device, &[], &[]);
+        let fixed_memory_bytes = atlas.memory_bytes + memory::init_buffer_size(mem::size_of::<ScreenUniform>());
+
+        TextRenderer {
+            pipeline,
+            screen_buffer,
+            bind_group,
+            vertex_buffer,
+            index_buffer,
+            num_index: 0,
+            fixed_memory_bytes,
+            text_memory_bytes: text_buffers_size(&[], &[]),
+            size: [0, 0],
+        }
+    }
+
+    pub fn set_text(&mut self, device: &wgpu::Device, lines: &[String]) {
+        let (vertices, indices) = build_glyph_quads(lines, [TEXT_MARGIN, TEXT_MARGIN], TEXT_COLOR);
+        let (vertex_buffer, index_buffer) = create_text_buffers(device, &vertices, &indices);
+        self.vertex_buffer = vertex_buffer;
+        self.index_buffer = index_buffer;
+        self.num_index = indices.len() as u32;
+        self.text_memory_bytes = text_buffers_size(&vertices, &indices);
+        self.size = text_size(lines);
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    pub fn memory_bytes(&self) -> u64 {
+        self.fixed_memory_bytes + self.text_memory_bytes
+    }
+
+    pub fn resize(&self, queue: &wgpu::Queue, width: u32, height: u32) {
+        queue.write_buffer(
+            &self.screen_buffer,
+            0,
+            bytemuck::cast_slice(&[ScreenUniform { size: [width as f32, height as f32], _padding: [0.0; 2] }]),
+        );
+    }
+
+    // zero when there is no text, nothing is drawn then
+    pub fn num_index(&self) -> u32 {
+        self.num_index
+    }
+
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.num_index == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..self.num_index, 0, 0..1);
+    }
+}
+
+fn text_buffers_size(vertices: &[TextVertex], indices: &[u16]) -> u64 {
+    memory::init_buffer_size(mem::size_of_val(vertices)) + memory::init_buffer_size(mem::size_of_val(indices))
+}
+
+fn create_text_buffers(device: &wgpu::Device, vertices: &[TextVertex], indices: &[u16]) -> (wgpu::Buffer, wgpu::Buffer) {
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Text Vertex Buffer"),
+        contents: bytemuck::cast_slice(vertices),
+        usage: wgpu::BufferUsage::VERTEX,
+    });
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Text Indices Buffer"),
+        contents: bytemuck::cast_slice(indices),
+        usage: wgpu::BufferUsage::INDEX,
+    });
+
+    (vertex_buffer, index_buffer)
+}
@@ -1,16 +1,45 @@
 use anyhow::*;
 use image::GenericImageView;
 use std::path::Path;
+use super::memory;
 
 pub struct Texture {
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
     pub sampler: wgpu::Sampler,
+    pub format: wgpu::TextureFormat,
+    // size of the texture and its mip levels on the GPU
+    pub memory_bytes: u64,
 }
 
 #[allow(dead_code)]
 impl Texture {
-    pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+    // depth formats in order of preference, Depth24Plus is supported everywhere
+    pub const DEPTH_FORMATS: [wgpu::TextureFormat; 2] = [
+        wgpu::TextureFormat::Depth32Float,
+        wgpu::TextureFormat::Depth24Plus,
+    ];
+    const DEPTH_USAGE: wgpu::TextureUsage = wgpu::TextureUsage::from_bits_truncate(
+        wgpu::TextureUsage::RENDER_ATTACHMENT.bits() | wgpu::TextureUsage::SAMPLED.bits()
+    );
+
+    pub const RENDER_TARGET_USAGE: wgpu::TextureUsage = wgpu::TextureUsage::from_bits_truncate(
+        wgpu::TextureUsage::RENDER_ATTACHMENT.bits() | wgpu::TextureUsage::SAMPLED.bits()
+    );
+
+    // first format of the chain the adapter can render to and sample, the last one otherwise
+    pub fn choose_depth_format(adapter: &wgpu::Adapter, formats: &[wgpu::TextureFormat]) -> wgpu::TextureFormat {
+        Self::pick_depth_format(formats, |format| adapter.get_texture_format_features(format).allowed_usages.contains(Self::DEPTH_USAGE))
+    }
+
+    // same as choose_depth_format, usable tells which formats the adapter supports
+    pub fn pick_depth_format<F: Fn(wgpu::TextureFormat) -> bool>(formats: &[wgpu::TextureFormat], usable: F) -> wgpu::TextureFormat {
+        formats.iter()
+            .copied()
+            .find(|format| usable(*format))
+            .or_else(|| formats.last().copied())
+            .unwrap_or(wgpu::TextureFormat::Depth24Plus)
+    }
 
     pub fn load<P: AsRef<Path>>(
         device: &wgpu::Device,
@@ -29,6 +58,7 @@ impl Texture {
     pub fn create_depth_texture(
         device: &wgpu::Device,
         sc_desc: &wgpu::SwapChainDescriptor,
+        format: wgpu::TextureFormat,
         label: &str,
     ) -> Self {
         let size = wgpu::Extent3d {
@@ -42,8 +72,8 @@ impl Texture {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: Self::DEPTH_FORMAT,
-            usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+            format,
+            usage: Self::DEPTH_USAGE,
         };
         let texture = device.create_texture(&desc);
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -64,6 +94,49 @@ impl Texture {
             texture,
             view,
             sampler,
+            format,
+            memory_bytes: memory::texture_size(sc_desc.width, sc_desc.height, 1, format),
+        }
+    }
+
+    // color texture the size of the swap chain, drawn into then sampled by a later pass
+    pub fn create_render_target(
+        device: &wgpu::Device,
+        sc_desc: &wgpu::SwapChainDescriptor,
+        format: wgpu::TextureFormat,
+        label: &str,
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: sc_desc.width,
+                height: sc_desc.height,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: Self::RENDER_TARGET_USAGE,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // the texels are read one to one, no filtering is needed
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            texture,
+            view,
+            sampler,
+            format,
+            memory_bytes: memory::texture_size(sc_desc.width, sc_desc.height, 1, format),
         }
     }
 
@@ -93,17 +166,18 @@ impl Texture {
             height: dimensions.1,
             depth: 1,
         };
+        let format = if is_normal_map {
+            wgpu::TextureFormat::Rgba8Unorm
+        } else {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        };
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label,
             size,
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: if is_normal_map {
-                wgpu::TextureFormat::Rgba8Unorm
-            } else {
-                wgpu::TextureFormat::Rgba8UnormSrgb
-            },
+            format,
             usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
         });
 
@@ -137,8 +211,168 @@ impl Texture {
             texture,
             view,
             sampler,
+            format,
+            memory_bytes: memory::texture_size(dimensions.0, dimensions.1, 1, format),
         })
     }
+
+    // Uploads the image with its mip chain, sampled with trilinear filtering and the given
+    // anisotropy. An atlas stops its chain when the tiles are 1x1 so the last levels do not
+    // blend them.
+    pub fn from_image_with_mips(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        img: &image::RgbaImage,
+        label: Option<&str>,
+        format: wgpu::TextureFormat,
+        mip_level_count: u32,
+        anisotropy_clamp: Option<std::num::NonZeroU8>,
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size: wgpu::Extent3d {
+                width: img.width(),
+                height: img.height(),
+                depth: 1,
+            },
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        });
+
+        let mut level = img.clone();
+        for mip_level in 0..mip_level_count {
+            if mip_level > 0 {
+                level = downsample(&level);
+            }
+            queue.write_texture(
+                wgpu::TextureCopyView {
+                    texture: &texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                &level,
+                wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: 4 * level.width(),
+                    rows_per_image: level.height(),
+                },
+                wgpu::Extent3d {
+                    width: level.width(),
+                    height: level.height(),
+                    depth: 1,
+                },
+            );
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            // the texels stay sharp up close
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            anisotropy_clamp,
+            ..Default::default()
+        });
+
+        Self {
+            texture,
+            view,
+            sampler,
+            format,
+            memory_bytes: memory::texture_size(img.width(), img.height(), mip_level_count, format),
+        }
+    }
+}
+
+// Highest anisotropy wgpu accepts. wgpu 0.7 has no adapter or device limit for it, the
+// wgpu::Limits only hold the bind group and buffer counts, so this bound is the one the
+// sampler creation checks. A device without anisotropic filtering does not fail on it,
+// wgpu gives its sampler no anisotropy at all.
+pub const MAX_ANISOTROPY: u8 = 16;
+
+// The sampler only takes powers of two, the requested value is rounded down to one and
+// kept within 1 and max. None stands for the plain trilinear filtering.
+pub fn clamp_anisotropy(requested: u8, max: u8) -> Option<std::num::NonZeroU8> {
+    let clamped = requested.clamp(1, max.max(1));
+    let power_of_two = 1 << (7 - clamped.leading_zeros());
+    std::num::NonZeroU8::new(power_of_two).filter(|a| a.get() > 1)
+}
+
+// every level halves the size down to 1x1
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+// Averages each 2x2 square of texels, textures made of power of two tiles keep them apart
+fn downsample(image: &image::RgbaImage) -> image::RgbaImage {
+    let width = (image.width() / 2).max(1);
+    let height = (image.height() / 2).max(1);
+    image::RgbaImage::from_fn(width, height, |x, y| {
+        let mut sum = [0u32; 4];
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)].iter() {
+            let px = (x * 2 + dx).min(image.width() - 1);
+            let py = (y * 2 + dy).min(image.height() - 1);
+            for (s, c) in sum.iter_mut().zip(image.get_pixel(px, py).0.iter()) {
+                *s += *c as u32;
+            }
+        }
+        image::Rgba([(sum[0] / 4) as u8, (sum[1] / 4) as u8, (sum[2] / 4) as u8, (sum[3] / 4) as u8])
+    })
+}
+
+// texture and sampler only, for the materials without normal map
+pub fn create_diffuse_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::Sampler {
+                    comparison: false,
+                    filtering: true,
+                },
+                count: None,
+            },
+        ],
+        label: Some("diffuse_bind_group_layout"),
+    })
+}
+
+pub fn create_diffuse_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    texture: &Texture,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&texture.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&texture.sampler),
+            },
+        ],
+        label: Some("diffuse_bind_group"),
+    })
 }
 
 #[allow(dead_code)]
@@ -1,11 +1,30 @@
 use cgmath::*;
 use super::camera;
 
+// the time goes back to 0 after this many seconds to keep its precision, the
+// animations stay seamless as long as their speed is a whole number of radians per second
+pub const TIME_PERIOD: f32 = 200.0 * std::f32::consts::PI;
+
 #[repr(C)]
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Uniforms {
     pub view_position: [f32; 4],
     pub view_proj: [[f32; 4]; 4],
+    // seconds since the start, wrapped to TIME_PERIOD
+    pub time: f32,
+    // 0 when the fog is off, 1 otherwise
+    pub fog: f32,
+    // part of the ambient occlusion of the vertices that darkens them, from 0 to 1
+    pub ao_strength: f32,
+    pub _padding: f32,
+    // sky color, the far terrain fades into it
+    pub ambient_color: [f32; 4],
+}
+
+impl Default for Uniforms {
+    fn default() -> Self {
+        Uniforms::new()
+    }
 }
 
 impl Uniforms {
@@ -13,6 +32,11 @@ impl Uniforms {
         Self {
             view_position: [0.0; 4],
             view_proj: cgmath::Matrix4::identity().into(),
+            time: 0.0,
+            fog: 1.0,
+            ao_strength: 1.0,
+            _padding: 0.0,
+            ambient_color: [0.0, 0.0, 0.0, 1.0],
         }
     }
 
@@ -21,6 +45,23 @@ impl Uniforms {
         self.view_position = camera.position.to_homogeneous().into();
         self.view_proj = (projection.calc_matrix() * camera.calc_matrix()).into()
     }
+
+    pub fn set_ambient_color(&mut self, color: [f32; 3]) {
+        self.ambient_color = [color[0], color[1], color[2], 1.0];
+    }
+
+    pub fn set_fog(&mut self, enabled: bool) {
+        self.fog = if enabled { 1.0 } else { 0.0 };
+    }
+
+    // applied by the shader, the meshes do not need to be rebuilt
+    pub fn set_ao_strength(&mut self, strength: f32) {
+        self.ao_strength = strength.clamp(0.0, 1.0);
+    }
+
+    pub fn advance_time(&mut self, dt: std::time::Duration) {
+        self.time = (self.time + dt.as_secs_f32()) % TIME_PERIOD;
+    }
 }
 
 pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
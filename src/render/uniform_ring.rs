@@ -0,0 +1,66 @@
+use wgpu::util::DeviceExt;
+use super::memory;
+
+// copies of each uniform buffer, the GPU can still read the one of the frame before
+pub const FRAMES_IN_FLIGHT: usize = 3;
+
+// Uniform buffers used one frame after the other, each with its own bind group. The
+// buffer written by a frame has not been bound since FRAMES_IN_FLIGHT - 1 frames, so the
+// write does not wait on the GPU.
+pub struct UniformRing {
+    buffers: Vec<wgpu::Buffer>,
+    bind_groups: Vec<wgpu::BindGroup>,
+    active: usize,
+    memory_bytes: u64,
+}
+
+impl UniformRing {
+    // every copy starts with the contents, create_bind_group is the one of the uniform module
+    pub fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        label: &str,
+        contents: &[u8],
+        create_bind_group: fn(&wgpu::Device, &wgpu::BindGroupLayout, &wgpu::Buffer) -> wgpu::BindGroup,
+    ) -> Self {
+        let buffers: Vec<wgpu::Buffer> = (0..FRAMES_IN_FLIGHT)
+            .map(|_| device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+            }))
+            .collect();
+        let bind_groups = buffers.iter()
+            .map(|buffer| create_bind_group(device, layout, buffer))
+            .collect();
+
+        UniformRing {
+            buffers,
+            bind_groups,
+            active: 0,
+            memory_bytes: memory::init_buffer_size(contents.len()) * FRAMES_IN_FLIGHT as u64,
+        }
+    }
+
+    // once per frame, before the writes of the frame
+    pub fn advance(&mut self) {
+        self.active = next_index(self.active, self.buffers.len());
+    }
+
+    // goes to the active buffer, the other copies keep what their frame was given
+    pub fn write(&self, queue: &wgpu::Queue, data: &[u8]) {
+        queue.write_buffer(&self.buffers[self.active], 0, data);
+    }
+
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_groups[self.active]
+    }
+
+    pub fn memory_bytes(&self) -> u64 {
+        self.memory_bytes
+    }
+}
+
+pub fn next_index(active: usize, len: usize) -> usize {
+    (active + 1) % len
+}
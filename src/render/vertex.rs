@@ -12,6 +12,13 @@ pub trait Vertex {
 pub struct ColorVertex {
     pub position: [f32; 3],
     pub color: [f32; 3],
+    pub normal: [f32; 3],
+    // in the block atlas
+    pub tex_coords: [f32; 2],
+    // brightness left by the ambient occlusion, 1 when nothing is around the vertex
+    pub ao: f32,
+    // index in the materials uniform, see material::BLOCK_MATERIALS
+    pub material_id: u32,
 }
 
 impl Vertex for ColorVertex {
@@ -32,6 +39,30 @@ impl Vertex for ColorVertex {
                     shader_location: 1,
                     format: wgpu::VertexFormat::Float3,
                 },
+                // Normal
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float3,
+                },
+                // Tex_coords
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float2,
+                },
+                // Ao
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 11]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float,
+                },
+                // Material_id
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 12]>() as wgpu::BufferAddress,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Uint,
+                },
             ],
         }
     }
@@ -0,0 +1,113 @@
+use anyhow::*;
+use log::warn;
+use std::str::FromStr;
+use winit::{dpi::{PhysicalPosition, PhysicalSize}, event::*, event_loop::EventLoop, window::{Fullscreen, Window, WindowBuilder}};
+
+pub const DEFAULT_WIDTH: u32 = 800;
+pub const DEFAULT_HEIGHT: u32 = 600;
+
+// How the cursor is held while the camera is controlled
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorGrab {
+    // hidden and kept at the center of the window
+    Lock,
+    // visible but cannot leave the window, friendlier with several monitors
+    Confine,
+}
+
+impl FromStr for CursorGrab {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "lock" => Ok(CursorGrab::Lock),
+            "confine" => Ok(CursorGrab::Confine),
+            _ => bail!("expected lock or confine"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub cursor_grab: CursorGrab,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            fullscreen: false,
+            cursor_grab: CursorGrab::Lock,
+        }
+    }
+}
+
+impl WindowConfig {
+    pub fn build(&self, title: &str, event_loop: &EventLoop<()>) -> Result<Window> {
+        let mut builder = WindowBuilder::new()
+            .with_title(title)
+            .with_inner_size(PhysicalSize::new(self.width, self.height));
+        if self.fullscreen {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+
+        Ok(builder.build(event_loop)?)
+    }
+}
+
+// closing the window and Escape both end the engine, State::on_exit then runs from LoopDestroyed
+pub fn requests_exit(event: &WindowEvent) -> bool {
+    match event {
+        WindowEvent::CloseRequested => true,
+        WindowEvent::KeyboardInput { input, .. } => matches!(input, KeyboardInput {
+            state: ElementState::Pressed,
+            virtual_keycode: Some(VirtualKeyCode::Escape),
+            ..
+        }),
+        _ => false,
+    }
+}
+
+// switches between windowed and borderless fullscreen on the current monitor
+pub fn toggle_fullscreen(window: &Window) {
+    if window.fullscreen().is_some() {
+        window.set_fullscreen(None);
+    } else {
+        window.set_fullscreen(Some(Fullscreen::Borderless(window.current_monitor())));
+    }
+}
+
+// some platforms and compositors refuse the grab, the cursor is then left free
+pub fn grab_cursor(window: &Window, mode: CursorGrab) -> Result<()> {
+    window.set_cursor_grab(true).context("Cannot grab the cursor")?;
+    window.set_cursor_visible(mode == CursorGrab::Confine);
+    Ok(())
+}
+
+// whether the mouse is captured after the grab, a refused grab is logged and the cursor stays free
+pub fn grab_captures(grab: Result<()>) -> bool {
+    if let Err(e) = &grab {
+        warn!("{:?}", e);
+    }
+    grab.is_ok()
+}
+
+pub fn release_cursor(window: &Window) {
+    if let Err(e) = window.set_cursor_grab(false) {
+        warn!("Cannot release the cursor: {}", e);
+    }
+    window.set_cursor_visible(true);
+}
+
+// winit only confines the cursor on most platforms, moving it back emulates the lock
+pub fn recenter_cursor(window: &Window) {
+    let size = window.inner_size();
+    // not every platform can move the cursor, it then only stays confined
+    let _ = window.set_cursor_position(PhysicalPosition::new(size.width / 2, size.height / 2));
+}
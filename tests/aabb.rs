@@ -0,0 +1,56 @@
+use cgmath::*;
+use rover_engine::render::math::Aabb;
+
+fn unit_box(x: f32, y: f32, z: f32) -> Aabb {
+    Aabb::new(Vector3::new(x, y, z), Vector3::new(x + 1.0, y + 1.0, z + 1.0))
+}
+
+#[test]
+fn overlapping_boxes_intersect() {
+    let a = unit_box(0.0, 0.0, 0.0);
+    let b = unit_box(0.5, 0.5, 0.5);
+    assert!(a.intersects(&b) && b.intersects(&a));
+    // one inside the other
+    let inner = Aabb::new(Vector3::new(0.25, 0.25, 0.25), Vector3::new(0.75, 0.75, 0.75));
+    assert!(a.intersects(&inner) && inner.intersects(&a));
+    // touching by a face
+    assert!(a.intersects(&unit_box(1.0, 0.0, 0.0)));
+}
+
+#[test]
+fn disjoint_boxes_do_not_intersect() {
+    let a = unit_box(0.0, 0.0, 0.0);
+    for &(x, y, z) in [(1.5, 0.0, 0.0), (0.0, -1.5, 0.0), (0.0, 0.0, 2.0), (1.5, 1.5, 1.5)].iter() {
+        let b = unit_box(x, y, z);
+        assert!(!a.intersects(&b) && !b.intersects(&a), "{:?}", b);
+    }
+    // apart on a single axis is enough
+    assert!(!a.intersects(&unit_box(0.5, 0.5, 3.0)));
+}
+
+#[test]
+fn points_on_the_faces_are_inside() {
+    let a = unit_box(0.0, 0.0, 0.0);
+    assert!(a.contains_point(Vector3::new(0.5, 0.5, 0.5)));
+    assert!(a.contains_point(Vector3::new(1.0, 0.0, 0.5)));
+    assert!(!a.contains_point(Vector3::new(1.01, 0.5, 0.5)));
+    assert_eq!(a.distance_to(Vector3::new(0.5, 0.5, 0.5)), 0.0);
+    assert!((a.distance_to(Vector3::new(3.0, 0.5, 0.5)) - 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn corners_and_center_of_a_box() {
+    let a = Aabb::new(Vector3::new(-1.0, 0.0, 2.0), Vector3::new(3.0, 4.0, 6.0));
+    assert_eq!(a.center(), Vector3::new(1.0, 2.0, 4.0));
+    let corners = a.corners();
+    assert_eq!((corners[0], corners[6]), (a.min, a.max));
+    for corner in corners.iter() {
+        assert!(a.contains_point(*corner));
+        // every corner is on the min or the max of each axis
+        assert!((0..3).all(|axis| corner[axis] == a.min[axis] || corner[axis] == a.max[axis]));
+    }
+    for (i, a) in corners.iter().enumerate() {
+        assert!(corners[i + 1..].iter().all(|b| a != b), "{:?} is there twice", a);
+    }
+    assert_eq!(a.union(&unit_box(5.0, -1.0, 2.0)), Aabb::new(Vector3::new(-1.0, -1.0, 2.0), Vector3::new(6.0, 4.0, 6.0)));
+}
@@ -0,0 +1,66 @@
+use rover_engine::render::terrain::block::{BlockType, HALF_BLOCK_SIZE};
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours, AO_BRIGHTNESS, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::lighting::ao_shade;
+
+fn empty_chunk() -> Chunk {
+    let mut chunk = Chunk::new();
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, BlockType::AIR);
+            }
+        }
+    }
+    chunk
+}
+
+// ambient occlusion of the vertices on the top face of the block at that height
+fn top_face_ao(chunk: &Chunk, y: usize) -> Vec<f32> {
+    let top = y as f32 * 2.0 * HALF_BLOCK_SIZE + HALF_BLOCK_SIZE;
+    chunk.create_mesh(&VerticalNeighbours::default()).vertices.iter()
+        .filter(|v| v.normal == [0.0, 1.0, 0.0] && (v.position[1] - top).abs() < 1e-6)
+        .map(|v| v.ao)
+        .collect()
+}
+
+#[test]
+fn zero_strength_ignores_the_ao() {
+    for &ao in AO_BRIGHTNESS.iter() {
+        assert_eq!(ao_shade(ao, 0.0), 1.0);
+    }
+}
+
+#[test]
+fn full_strength_applies_all_of_it() {
+    for &ao in AO_BRIGHTNESS.iter() {
+        assert!((ao_shade(ao, 1.0) - ao).abs() < 1e-6);
+    }
+    assert!(ao_shade(AO_BRIGHTNESS[0], 0.5) > AO_BRIGHTNESS[0]);
+}
+
+#[test]
+fn lone_block_is_not_occluded() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    assert!(mesh.vertices.iter().all(|v| v.ao == 1.0));
+}
+
+#[test]
+fn block_along_an_edge_darkens_its_two_corners() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    chunk.set_block(5, 5, 4, BlockType::STONE);
+    let mut ao = top_face_ao(&chunk, 4);
+    ao.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(ao, vec![AO_BRIGHTNESS[2], AO_BRIGHTNESS[2], 1.0, 1.0]);
+}
+
+#[test]
+fn corner_between_two_edges_is_fully_occluded() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    chunk.set_block(5, 5, 4, BlockType::STONE);
+    chunk.set_block(4, 5, 5, BlockType::STONE);
+    assert!(top_face_ao(&chunk, 4).contains(&AO_BRIGHTNESS[0]));
+}
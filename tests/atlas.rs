@@ -0,0 +1,64 @@
+use rover_engine::render::material::{self, Materials};
+use rover_engine::render::terrain::atlas::{self, GRASS_SIDE_TILE, TILE_COUNT, TILE_SIZE};
+use rover_engine::render::terrain::block::{BlockType, Faces, HALF_BLOCK_SIZE};
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::registry::BlockRegistry;
+
+fn flat_chunk() -> Chunk {
+    let mut chunk = Chunk::new();
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, if y == 0 { BlockType::STONE } else { BlockType::AIR });
+            }
+        }
+    }
+    chunk
+}
+
+#[test]
+fn shared_corners_have_the_same_tex_coords() {
+    let mesh = flat_chunk().create_mesh(&VerticalNeighbours::default());
+    let tops: Vec<_> = mesh.vertices.iter().filter(|v| v.normal == [0.0, 1.0, 0.0]).collect();
+    for a in tops.iter() {
+        for b in tops.iter().filter(|b| b.position == a.position) {
+            assert_eq!(a.tex_coords, b.tex_coords);
+        }
+    }
+}
+
+#[test]
+fn tex_coords_count_blocks() {
+    let size = 2.0 * HALF_BLOCK_SIZE;
+    // the corners of the block 3 along x start at 3
+    let corner = [3.0 * size - HALF_BLOCK_SIZE, HALF_BLOCK_SIZE, 5.0 * size - HALF_BLOCK_SIZE];
+    assert_eq!(atlas::face_tex_coords(Faces::TOP, corner), [3.0, 5.0]);
+    // the sides go down the image, the top of the block is at the top of the tile
+    assert_eq!(atlas::face_tex_coords(Faces::FRONT, corner)[1], 0.0);
+}
+
+#[test]
+fn wrapped_tex_coords_stay_in_their_tile() {
+    let inset = 0.5 / TILE_SIZE as f32;
+    for &uv in [[0.0, 0.0], [0.999, 0.999], [7.25, -3.5], [-0.01, 12.0]].iter() {
+        let [u, v] = atlas::tile_tex_coords(2, uv);
+        assert!(u >= (2.0 + inset) / TILE_COUNT as f32 && u <= (3.0 - inset) / TILE_COUNT as f32, "{}", u);
+        assert!((inset..=1.0 - inset).contains(&v), "{}", v);
+    }
+    assert_eq!(atlas::tile_tex_coords(2, [7.25, 0.0]), atlas::tile_tex_coords(2, [0.25, 0.0]));
+}
+
+#[test]
+fn grass_has_its_own_top_dirt_below_and_a_side_tile() {
+    let registry = BlockRegistry::builtin();
+    let grass = registry.get(BlockType::GRASS).textures();
+    assert_eq!(grass.tile(Faces::TOP), BlockType::GRASS.0);
+    assert_eq!(grass.tile(Faces::BOTTOM), BlockType::DIRT.0);
+    for &side in [Faces::FRONT, Faces::BACK, Faces::LEFT, Faces::RIGHT].iter() {
+        assert_eq!(grass.tile(side), GRASS_SIDE_TILE);
+    }
+    // the shader reads the tiles from the material of the block
+    let material = Materials::new().materials[material::material_id(BlockType::GRASS) as usize];
+    assert_eq!(material.tiles[..3], [BlockType::GRASS.0, BlockType::DIRT.0, GRASS_SIDE_TILE]);
+    assert_eq!(registry.get(BlockType::STONE).textures().tile(Faces::FRONT), BlockType::STONE.0);
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+use cgmath::*;
+use rover_engine::render::benchmark::{Benchmark, CameraPath};
+use rover_engine::render::camera::Camera;
+
+fn path() -> CameraPath {
+    CameraPath::new(vec![
+        Point3::new(0.0, 5.0, 0.0),
+        Point3::new(4.0, 6.0, 0.0),
+        Point3::new(4.0, 7.0, 4.0),
+        Point3::new(0.0, 8.0, 4.0),
+    ])
+}
+
+fn close(a: Point3<f32>, b: Point3<f32>) -> bool {
+    (a - b).magnitude() < 1e-5
+}
+
+#[test]
+fn path_starts_and_ends_on_its_end_points() {
+    let path = path();
+    assert!(close(path.evaluate(0.0), Point3::new(0.0, 5.0, 0.0)));
+    assert!(close(path.evaluate(1.0), Point3::new(0.0, 8.0, 4.0)));
+    // out of range times stay on the end points
+    assert!(close(path.evaluate(-1.0), path.evaluate(0.0)));
+    assert!(close(path.evaluate(2.0), path.evaluate(1.0)));
+}
+
+#[test]
+fn path_goes_through_its_control_points() {
+    let path = path();
+    assert!(close(path.evaluate(1.0 / 3.0), Point3::new(4.0, 6.0, 0.0)));
+    assert!(close(path.evaluate(2.0 / 3.0), Point3::new(4.0, 7.0, 4.0)));
+}
+
+#[test]
+fn benchmark_finishes_after_its_duration() {
+    let mut benchmark = Benchmark::new(Duration::from_secs(2));
+    let mut camera = Camera::new((0.0, 0.0, 0.0), Deg(0.0), Deg(0.0));
+    benchmark.update_camera(&mut camera, Duration::from_secs(1));
+    assert!(!benchmark.finished());
+    benchmark.update_camera(&mut camera, Duration::from_secs(1));
+    assert!(benchmark.finished());
+}
@@ -0,0 +1,51 @@
+use cgmath::*;
+use rover_engine::render::terrain::block::HALF_BLOCK_SIZE;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_WIDTH, OUTLINE_VERTICES};
+
+const INFLATION: f32 = 0.01;
+
+fn bounds(chunk: &Chunk, block: [usize; 3]) -> (Vector3<f32>, Vector3<f32>) {
+    let lines = chunk.block_outline(block, INFLATION, [0.0; 3]);
+    assert_eq!(lines.len(), OUTLINE_VERTICES);
+    let points: Vec<Vector3<f32>> = lines.iter().map(|v| Vector3::from(v.position)).collect();
+    let min = points.iter().fold(points[0], |m, p| Vector3::new(m.x.min(p.x), m.y.min(p.y), m.z.min(p.z)));
+    let max = points.iter().fold(points[0], |m, p| Vector3::new(m.x.max(p.x), m.y.max(p.y), m.z.max(p.z)));
+    (min, max)
+}
+
+// center of the block in the world, the blocks are centered on their coordinates
+fn block_center(chunk: &Chunk, [x, y, z]: [usize; 3]) -> Vector3<f32> {
+    chunk.world_offset() + Vector3::new(x as f32, y as f32, z as f32) * 2.0 * HALF_BLOCK_SIZE
+}
+
+fn assert_near(a: Vector3<f32>, b: Vector3<f32>) {
+    assert!((a - b).magnitude() < 1e-5, "{:?} instead of {:?}", a, b);
+}
+
+#[test]
+fn outline_surrounds_the_hit_block() {
+    let chunk = Chunk::new();
+    let block = [3, 7, 12];
+    let (min, max) = bounds(&chunk, block);
+    assert_near((min + max) / 2.0, block_center(&chunk, block));
+    let size = 2.0 * (HALF_BLOCK_SIZE + INFLATION);
+    assert_near(max - min, Vector3::new(size, size, size));
+}
+
+#[test]
+fn outline_follows_the_chunk_in_the_world() {
+    let chunk = Chunk::generate_at(TerrainConfig::default(), (-1, 2));
+    let block = [CHUNK_WIDTH - 1, 0, 0];
+    let (min, max) = bounds(&chunk, block);
+    assert_near((min + max) / 2.0, Vector3::new(-0.5, 0.0, 16.0));
+}
+
+#[test]
+fn every_edge_is_along_an_axis() {
+    let lines = Chunk::new().block_outline([1, 1, 1], INFLATION, [0.0; 3]);
+    for edge in lines.chunks_exact(2) {
+        let delta = Vector3::from(edge[1].position) - Vector3::from(edge[0].position);
+        let moved = (0..3).filter(|&axis| delta[axis].abs() > 1e-6).count();
+        assert_eq!(moved, 1);
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+use rover_engine::render::command;
+use rover_engine::render::material::{self, Materials};
+use rover_engine::render::terrain::block::{BlockType, Faces};
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::lighting::{LightMap, MAX_LIGHT};
+use rover_engine::render::terrain::registry::{BlockMesh, BlockRegistry, MAX_BLOCK_TYPES};
+
+// a white block named marble takes the place of the stone
+const MARBLE: &str = r#"[{ "name": "marble", "id": 1, "color": [0.95, 0.95, 0.95] }]"#;
+// a new block after the glass, lit and seen through
+const CRYSTAL: &str = r#"[{
+    "name": "crystal", "id": 9, "color": [0.6, 0.2, 0.9], "mesh": "glass", "emissive": true,
+    "tiles": { "top": 8, "bottom": 8, "side": 8 }, "specular": 0.8, "shininess": 50.0
+}]"#;
+const CRYSTAL_ID: BlockType = BlockType(9);
+
+fn lone_block(block_type: BlockType, registry: BlockRegistry) -> Chunk {
+    let mut chunk = Chunk::new().with_registry(Arc::new(registry));
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, BlockType::AIR);
+            }
+        }
+    }
+    chunk.set_block(4, 4, 4, block_type);
+    chunk
+}
+
+#[test]
+fn custom_block_gives_its_color_to_the_mesh() {
+    let registry = BlockRegistry::from_json(MARBLE).unwrap();
+    assert_eq!(registry.by_name("marble").unwrap().id, BlockType::STONE.0);
+    assert!(registry.by_name("stone").is_none());
+
+    let mesh = lone_block(BlockType::STONE, registry).create_mesh(&VerticalNeighbours::default());
+    // the top faces are not darkened by the direction of the light
+    let top = mesh.vertices.iter().find(|v| v.normal == [0.0, 1.0, 0.0]).unwrap();
+    let level = top.color[0] / 0.95;
+    for channel in 0..3 {
+        assert!((top.color[channel] - 0.95 * level).abs() < 1e-6);
+    }
+    let builtin = lone_block(BlockType::STONE, BlockRegistry::builtin()).create_mesh(&VerticalNeighbours::default());
+    let builtin_top = builtin.vertices.iter().find(|v| v.normal == [0.0, 1.0, 0.0]).unwrap();
+    assert!((builtin_top.color[0] / 0.5 - level).abs() < 1e-6);
+}
+
+#[test]
+fn blocks_left_out_of_the_file_keep_their_definition() {
+    let registry = BlockRegistry::from_json(MARBLE).unwrap();
+    let builtin = BlockRegistry::builtin();
+    assert_eq!(registry.get(BlockType::GRASS), builtin.get(BlockType::GRASS));
+    assert_eq!(registry.face_color(BlockType::GRASS, Faces::TOP), [0.3, 0.6, 0.2]);
+    assert_eq!(registry.face_color(BlockType::GRASS, Faces::LEFT), registry.face_color(BlockType::DIRT, Faces::LEFT));
+}
+
+#[test]
+fn builtin_blocks_are_indexed_by_their_type() {
+    let registry = BlockRegistry::builtin();
+    let names = ["air", "stone", "dirt", "grass", "water", "coal", "iron", "lamp", "glass"];
+    assert_eq!(registry.iter().count(), names.len());
+    for (id, name) in names.iter().enumerate() {
+        assert_eq!(registry.by_name(name).unwrap().id as usize, id);
+    }
+    assert_eq!(registry.get(BlockType::GLASS).name, "glass");
+}
+
+#[test]
+fn ids_the_chunks_cannot_hold_are_refused() {
+    // one past the last material
+    let basalt = format!(r#"[{{ "name": "basalt", "id": {}, "color": [0.1, 0.1, 0.1] }}]"#, MAX_BLOCK_TYPES);
+    let message = format!("{:#}", BlockRegistry::from_json(&basalt).expect_err("the id was accepted"));
+    assert!(message.contains("basalt has the id 16, the ids go from 0 to 15"), "{}", message);
+    // the new ids follow the last one
+    let gap = r#"[{ "name": "basalt", "id": 10, "color": [0.1, 0.1, 0.1] }]"#;
+    let message = format!("{:#}", BlockRegistry::from_json(gap).expect_err("the gap was accepted"));
+    assert!(message.contains("basalt has the id 10, the next new id is 9"), "{}", message);
+    let tile = r#"[{ "name": "basalt", "id": 9, "color": [0.1, 0.1, 0.1], "tiles": { "top": 1, "bottom": 1, "side": 42 } }]"#;
+    assert!(BlockRegistry::from_json(tile).is_err());
+    assert!(BlockRegistry::from_json("not json").is_err());
+}
+
+#[test]
+fn new_blocks_follow_the_builtin_ones() {
+    let both = r#"[
+        { "name": "slate", "id": 10, "color": [0.3, 0.3, 0.35] },
+        { "name": "basalt", "id": 9, "color": [0.1, 0.1, 0.1] }
+    ]"#;
+    let registry = BlockRegistry::from_json(both).unwrap();
+    assert_eq!(registry.get(BlockType(9)).name, "basalt");
+    assert_eq!(registry.get(BlockType(10)).name, "slate");
+    // a cube with the plain tile and no highlight when left out
+    assert_eq!(registry.get(BlockType(10)).mesh, BlockMesh::Cube);
+    assert!(!registry.contains(BlockType(11)));
+    assert!(!BlockRegistry::builtin().contains(BlockType(9)));
+}
+
+#[test]
+fn custom_block_is_meshed_lit_and_shaded_from_its_definition() {
+    let registry = BlockRegistry::from_json(CRYSTAL).unwrap();
+    let chunk = lone_block(CRYSTAL_ID, registry.clone());
+
+    // drawn with the glass, not in the opaque mesh
+    assert!(chunk.create_mesh(&VerticalNeighbours::default()).vertices.is_empty());
+    let glass = chunk.create_glass_mesh();
+    assert_eq!(glass.vertices.len(), 6 * 4);
+    assert!(glass.vertices.iter().all(|v| v.material_id == material::material_id(CRYSTAL_ID)));
+    // emissive, its faces are not darkened by their direction
+    let side = glass.vertices.iter().find(|v| v.normal == [-1.0, 0.0, 0.0]).unwrap();
+    assert_eq!(side.color, [0.6, 0.2, 0.9]);
+    // and it lights the air around it
+    let light = LightMap::compute(&chunk);
+    assert_eq!(light.level(4, 4, 4), MAX_LIGHT);
+    assert_eq!(light.level(5, 4, 4), MAX_LIGHT - 1);
+
+    let crystal = Materials::from_registry(&registry).materials[CRYSTAL_ID.0 as usize];
+    assert_eq!((crystal.specular, crystal.shininess, crystal.emissive), (0.8, 50.0, 1.0));
+    assert_eq!(crystal.tiles[..3], [8, 8, 8]);
+}
+
+#[test]
+fn blocks_out_of_the_registry_cannot_be_placed() {
+    let mut chunk = lone_block(BlockType::STONE, BlockRegistry::builtin());
+    assert!(!command::set_block(&mut chunk, [0, 0, 0], CRYSTAL_ID));
+    assert_eq!(chunk.block_at(0, 0, 0), Some(BlockType::AIR));
+
+    let mut chunk = lone_block(BlockType::STONE, BlockRegistry::from_json(CRYSTAL).unwrap());
+    assert!(command::set_block(&mut chunk, [0, 0, 0], CRYSTAL_ID));
+    assert_eq!(chunk.block_at(0, 0, 0), Some(CRYSTAL_ID));
+}
+
+#[test]
+fn blocks_can_be_given_next_to_a_comment() {
+    let commented = format!(r#"{{ "comment": "the stone is white", "blocks": {} }}"#, MARBLE);
+    assert_eq!(BlockRegistry::from_json(&commented).unwrap(), BlockRegistry::from_json(MARBLE).unwrap());
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use cgmath::*;
+use rover_engine::render::block_tint::{self, BlockTints, DEFAULT_TINT};
+use rover_engine::render::terrain::block::{BlockType, HALF_BLOCK_SIZE};
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::mesh::{self, Mesh};
+
+const RED: [f32; 3] = [1.0, 0.2, 0.2];
+
+fn chunk_with(blocks: &[[usize; 3]]) -> Chunk {
+    let mut chunk = Chunk::new();
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, BlockType::AIR);
+            }
+        }
+    }
+    for &[x, y, z] in blocks {
+        chunk.set_block(x, y, z, BlockType::STONE);
+    }
+    chunk
+}
+
+// whether the vertex is a corner of the block, in the local space of the chunk
+fn touches(mesh: &Mesh, vertex: usize, [x, y, z]: [usize; 3]) -> bool {
+    let center = Vector3::new(x as f32, y as f32, z as f32) * 2.0 * HALF_BLOCK_SIZE;
+    let offset = Vector3::from(mesh.vertices[vertex].position) - center;
+    offset.x.abs().max(offset.y.abs()).max(offset.z.abs()) <= HALF_BLOCK_SIZE + 1e-5
+}
+
+#[test]
+fn tinting_a_block_only_recolors_that_block() {
+    let (tinted, other) = ([4, 4, 4], [8, 4, 4]);
+    let mesh = chunk_with(&[tinted, other]).create_mesh(&VerticalNeighbours::default());
+    let mut tints = BlockTints::new(&mesh, &HashMap::new());
+    let before: Vec<[f32; 3]> = (0..mesh.vertex_count()).map(|v| tints.tinted_color(&mesh, v)).collect();
+
+    let range = tints.set(block_tint::block_index(tinted), RED).unwrap();
+    assert_eq!(range.len(), 24);
+
+    for (vertex, color) in before.iter().enumerate() {
+        let after = tints.tinted_color(&mesh, vertex);
+        if touches(&mesh, vertex, tinted) {
+            assert!(range.contains(&vertex));
+            assert_eq!(after, [color[0] * RED[0], color[1] * RED[1], color[2] * RED[2]]);
+        } else {
+            assert!(!range.contains(&vertex));
+            assert_eq!(after, *color, "vertex {} of the other block changed", vertex);
+        }
+    }
+}
+
+#[test]
+fn vertices_belong_to_their_block() {
+    let blocks = [[0, 0, 0], [1, 0, 0], [CHUNK_WIDTH - 1, CHUNK_HEIGHT - 1, CHUNK_WIDTH - 1]];
+    let mesh = chunk_with(&blocks).create_mesh(&VerticalNeighbours::default());
+    let vertex_blocks = block_tint::vertex_blocks(&mesh);
+    for (vertex, block) in vertex_blocks.iter().enumerate() {
+        let owner = blocks.iter().find(|&&b| block_tint::block_index(b) == *block).unwrap();
+        assert!(touches(&mesh, vertex, *owner));
+    }
+}
+
+#[test]
+fn deduplicated_vertices_still_have_a_block() {
+    let blocks = [[4, 4, 4], [5, 4, 4]];
+    let indexes: Vec<u32> = blocks.iter().map(|&b| block_tint::block_index(b)).collect();
+    let mesh = mesh::deduplicate_vertices(&chunk_with(&blocks).create_mesh(&VerticalNeighbours::default()));
+    assert!(block_tint::vertex_blocks(&mesh).iter().all(|block| indexes.contains(block)));
+}
+
+#[test]
+fn tints_are_given_back_to_the_next_mesh() {
+    let block = [4, 4, 4];
+    let mesh = chunk_with(&[block, [8, 4, 4]]).create_mesh(&VerticalNeighbours::default());
+    let mut block_tints = HashMap::new();
+    block_tints.insert(block_tint::block_index(block), RED);
+    let tints = BlockTints::new(&mesh, &block_tints);
+    for (vertex, tint) in tints.tints().iter().enumerate() {
+        let expected = if touches(&mesh, vertex, block) { RED } else { DEFAULT_TINT };
+        assert_eq!(tint.tint, expected);
+    }
+}
+
+#[test]
+fn blocks_without_faces_are_not_uploaded() {
+    let mesh = chunk_with(&[[4, 4, 4]]).create_mesh(&VerticalNeighbours::default());
+    let mut tints = BlockTints::new(&mesh, &HashMap::new());
+    assert_eq!(tints.set(block_tint::block_index([9, 9, 9]), RED), None);
+}
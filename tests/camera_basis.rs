@@ -0,0 +1,29 @@
+use cgmath::*;
+use rover_engine::render::camera::Camera;
+
+fn assert_near(a: Vector3<f32>, b: Vector3<f32>) {
+    assert!((a - b).magnitude() < 1e-5, "{:?} instead of {:?}", a, b);
+}
+
+#[test]
+fn default_look_is_along_minus_z() {
+    let camera = Camera::new((0.0, 0.0, 0.0), Deg(-90.0), Deg(0.0));
+    assert_near(camera.forward(), -Vector3::unit_z());
+    assert_near(camera.right(), Vector3::unit_x());
+    assert_near(camera.up(), Vector3::unit_y());
+}
+
+#[test]
+fn basis_stays_orthonormal() {
+    for &(yaw, pitch) in [(-90.0, 0.0), (0.0, 30.0), (135.0, -60.0), (-20.0, 85.0)].iter() {
+        let camera = Camera::new((1.0, 2.0, 3.0), Deg(yaw), Deg(pitch));
+        let (forward, right, up) = (camera.forward(), camera.right(), camera.up());
+        for v in [forward, right, up].iter() {
+            assert!((v.magnitude() - 1.0).abs() < 1e-5, "{:?} at yaw {} pitch {}", v, yaw, pitch);
+        }
+        assert!(forward.dot(right).abs() < 1e-5 && forward.dot(up).abs() < 1e-5 && right.dot(up).abs() < 1e-5);
+        // right stays level, up leans back when looking down
+        assert!(right.y.abs() < 1e-5 && up.y > 0.0);
+        assert_near(right.cross(up), -forward);
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+use cgmath::*;
+use rover_engine::render::camera::{Camera, CameraShake};
+
+#[test]
+fn amplitude_fades_out_linearly() {
+    let mut shake = CameraShake::new(2.0, Duration::from_secs(1));
+    assert_eq!(shake.amplitude(), 2.0);
+    shake.update(Duration::from_millis(250));
+    assert!((shake.amplitude() - 1.5).abs() < 1e-5);
+    shake.update(Duration::from_millis(500));
+    assert!((shake.amplitude() - 0.5).abs() < 1e-5);
+    assert!(!shake.is_finished());
+    // the last step goes past the end, the amplitude does not go below zero
+    shake.update(Duration::from_millis(400));
+    assert!(shake.is_finished());
+    assert_eq!(shake.amplitude(), 0.0);
+}
+
+#[test]
+fn offset_stays_in_the_amplitude_along_the_screen_axes() {
+    let camera = Camera::new((0.0, 0.0, 0.0), Deg(30.0), Deg(-10.0));
+    let (right, up) = (camera.right(), camera.up());
+    let mut shake = CameraShake::new(0.5, Duration::from_secs(2));
+    for _ in 0..20 {
+        let offset = shake.offset(right, up);
+        assert!(offset.dot(camera.forward()).abs() < 1e-5, "{:?}", offset);
+        assert!(offset.dot(right).abs() <= shake.amplitude() + 1e-5 && offset.dot(up).abs() <= shake.amplitude() + 1e-5);
+        shake.update(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn camera_is_back_in_place_once_the_shake_is_over() {
+    let mut camera = Camera::new((1.0, 2.0, 3.0), Deg(0.0), Deg(0.0));
+    camera.add_shake(1.0, Duration::from_millis(100));
+    camera.update(Duration::from_millis(60));
+    assert!(camera.shake.is_some());
+    // the shake only moves the view
+    assert_eq!(camera.position, Point3::new(1.0, 2.0, 3.0));
+    camera.update(Duration::from_millis(60));
+    assert!(camera.shake.is_none());
+    assert_eq!(camera.view_position(), camera.position);
+}
@@ -0,0 +1,89 @@
+use rover_engine::render::terrain::block::{BlockType, HALF_BLOCK_SIZE};
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::noise::NoiseGenerator;
+
+const LAST: usize = CHUNK_WIDTH - 1;
+
+// highest block that is not air in a column of the chunk
+fn ground_height(chunk: &Chunk, x: usize, z: usize) -> Option<usize> {
+    (0..CHUNK_HEIGHT).rev().find(|&y| chunk.blocks[x][y][z].block_type != BlockType::AIR)
+}
+
+// the same height, straight from the noise at the world coordinates of the column
+fn noise_height(terrain: &TerrainConfig, world_x: i32, world_z: i32) -> Option<usize> {
+    let noise = NoiseGenerator::from_seed(terrain.seed, terrain.noise);
+    let height = noise.get(world_x as f64 * terrain.frequency, world_z as f64 * terrain.frequency) * terrain.amplitude;
+    if height < 0.0 {
+        None
+    } else {
+        Some((height.floor() as usize).min(CHUNK_HEIGHT - 1))
+    }
+}
+
+#[test]
+fn negative_chunk_meets_the_main_one_along_x() {
+    let terrain = TerrainConfig::default();
+    let left = Chunk::generate_at(terrain, (-1, 0));
+    let main = Chunk::generate_at(terrain, (0, 0));
+    for z in 0..CHUNK_WIDTH {
+        // the right edge of (-1, 0) is at x = -1, right before the left edge of (0, 0)
+        assert_eq!(ground_height(&left, LAST, z), noise_height(&terrain, -1, z as i32));
+        assert_eq!(ground_height(&main, 0, z), noise_height(&terrain, 0, z as i32));
+    }
+}
+
+#[test]
+fn negative_chunk_meets_the_main_one_along_z() {
+    let terrain = TerrainConfig::default();
+    let front = Chunk::generate_at(terrain, (0, -1));
+    for x in 0..CHUNK_WIDTH {
+        assert_eq!(ground_height(&front, x, LAST), noise_height(&terrain, x as i32, -1));
+    }
+}
+
+#[test]
+fn negative_chunk_ends_one_block_before_the_main_one() {
+    let left = Chunk::generate_at(TerrainConfig::default(), (-1, -1));
+    let main = Chunk::generate_at(TerrainConfig::default(), (0, 0));
+    let block_size = 2.0 * HALF_BLOCK_SIZE;
+    let last = left.world_offset().x + LAST as f32 * block_size;
+    assert!((main.world_offset().x - last - block_size).abs() < 1e-6);
+    assert!((main.world_offset().z - (left.world_offset().z + LAST as f32 * block_size) - block_size).abs() < 1e-6);
+}
+
+#[test]
+fn world_coordinates_of_negative_chunks_are_contiguous() {
+    let chunk = Chunk::generate_at(TerrainConfig::default(), (-2, -1));
+    let (mut min_x, mut max_x, mut min_z, mut max_z) = (i32::MAX, i32::MIN, i32::MAX, i32::MIN);
+    for (x, _, z, _) in chunk.iter_blocks() {
+        min_x = min_x.min(x);
+        max_x = max_x.max(x);
+        min_z = min_z.min(z);
+        max_z = max_z.max(z);
+    }
+    assert_eq!((min_x, max_x), (-32, -17));
+    assert_eq!((min_z, max_z), (-16, -1));
+}
+
+#[test]
+fn right_edge_meets_the_left_edge_of_the_next_chunk() {
+    let terrain = TerrainConfig::default();
+    let main = Chunk::generate_at(terrain, (0, 0));
+    let right = Chunk::generate_at(terrain, (1, 0));
+    let width = CHUNK_WIDTH as i32;
+    for z in 0..CHUNK_WIDTH {
+        assert_eq!(ground_height(&main, LAST, z), noise_height(&terrain, width - 1, z as i32));
+        assert_eq!(ground_height(&right, 0, z), noise_height(&terrain, width, z as i32));
+    }
+}
+
+#[test]
+fn next_chunk_does_not_repeat_the_main_one() {
+    let terrain = TerrainConfig::default();
+    let main = Chunk::generate_at(terrain, (0, 0));
+    let right = Chunk::generate_at(terrain, (1, 0));
+    let heights = |chunk: &Chunk| -> Vec<Option<usize>> {
+        (0..CHUNK_WIDTH).flat_map(|x| (0..CHUNK_WIDTH).map(move |z| (x, z))).map(|(x, z)| ground_height(chunk, x, z)).collect()
+    };
+    assert_ne!(heights(&main), heights(&right));
+}
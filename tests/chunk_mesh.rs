@@ -0,0 +1,186 @@
+use rover_engine::render::material;
+use rover_engine::render::terrain::block::{BlockType, Faces, HALF_BLOCK_SIZE};
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, VerticalNeighbours, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::mesh::Mesh;
+
+// a chunk of air, the tests then place the blocks they need
+fn empty_chunk() -> Chunk {
+    let mut chunk = Chunk::new();
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, BlockType::AIR);
+            }
+        }
+    }
+    chunk
+}
+
+#[test]
+fn empty_chunk_has_no_mesh() {
+    let mesh = empty_chunk().create_mesh(&VerticalNeighbours::default());
+    assert_eq!(mesh.vertex_count(), 0);
+    assert!(mesh.indices.is_empty());
+}
+
+#[test]
+fn lone_block_has_six_faces() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    assert_eq!(mesh.triangle_count(), 6 * 2);
+}
+
+// With FrontFace::Cw a triangle seen from outside goes clockwise, (c - a) x (b - a) then
+// points out of the block like the normal of its face
+#[test]
+fn faces_wind_clockwise_seen_from_outside() {
+    use cgmath::*;
+    let block = [4, 4, 4];
+    let mut chunk = empty_chunk();
+    chunk.set_block(block[0], block[1], block[2], BlockType::STONE);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    let center = Vector3::new(block[0] as f32, block[1] as f32, block[2] as f32) * 2.0 * HALF_BLOCK_SIZE;
+
+    let mut normals = vec![];
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vector3::from(mesh.vertices[triangle[i] as usize].position));
+        let normal = Vector3::from(mesh.vertices[triangle[0] as usize].normal);
+        assert!((c - a).cross(b - a).dot(normal) > 0.0, "triangle {:?} winds against {:?}", triangle, normal);
+        assert!(((a + b + c) / 3.0 - center).dot(normal) > 0.0, "{:?} looks into the block", normal);
+        normals.push(mesh.vertices[triangle[0] as usize].normal);
+    }
+    for face in [Faces::FRONT, Faces::BACK, Faces::TOP, Faces::BOTTOM, Faces::LEFT, Faces::RIGHT].iter() {
+        assert_eq!(normals.iter().filter(|&&n| n == face.normal()).count(), 2, "{:?}", face);
+    }
+}
+
+#[test]
+fn face_between_two_blocks_is_culled() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    chunk.set_block(5, 4, 4, BlockType::STONE);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    assert_eq!(mesh.triangle_count(), 10 * 2);
+}
+
+#[test]
+fn face_next_to_glass_is_kept() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    chunk.set_block(5, 4, 4, BlockType::GLASS);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    assert_eq!(mesh.triangle_count(), 6 * 2);
+    // the glass is left to its own mesh, without the face hidden by the stone
+    assert_eq!(chunk.create_glass_mesh().triangle_count(), 5 * 2);
+    // the stone face behind the glass is the one looking at it, at the border of the two blocks
+    let border = 4.0 * 2.0 * HALF_BLOCK_SIZE + HALF_BLOCK_SIZE;
+    let behind_glass: Vec<_> = mesh.vertices.iter().filter(|v| v.normal == Faces::RIGHT.normal()).collect();
+    assert_eq!(behind_glass.len(), 4);
+    assert!(behind_glass.iter().all(|v| (v.position[0] - border).abs() < 1e-6));
+}
+
+#[test]
+fn face_between_two_glass_blocks_is_culled() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::GLASS);
+    chunk.set_block(5, 4, 4, BlockType::GLASS);
+    assert_eq!(chunk.create_glass_mesh().triangle_count(), 10 * 2);
+    // none of the glass goes to the opaque mesh
+    assert_eq!(chunk.create_mesh(&VerticalNeighbours::default()).triangle_count(), 0);
+}
+
+#[test]
+fn bottom_faces_on_the_world_floor_are_culled() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 0, 4, BlockType::STONE);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    assert_eq!(mesh.triangle_count(), 5 * 2);
+}
+
+#[test]
+fn bottom_faces_over_a_chunk_below_are_culled() {
+    let mut below = empty_chunk();
+    below.set_block(4, CHUNK_HEIGHT - 1, 4, BlockType::STONE);
+    let mut chunk = empty_chunk();
+    chunk.world_floor = false;
+    chunk.set_block(4, 0, 4, BlockType::STONE);
+    chunk.set_block(6, 0, 4, BlockType::STONE);
+    let neighbours = VerticalNeighbours { above: None, below: Some(&below) };
+    let bottoms = |mesh: &Mesh| mesh.vertices.iter().filter(|v| v.normal == [0.0, -1.0, 0.0]).count() / 4;
+    // only the block without a solid block under it keeps its bottom face
+    assert_eq!(bottoms(&chunk.create_mesh(&neighbours)), 1);
+    // without the world floor and the chunk below both are open
+    assert_eq!(bottoms(&chunk.create_mesh(&VerticalNeighbours::default())), 2);
+}
+
+#[test]
+fn top_faces_under_a_chunk_above_are_culled() {
+    let mut above = empty_chunk();
+    above.set_block(4, 0, 4, BlockType::STONE);
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, CHUNK_HEIGHT - 1, 4, BlockType::STONE);
+    let neighbours = VerticalNeighbours { above: Some(&above), below: None };
+    assert_eq!(chunk.create_mesh(&neighbours).triangle_count(), 5 * 2);
+    assert_eq!(chunk.create_mesh(&VerticalNeighbours::default()).triangle_count(), 6 * 2);
+}
+
+#[test]
+fn indices_stay_in_the_vertices() {
+    let mesh = Chunk::new().create_mesh(&VerticalNeighbours::default());
+    assert!(!mesh.indices.is_empty());
+    assert_eq!(mesh.indices.len() % 3, 0);
+    assert!(mesh.indices.iter().all(|&i| (i as usize) < mesh.vertex_count()));
+}
+
+#[test]
+fn parallel_mesh_is_the_serial_one() {
+    for &seed in [1337, 7, 42].iter() {
+        let chunk = Chunk::generate(TerrainConfig { seed, ..TerrainConfig::default() });
+        let parallel = chunk.create_mesh(&VerticalNeighbours::default());
+        let serial = chunk.create_mesh_single_threaded(&VerticalNeighbours::default());
+        let bytes = |mesh: &Mesh| (bytemuck::cast_slice::<_, u8>(&mesh.vertices).to_vec(), bytemuck::cast_slice::<_, u8>(&mesh.indices).to_vec());
+        assert_eq!(bytes(&parallel), bytes(&serial), "seed {}", seed);
+    }
+}
+
+#[test]
+fn faces_carry_the_material_of_their_block() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::IRON);
+    chunk.set_block(8, 4, 4, BlockType::COAL);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    let iron = mesh.vertices.iter().filter(|v| v.material_id == material::material_id(BlockType::IRON)).count();
+    let coal = mesh.vertices.iter().filter(|v| v.material_id == material::material_id(BlockType::COAL)).count();
+    assert_eq!(iron, 6 * 4);
+    assert_eq!(coal, 6 * 4);
+}
+
+// colors of the vertices of a lone block facing that way
+fn face_colors(block_type: BlockType, normal: [f32; 3]) -> Vec<[f32; 3]> {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, block_type);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    mesh.vertices.iter().filter(|v| v.normal == normal).map(|v| v.color).collect()
+}
+
+#[test]
+fn grass_top_is_colored_apart_from_its_sides() {
+    let top = face_colors(BlockType::GRASS, [0.0, 1.0, 0.0]);
+    let left = face_colors(BlockType::GRASS, [-1.0, 0.0, 0.0]);
+    assert_eq!((top.len(), left.len()), (4, 4));
+    // green over dirt, not only a different brightness
+    let hue = |[r, g, b]: [f32; 3]| [r / g, b / g];
+    assert_ne!(hue(top[0]), hue(left[0]));
+}
+
+#[test]
+fn top_faces_are_brighter_than_bottom_faces() {
+    let luminance = |[r, g, b]: [f32; 3]| r + g + b;
+    let top = face_colors(BlockType::STONE, [0.0, 1.0, 0.0]);
+    let bottom = face_colors(BlockType::STONE, [0.0, -1.0, 0.0]);
+    let side = face_colors(BlockType::STONE, [0.0, 0.0, -1.0]);
+    assert_eq!((top.len(), bottom.len(), side.len()), (4, 4, 4));
+    assert!(luminance(top[0]) > luminance(side[0]));
+    assert!(luminance(side[0]) > luminance(bottom[0]));
+}
@@ -0,0 +1,48 @@
+use cgmath::*;
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::chunk_manager::{ChunkManager, SeedMode};
+
+// heights of the columns along the z = 0 edge of a chunk
+fn front_edge(chunk: &Chunk) -> Vec<Option<usize>> {
+    (0..CHUNK_WIDTH)
+        .map(|x| (0..CHUNK_HEIGHT).rev().find(|&y| chunk.blocks[x][y][0].block_type != BlockType::AIR))
+        .collect()
+}
+
+fn generated_manager(seed_mode: SeedMode) -> ChunkManager {
+    let mut manager = ChunkManager::new(TerrainConfig::default(), 1, seed_mode);
+    manager.generation_budget = usize::MAX;
+    manager.generate_pending(Point3::new(0.0, 0.0, 0.0), |_| true);
+    manager
+}
+
+// The edge of the chunk at (1, 0) going on into (1, 1): the terrain of (1, 0) generated
+// at the position of its neighbour, then compared with the neighbour the manager made
+fn seam_matches(manager: &ChunkManager) -> bool {
+    let terrain = manager.chunks[&(1, 0)].terrain;
+    let carried_on = Chunk::generate_at(terrain, (1, 1));
+    front_edge(&carried_on) == front_edge(&manager.chunks[&(1, 1)])
+}
+
+#[test]
+fn shared_seed_carries_the_terrain_over_the_seam() {
+    let manager = generated_manager(SeedMode::Shared);
+    assert!(seam_matches(&manager));
+    assert_eq!(manager.chunks[&(1, 0)].terrain.seed, manager.chunks[&(1, 1)].terrain.seed);
+}
+
+#[test]
+fn chunk_seeds_break_the_seam() {
+    let manager = generated_manager(SeedMode::PerChunk);
+    assert!(!seam_matches(&manager));
+    assert_ne!(manager.chunks[&(1, 0)].terrain.seed, manager.chunks[&(1, 1)].terrain.seed);
+}
+
+#[test]
+fn chunk_seeds_do_not_change_between_runs() {
+    let (a, b) = (generated_manager(SeedMode::PerChunk), generated_manager(SeedMode::PerChunk));
+    for (position, chunk) in a.chunks.iter() {
+        assert_eq!(chunk.terrain.seed, b.chunks[position].terrain.seed);
+    }
+}
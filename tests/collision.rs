@@ -0,0 +1,74 @@
+use std::time::Duration;
+use cgmath::*;
+use rover_engine::render::camera::collision::{sweep, Walker};
+use rover_engine::render::math::Aabb;
+use rover_engine::render::terrain::block::{BlockType, HALF_BLOCK_SIZE};
+use rover_engine::render::terrain::chunk::{Chunk, CHUNK_WIDTH};
+
+const BLOCK_SIZE: f32 = 2.0 * HALF_BLOCK_SIZE;
+
+// a floor of stone with a wall one block thick at x = 8, the rest is air
+fn walled_floor() -> Chunk {
+    let mut chunk = Chunk::from_voxel_str(".").unwrap();
+    for x in 0..CHUNK_WIDTH {
+        for z in 0..CHUNK_WIDTH {
+            chunk.set_block(x, 0, z, BlockType::STONE);
+        }
+    }
+    for y in 1..6 {
+        for z in 0..CHUNK_WIDTH {
+            chunk.set_block(8, y, z, BlockType::STONE);
+        }
+    }
+    chunk
+}
+
+fn small_box(center: Vector3<f32>) -> Aabb {
+    Aabb::new(center - Vector3::new(0.1, 0.1, 0.1), center + Vector3::new(0.1, 0.1, 0.1))
+}
+
+#[test]
+fn fast_box_stops_at_the_wall() {
+    let chunk = walled_floor();
+    let start = small_box(Vector3::new(2.0, 1.0, 2.0));
+    // far past the wall in a single step
+    let (done, blocked) = sweep(&chunk, start, Vector3::new(20.0, 0.0, 0.0));
+    let wall = 8.0 * BLOCK_SIZE - HALF_BLOCK_SIZE;
+    assert!(blocked[0] && !blocked[1] && !blocked[2]);
+    assert!(start.max.x + done.x <= wall);
+    assert!(wall - (start.max.x + done.x) < 0.01, "stopped {} before the wall", wall - (start.max.x + done.x));
+}
+
+#[test]
+fn box_slides_along_the_wall() {
+    let chunk = walled_floor();
+    let start = small_box(Vector3::new(3.5, 1.0, 2.0));
+    let (done, blocked) = sweep(&chunk, start, Vector3::new(2.0, 0.0, 1.5));
+    assert!(blocked[0] && !blocked[2]);
+    assert!((done.z - 1.5).abs() < 1e-6);
+}
+
+#[test]
+fn fast_fall_lands_on_the_floor() {
+    let chunk = walled_floor();
+    let start = small_box(Vector3::new(2.0, 2.0, 2.0));
+    let (done, blocked) = sweep(&chunk, start, Vector3::new(0.0, -100.0, 0.0));
+    assert!(blocked[1]);
+    let floor = HALF_BLOCK_SIZE;
+    assert!(start.min.y + done.y >= floor);
+    assert!(start.min.y + done.y - floor < 0.01);
+}
+
+#[test]
+fn walker_falling_from_high_up_stands_on_the_floor() {
+    let chunk = walled_floor();
+    let mut walker = Walker::new();
+    let mut position = Point3::new(2.0, 6.0, 2.0);
+    // long frames, the fall goes at its top speed
+    for _ in 0..50 {
+        let previous = position;
+        walker.update(&chunk, previous, &mut position, false, Duration::from_millis(250));
+    }
+    let feet = Walker::aabb(position).min.y;
+    assert!(feet >= HALF_BLOCK_SIZE && feet - HALF_BLOCK_SIZE < 0.01, "feet at {}", feet);
+}
@@ -0,0 +1,77 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, MeshMode, TerrainConfig, VerticalNeighbours, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::mesh_cache::mesh_key;
+
+const BLOCKS: [[usize; 3]; 4] = [[2, 4, 2], [9, 4, 11], [14, 4, 5], [5, 4, 13]];
+
+// lone stone blocks in the air, so only the jitter can change the color of their tops
+fn stone_blocks(color_jitter: f32) -> Chunk {
+    stone_blocks_at(TerrainConfig { color_jitter, ..TerrainConfig::default() }, (0, 0))
+}
+
+fn stone_blocks_at(terrain: TerrainConfig, position: (i32, i32)) -> Chunk {
+    let mut chunk = Chunk::generate_at(terrain, position);
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, BlockType::AIR);
+            }
+        }
+    }
+    for &[x, y, z] in BLOCKS.iter() {
+        chunk.set_block(x, y, z, BlockType::STONE);
+    }
+    chunk
+}
+
+fn top_colors(chunk: &Chunk) -> Vec<[f32; 3]> {
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    mesh.vertices.iter().filter(|v| v.normal == [0.0, 1.0, 0.0]).map(|v| v.color).collect()
+}
+
+#[test]
+fn no_jitter_keeps_the_colors_flat() {
+    let colors = top_colors(&stone_blocks(0.0));
+    assert_eq!(colors.len(), BLOCKS.len() * 4);
+    assert!(colors.iter().all(|&color| color == colors[0]));
+}
+
+#[test]
+fn jitter_changes_the_color_of_each_block() {
+    let flat = top_colors(&stone_blocks(0.0))[0];
+    let colors = top_colors(&stone_blocks(0.2));
+    assert!(colors.iter().any(|&color| color != colors[0]));
+    for color in colors {
+        let factor = color[0] / flat[0];
+        assert!((0.8..=1.2).contains(&factor), "{}", factor);
+        // the channels are scaled together, the hue stays the same
+        assert!((color[1] / flat[1] - factor).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn jitter_is_the_same_for_the_same_coordinates() {
+    assert_eq!(top_colors(&stone_blocks(0.2)), top_colors(&stone_blocks(0.2)));
+    let chunk = stone_blocks(0.2);
+    assert_eq!(chunk.color_jitter(3, 7, 1), stone_blocks(0.2).color_jitter(3, 7, 1));
+    // the chunks next to each other do not repeat the same pattern
+    let neighbour = Chunk::generate_at(chunk.terrain, (1, 0));
+    let repeats = (0..CHUNK_WIDTH).all(|x| chunk.color_jitter(x, 4, 0) == neighbour.color_jitter(x, 4, 0));
+    assert!(!repeats);
+}
+
+#[test]
+fn jittered_chunks_do_not_share_their_mesh() {
+    let terrain = TerrainConfig { color_jitter: 0.2, ..TerrainConfig::default() };
+    let key = |chunk: &Chunk| mesh_key(chunk, MeshMode::Blocky, false);
+    let chunk = stone_blocks_at(terrain, (0, 0));
+    let neighbour = stone_blocks_at(terrain, (1, 0));
+    assert_ne!(top_colors(&chunk), top_colors(&neighbour));
+    assert_ne!(key(&chunk), key(&neighbour));
+    let reseeded = stone_blocks_at(TerrainConfig { seed: terrain.seed + 1, ..terrain }, (0, 0));
+    assert_ne!(key(&chunk), key(&reseeded));
+
+    // without jitter the same blocks still share their mesh wherever they are
+    let flat = TerrainConfig::default();
+    assert_eq!(key(&stone_blocks_at(flat, (0, 0))), key(&stone_blocks_at(flat, (1, 0))));
+}
@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use cgmath::*;
+use rover_engine::render::camera::{CameraController, Projection};
+use rover_engine::render::config::{Config, ViewConfig};
+use rover_engine::render::terrain::chunk::TerrainConfig;
+use rover_engine::render::window::WindowConfig;
+
+// a file of its own for each test, they run at the same time
+fn config_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rover-engine-{}-{}.json", name, std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn running_config(path: &Path) -> Config {
+    Config { path: Some(path.to_string_lossy().into_owned()), ..Config::default() }
+}
+
+fn fov(projection: &Projection) -> f32 {
+    Deg::from(projection.fov_y()).0
+}
+
+#[test]
+fn reloading_a_new_fov_updates_the_projection() {
+    let path = config_file("fov", r#"{ "view": { "fov": 70.0 } }"#);
+    let config = running_config(&path);
+    let mut projection = Projection::new(800, 600, Deg(config.view.fov), 0.1, 100.0);
+    let mut controller = CameraController::new(5.0, config.view.sensitivity);
+
+    let reloaded = config.reload().unwrap();
+    reloaded.view.apply(&mut projection, &mut controller);
+    fs::remove_file(&path).unwrap();
+
+    assert!((fov(&projection) - 70.0).abs() < 1e-3);
+    assert_eq!(reloaded.path, config.path);
+}
+
+#[test]
+fn invalid_config_is_not_reloaded() {
+    let path = config_file("invalid", r#"{ "view": { "fov": "wide" "#);
+    let config = running_config(&path);
+    let projection = Projection::new(800, 600, Deg(config.view.fov), 0.1, 100.0);
+
+    assert!(config.reload().is_err());
+    fs::remove_file(&path).unwrap();
+
+    assert!((fov(&projection) - config.view.fov).abs() < 1e-3);
+}
+
+#[test]
+fn nothing_to_reload_without_a_file() {
+    assert!(Config::default().reload().is_err());
+}
+
+#[test]
+fn saved_config_reads_back_the_same() {
+    let config = Config {
+        view: ViewConfig { fov: 72.5, view_radius: 5, sensitivity: 1.25, mouse_dead_zone: 2.0 },
+        terrain: TerrainConfig { seed: 1234, ..Default::default() },
+        window: WindowConfig { fullscreen: true, ..Default::default() },
+        anisotropy: Some(8),
+        light_center: Some([1.0, 2.0, 3.0]),
+        ..Default::default()
+    };
+
+    let path = config_file("round-trip", "");
+    config.save(&path).unwrap();
+    let loaded = Config::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, config);
+}
+
+#[test]
+fn settings_changed_while_running_are_the_ones_saved() {
+    let mut projection = Projection::new(800, 600, Deg(45.0), 0.1, 100.0);
+    let mut controller = CameraController::new(5.0, 0.6);
+    let view = ViewConfig { fov: 80.0, view_radius: 3, sensitivity: 0.9, mouse_dead_zone: 1.5 };
+    view.apply(&mut projection, &mut controller);
+
+    let current = ViewConfig::current(&projection, &controller, 3);
+    assert!((current.fov - view.fov).abs() < 1e-3);
+    assert_eq!(ViewConfig { fov: view.fov, ..current }, view);
+}
@@ -0,0 +1,43 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH, DEFAULT_SEED};
+
+fn with_seed(seed: u32) -> TerrainConfig {
+    TerrainConfig { seed, ..TerrainConfig::default() }
+}
+
+#[test]
+fn same_seed_gives_the_same_content_hash() {
+    assert_eq!(Chunk::new().content_hash(), Chunk::new().content_hash());
+    let a = Chunk::generate_at(with_seed(42), (3, -2));
+    let b = Chunk::generate_at(with_seed(42), (3, -2));
+    assert_eq!(a.content_hash(), b.content_hash());
+}
+
+#[test]
+fn other_seed_changes_the_content_hash() {
+    let a = Chunk::generate(with_seed(DEFAULT_SEED));
+    let b = Chunk::generate(with_seed(DEFAULT_SEED + 1));
+    assert_ne!(a.content_hash(), b.content_hash());
+}
+
+#[test]
+fn edit_changes_the_content_hash() {
+    let mut chunk = Chunk::new();
+    let before = chunk.content_hash();
+    chunk.set_block(0, 0, 0, BlockType::GLASS);
+    assert_ne!(chunk.content_hash(), before);
+}
+
+// the hash does not depend on the build, it can be kept from one run to the next
+#[test]
+fn content_hash_is_stable() {
+    let mut chunk = Chunk::new();
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, if y == 0 { BlockType::STONE } else { BlockType::AIR });
+            }
+        }
+    }
+    assert_eq!(chunk.content_hash(), 0x9255_3470_bb82_2425);
+}
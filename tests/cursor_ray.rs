@@ -0,0 +1,40 @@
+use cgmath::*;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use rover_engine::render::camera::{cursor_ray, Camera, Projection};
+
+const SIZE: PhysicalSize<u32> = PhysicalSize { width: 800, height: 600 };
+
+fn scene() -> (Camera, Projection) {
+    let camera = Camera::new((1.0, 5.0, 10.0), Deg(-60.0), Deg(-20.0));
+    let projection = Projection::new(SIZE.width, SIZE.height, Deg(45.0), 0.1, 100.0);
+    (camera, projection)
+}
+
+#[test]
+fn center_of_the_screen_looks_forward() {
+    let (camera, projection) = scene();
+    let (origin, direction) = cursor_ray(&camera, &projection, PhysicalPosition::new(400.0, 300.0), SIZE);
+    assert!((direction - camera.forward()).magnitude() < 1e-4, "{:?} instead of {:?}", direction, camera.forward());
+    // on the near plane, right in front of the camera
+    assert!(((origin - camera.position).magnitude() - 0.1).abs() < 1e-4);
+}
+
+#[test]
+fn top_left_corner_is_up_and_left() {
+    let (camera, projection) = scene();
+    let (_, direction) = cursor_ray(&camera, &projection, PhysicalPosition::new(0.0, 0.0), SIZE);
+    assert!(direction.dot(camera.right()) < 0.0);
+    assert!(direction.dot(camera.up()) > 0.0);
+    // the corner is half the vertical field of view above the center
+    let vertical = direction.dot(camera.up()).atan2(direction.dot(camera.forward()));
+    assert!((Deg::from(Rad(vertical)).0 - 22.5).abs() < 1e-2);
+}
+
+#[test]
+fn fraction_of_a_pixel_moves_the_ray() {
+    let (camera, projection) = scene();
+    let (_, a) = cursor_ray(&camera, &projection, PhysicalPosition::new(400.0, 300.0), SIZE);
+    let (_, b) = cursor_ray(&camera, &projection, PhysicalPosition::new(400.25, 300.0), SIZE);
+    assert!(a != b);
+    assert!(b.dot(camera.right()) > a.dot(camera.right()));
+}
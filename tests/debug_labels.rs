@@ -0,0 +1,29 @@
+use rover_engine::render::config::Config;
+use rover_engine::render::debug_labels::DebugLabels;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn labels_tell_the_frame_when_enabled() {
+    let labels = DebugLabels::new(true);
+    assert_eq!(labels.label("Render Encoder", 41), "Render Encoder (frame 41)");
+    let pass = labels.detailed_label("Render Pass", 42, || String::from("9 chunks"));
+    assert!(pass.contains("frame 42"));
+    assert!(pass.contains("9 chunks"));
+}
+
+#[test]
+fn labels_keep_their_name_when_disabled() {
+    let labels = DebugLabels::default();
+    assert_eq!(labels.label("Text Pass", 7), "Text Pass");
+    let pass = labels.detailed_label("Render Pass", 7, || panic!("the detail is not needed"));
+    assert_eq!(pass, "Render Pass");
+}
+
+#[test]
+fn labels_are_enabled_by_the_flag() {
+    assert!(Config::from_args(args(&["--debug-labels"])).unwrap().debug_labels);
+    assert!(!Config::default().debug_labels);
+}
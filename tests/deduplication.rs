@@ -0,0 +1,50 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::mesh::{deduplicate_vertices, Mesh, MeshBuilder};
+
+const UP: [f32; 3] = [0.0, 1.0, 0.0];
+
+// the corners of each triangle, to compare what is drawn before and after
+fn triangles(mesh: &Mesh) -> Vec<[f32; 3]> {
+    mesh.indices.iter().map(|&i| mesh.vertices[i as usize].position).collect()
+}
+
+#[test]
+fn quads_sharing_an_edge_share_its_corners() {
+    let mut builder = MeshBuilder::new();
+    builder.add_quad([[0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 0.0, 0.0]], [1.0; 3], UP);
+    builder.add_quad([[1.0, 0.0, 0.0], [1.0, 0.0, 1.0], [2.0, 0.0, 1.0], [2.0, 0.0, 0.0]], [1.0; 3], UP);
+    let mesh = builder.build();
+    let deduplicated = deduplicate_vertices(&mesh);
+    assert_eq!((mesh.vertex_count(), deduplicated.vertex_count()), (8, 6));
+    assert_eq!(deduplicated.triangle_count(), mesh.triangle_count());
+    assert_eq!(triangles(&deduplicated), triangles(&mesh));
+}
+
+#[test]
+fn corners_of_faces_looking_apart_are_kept() {
+    let mut builder = MeshBuilder::new();
+    builder.add_quad([[0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 0.0, 0.0]], [1.0; 3], UP);
+    builder.add_quad([[1.0, 0.0, 0.0], [1.0, 0.0, 1.0], [2.0, 0.0, 1.0], [2.0, 0.0, 0.0]], [1.0; 3], [0.0, -1.0, 0.0]);
+    assert_eq!(deduplicate_vertices(&builder.build()).vertex_count(), 8);
+}
+
+#[test]
+fn neighbouring_blocks_shrink_their_mesh() {
+    let mut chunk = Chunk::new();
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, BlockType::AIR);
+            }
+        }
+    }
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    chunk.set_block(5, 4, 4, BlockType::STONE);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    let deduplicated = deduplicate_vertices(&mesh);
+    // the 4 coplanar faces along the two blocks share 2 corners each
+    assert_eq!(mesh.vertex_count() - deduplicated.vertex_count(), 4 * 2);
+    assert_eq!(deduplicated.triangle_count(), mesh.triangle_count());
+    assert_eq!(triangles(&deduplicated), triangles(&mesh));
+}
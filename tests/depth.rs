@@ -0,0 +1,38 @@
+use rover_engine::render::state::{depth_stencil_state, primitive_state, WIREFRAME_DEPTH_BIAS};
+use rover_engine::render::texture::Texture;
+use wgpu::TextureFormat;
+
+#[test]
+fn first_usable_depth_format_is_chosen() {
+    assert_eq!(Texture::pick_depth_format(&Texture::DEPTH_FORMATS, |_| true), TextureFormat::Depth32Float);
+    let no_float = |format| format != TextureFormat::Depth32Float;
+    assert_eq!(Texture::pick_depth_format(&Texture::DEPTH_FORMATS, no_float), TextureFormat::Depth24Plus);
+}
+
+#[test]
+fn last_depth_format_is_kept_when_none_is_usable() {
+    assert_eq!(Texture::pick_depth_format(&Texture::DEPTH_FORMATS, |_| false), TextureFormat::Depth24Plus);
+    assert_eq!(Texture::pick_depth_format(&[], |_| true), TextureFormat::Depth24Plus);
+}
+
+#[test]
+fn pipelines_test_against_the_depth_texture_format() {
+    for &format in Texture::DEPTH_FORMATS.iter() {
+        let chosen = Texture::pick_depth_format(&[format], |_| true);
+        let state = depth_stencil_state(chosen, wgpu::DepthBiasState::default());
+        assert_eq!(state.format, format);
+        assert_eq!(state.depth_compare, wgpu::CompareFunction::Less);
+    }
+}
+
+#[test]
+fn wireframe_is_pulled_in_front_of_the_faces() {
+    let filled = depth_stencil_state(TextureFormat::Depth32Float, wgpu::DepthBiasState::default());
+    let wireframe = depth_stencil_state(TextureFormat::Depth32Float, WIREFRAME_DEPTH_BIAS);
+    assert!(!filled.bias.is_enabled());
+    assert_eq!(wireframe.bias, WIREFRAME_DEPTH_BIAS);
+    // the closer depths win the Less test
+    assert!(wireframe.bias.constant < 0 && wireframe.bias.slope_scale < 0.0);
+    assert_eq!(wireframe.depth_compare, filled.depth_compare);
+    assert_eq!(primitive_state(wgpu::PrimitiveTopology::TriangleList, wgpu::PolygonMode::Line).polygon_mode, wgpu::PolygonMode::Line);
+}
@@ -0,0 +1,14 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use rover_engine::render::state::State;
+
+#[test]
+fn trace_goes_to_the_configured_directory() {
+    assert_eq!(State::trace_path(Some(OsString::from("traces/run"))), Some(PathBuf::from("traces/run")));
+}
+
+#[test]
+fn trace_is_off_without_the_variable() {
+    assert_eq!(State::trace_path(None), None);
+    assert_eq!(State::trace_path(Some(OsString::new())), None);
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use cgmath::*;
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig};
+use rover_engine::render::terrain::chunk_manager::{ChunkManager, SeedMode};
+
+// the update cycles of the State, counting how many times each chunk is meshed
+fn run_updates(manager: &mut ChunkManager, updates: usize, remeshed: &mut HashMap<(i32, i32), usize>) {
+    for _ in 0..updates {
+        for position in manager.take_dirty() {
+            *remeshed.entry(position).or_insert(0) += 1;
+        }
+    }
+}
+
+fn generated_manager() -> ChunkManager {
+    let mut manager = ChunkManager::new(TerrainConfig::default(), 1, SeedMode::Shared);
+    manager.generation_budget = usize::MAX;
+    manager.generate_pending(Point3::new(0.0, 0.0, 0.0), |_| true);
+    manager
+}
+
+#[test]
+fn only_the_edited_chunk_is_meshed_again() {
+    let mut manager = generated_manager();
+    let mut remeshed = HashMap::new();
+    run_updates(&mut manager, 3, &mut remeshed);
+    // the new chunks are meshed once and then left alone
+    assert_eq!(remeshed.len(), 8);
+    assert!(remeshed.values().all(|&count| count == 1));
+
+    remeshed.clear();
+    manager.chunks.get_mut(&(1, 0)).unwrap().set_block(2, 3, 4, BlockType::STONE);
+    run_updates(&mut manager, 5, &mut remeshed);
+    assert_eq!(remeshed, [((1, 0), 1)].iter().cloned().collect());
+}
+
+#[test]
+fn edit_marks_the_chunk_dirty_once() {
+    let mut chunk = Chunk::new();
+    assert!(chunk.take_dirty());
+    assert!(!chunk.take_dirty());
+    chunk.set_block(0, 0, 0, BlockType::DIRT);
+    chunk.set_block(1, 0, 0, BlockType::DIRT);
+    assert!(chunk.take_dirty());
+    assert!(!chunk.take_dirty());
+}
@@ -0,0 +1,37 @@
+use rover_engine::render::stats::{DrawStats, FrameDrawStats};
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours};
+
+#[test]
+fn terrain_triangles_are_the_indices_of_its_meshes_by_three() {
+    let chunk = Chunk::new();
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    let num_index = mesh.indices.len() as u32;
+    assert!(num_index > 0);
+
+    let mut stats = FrameDrawStats::default();
+    stats.terrain.record(num_index);
+    assert_eq!(stats.terrain, DrawStats { draw_calls: 1, vertices: num_index, triangles: num_index / 3 });
+    assert_eq!(stats.terrain.triangles as usize, mesh.triangle_count());
+}
+
+#[test]
+fn lines_and_points_make_no_triangles() {
+    let mut stats = DrawStats::default();
+    stats.record_lines(24);
+    stats.record_points(100);
+    assert_eq!(stats, DrawStats { draw_calls: 2, vertices: 124, triangles: 0 });
+}
+
+#[test]
+fn overlay_draws_stay_out_of_the_terrain() {
+    let mut stats = FrameDrawStats::default();
+    stats.terrain.record(600);
+    stats.terrain.record(60);
+    // the light marker, the tone map and the text
+    stats.overlay.record(36);
+    stats.overlay.record(3);
+    stats.overlay.record(120);
+
+    assert_eq!(stats.terrain, DrawStats { draw_calls: 2, vertices: 660, triangles: 220 });
+    assert_eq!(stats.total(), DrawStats { draw_calls: 5, vertices: 819, triangles: 273 });
+}
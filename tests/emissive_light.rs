@@ -0,0 +1,63 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours};
+use rover_engine::render::terrain::lighting::{light_factor, LightMap, MAX_LIGHT};
+
+fn empty_chunk() -> Chunk {
+    Chunk::from_voxel_str(".").unwrap()
+}
+
+#[test]
+fn lamp_lights_the_blocks_next_to_it() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::LAMP);
+    let light = LightMap::compute(&chunk);
+    assert_eq!(light.level(4, 4, 4), MAX_LIGHT);
+    for &(x, y, z) in [(5, 4, 4), (3, 4, 4), (4, 5, 4), (4, 3, 4), (4, 4, 5), (4, 4, 3)].iter() {
+        assert_eq!(light.level(x, y, z), MAX_LIGHT - 1);
+        assert!(light_factor(light.level(x, y, z)) > light_factor(0));
+    }
+    // one level less for each block away, none past the range
+    assert_eq!(light.level(6, 5, 4), MAX_LIGHT - 3);
+    assert_eq!(light.level(4 + MAX_LIGHT as i32, 4, 4), 0);
+}
+
+#[test]
+fn no_light_without_a_lamp() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    let light = LightMap::compute(&chunk);
+    assert_eq!(light.level(5, 4, 4), 0);
+    assert_eq!(light_factor(0), 1.0);
+}
+
+#[test]
+fn opaque_blocks_stop_the_light() {
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::LAMP);
+    // a closed stone box around the lamp
+    for x in 3..=5 {
+        for y in 3..=5 {
+            for z in 3..=5 {
+                if (x, y, z) != (4, 4, 4) {
+                    chunk.set_block(x, y, z, BlockType::STONE);
+                }
+            }
+        }
+    }
+    assert_eq!(LightMap::compute(&chunk).level(7, 4, 4), 0);
+}
+
+#[test]
+fn faces_near_the_lamp_are_brighter() {
+    let brightest = |chunk: &Chunk| {
+        chunk.create_mesh(&VerticalNeighbours::default()).vertices.iter()
+            .filter(|v| v.position[0] > 1.5)
+            .map(|v| v.color[0])
+            .fold(0.0, f32::max)
+    };
+    let mut chunk = empty_chunk();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    let unlit = brightest(&chunk);
+    chunk.set_block(2, 4, 4, BlockType::LAMP);
+    assert!(brightest(&chunk) > unlit, "{} is not over {}", brightest(&chunk), unlit);
+}
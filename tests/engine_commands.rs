@@ -0,0 +1,42 @@
+use std::sync::mpsc;
+use rover_engine::render::command::{self, EngineCommand};
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, CHUNK_WIDTH};
+
+// what State::apply_commands does with the block edits at the start of an update
+fn update(chunk: &mut Chunk, commands: &mpsc::Receiver<EngineCommand>) -> usize {
+    let mut applied = 0;
+    for command in commands.try_iter() {
+        if let EngineCommand::SetBlock { position, block_type } = command {
+            applied += command::set_block(chunk, position, block_type) as usize;
+        }
+    }
+    applied
+}
+
+#[test]
+fn set_block_lands_on_the_next_update() {
+    let mut chunk = Chunk::new();
+    chunk.take_dirty();
+    let (sender, commands) = mpsc::channel();
+    let position = [3, 30, 5];
+    assert_ne!(chunk.block_at(3, 30, 5), Some(BlockType::LAMP));
+
+    // from another thread, as a script or the network would
+    std::thread::spawn(move || {
+        sender.send(EngineCommand::SetBlock { position, block_type: BlockType::LAMP }).unwrap();
+    }).join().unwrap();
+    assert_ne!(chunk.block_at(3, 30, 5), Some(BlockType::LAMP));
+
+    assert_eq!(update(&mut chunk, &commands), 1);
+    assert_eq!(chunk.block_at(3, 30, 5), Some(BlockType::LAMP));
+    assert!(chunk.take_dirty(), "the edit is not meshed");
+}
+
+#[test]
+fn set_block_out_of_the_chunk_is_ignored() {
+    let mut chunk = Chunk::new();
+    chunk.take_dirty();
+    assert!(!command::set_block(&mut chunk, [CHUNK_WIDTH, 0, 0], BlockType::STONE));
+    assert!(!chunk.take_dirty());
+}
@@ -0,0 +1,29 @@
+use winit::event::*;
+use rover_engine::render::window::requests_exit;
+
+#[allow(deprecated)]
+fn key(state: ElementState, keycode: VirtualKeyCode) -> WindowEvent<'static> {
+    WindowEvent::KeyboardInput {
+        device_id: unsafe { DeviceId::dummy() },
+        input: KeyboardInput { scancode: 0, state, virtual_keycode: Some(keycode), modifiers: ModifiersState::empty() },
+        is_synthetic: false,
+    }
+}
+
+#[test]
+fn closing_the_window_exits() {
+    assert!(requests_exit(&WindowEvent::CloseRequested));
+}
+
+#[test]
+fn pressing_escape_exits() {
+    assert!(requests_exit(&key(ElementState::Pressed, VirtualKeyCode::Escape)));
+    // only once, the release does not ask again
+    assert!(!requests_exit(&key(ElementState::Released, VirtualKeyCode::Escape)));
+}
+
+#[test]
+fn other_events_do_not_exit() {
+    assert!(!requests_exit(&key(ElementState::Pressed, VirtualKeyCode::F11)));
+    assert!(!requests_exit(&WindowEvent::Focused(false)));
+}
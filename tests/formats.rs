@@ -0,0 +1,23 @@
+use rover_engine::render::state::{scene_format, swap_chain_format, FALLBACK_SWAP_CHAIN_FORMAT};
+use rover_engine::render::tonemap::HDR_FORMAT;
+
+// State::color_format gives back the scene_format the pipelines were built with, building
+// a State needs a surface so these tests check the choice of the formats themselves
+
+#[test]
+fn scene_is_drawn_in_the_hdr_format() {
+    assert_eq!(scene_format(Some(HDR_FORMAT), wgpu::TextureFormat::Bgra8UnormSrgb), HDR_FORMAT);
+}
+
+#[test]
+fn scene_is_drawn_in_the_swap_chain_without_hdr() {
+    let format = swap_chain_format(Some(wgpu::TextureFormat::Rgba8UnormSrgb));
+    assert_eq!(format, wgpu::TextureFormat::Rgba8UnormSrgb);
+    assert_eq!(scene_format(None, format), format);
+}
+
+#[test]
+fn unsupported_swap_chain_format_falls_back() {
+    assert_eq!(swap_chain_format(Some(wgpu::TextureFormat::R8Unorm)), FALLBACK_SWAP_CHAIN_FORMAT);
+    assert_eq!(swap_chain_format(None), FALLBACK_SWAP_CHAIN_FORMAT);
+}
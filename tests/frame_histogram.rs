@@ -0,0 +1,54 @@
+use std::time::Duration;
+use rover_engine::render::stats::{FrameTimeHistogram, HISTOGRAM_BUCKET, HISTOGRAM_RANGE};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+// 1 to 100ms, one frame each
+fn ramp() -> FrameTimeHistogram {
+    let mut histogram = FrameTimeHistogram::new();
+    for millis in 1..=100 {
+        histogram.record(ms(millis) - Duration::from_micros(50));
+    }
+    histogram
+}
+
+#[test]
+fn percentiles_of_a_ramp() {
+    let histogram = ramp();
+    assert_eq!(histogram.frames, 100);
+    assert_eq!(histogram.percentile(50.0), ms(50));
+    assert_eq!(histogram.percentile(95.0), ms(95));
+    assert_eq!(histogram.percentile(99.0), ms(99));
+    assert_eq!(histogram.percentile(100.0), ms(100));
+}
+
+#[test]
+fn spikes_show_in_the_high_percentiles() {
+    let mut histogram = FrameTimeHistogram::new();
+    for _ in 0..980 {
+        histogram.record(Duration::from_micros(16_650));
+    }
+    for _ in 0..20 {
+        histogram.record(ms(40));
+    }
+    assert_eq!(histogram.percentile(50.0), Duration::from_micros(16_700));
+    assert_eq!(histogram.percentile(95.0), Duration::from_micros(16_700));
+    assert_eq!(histogram.percentile(99.0), ms(40) + HISTOGRAM_BUCKET);
+}
+
+#[test]
+fn long_frames_land_in_the_last_bucket() {
+    let mut histogram = FrameTimeHistogram::new();
+    histogram.record(Duration::from_secs(3));
+    assert_eq!(histogram.percentile(50.0), HISTOGRAM_RANGE + HISTOGRAM_BUCKET);
+    assert_eq!(histogram.total, Duration::from_secs(3));
+}
+
+#[test]
+fn empty_histogram_has_no_percentiles() {
+    let histogram = FrameTimeHistogram::new();
+    assert_eq!(histogram.percentile(99.0), Duration::from_secs(0));
+    assert!(histogram.summary().contains("0 frames"));
+}
@@ -0,0 +1,31 @@
+use rover_engine::render::config::Config;
+use rover_engine::render::stats::FrameLimit;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+// the event loop exits on the frame for which record gives true
+#[test]
+fn loop_stops_after_the_last_frame() {
+    let mut limit = FrameLimit::new(3);
+    let frames = std::iter::repeat(()).take_while(|_| !limit.record()).count() + 1;
+    assert_eq!(frames, 3);
+    assert!(limit.finished());
+    assert_eq!(limit.drawn, 3);
+}
+
+#[test]
+fn single_frame_stops_at_once() {
+    let mut limit = FrameLimit::new(1);
+    assert!(!limit.finished());
+    assert!(limit.record());
+}
+
+#[test]
+fn frames_are_read_from_the_flag() {
+    let config = Config::from_args(args(&["--frames", "120"])).unwrap();
+    assert_eq!(config.frames, Some(120));
+    assert!(Config::from_args(args(&["--frames", "0"])).is_err());
+    assert_eq!(Config::default().frames, None);
+}
@@ -0,0 +1,21 @@
+use std::time::Duration;
+use rover_engine::render::config::Config;
+use rover_engine::render::stats::{clamp_frame_time, max_frame_time, DEFAULT_MAX_FRAME_TIME};
+
+#[test]
+fn stalled_frame_is_clamped_and_a_normal_one_is_not() {
+    let max = max_frame_time(None);
+    assert_eq!(max, DEFAULT_MAX_FRAME_TIME);
+    assert_eq!(clamp_frame_time(Duration::from_secs(2), max), Duration::from_millis(100));
+    assert_eq!(clamp_frame_time(Duration::from_millis(16), max), Duration::from_millis(16));
+}
+
+#[test]
+fn max_frame_time_comes_from_the_command_line() {
+    let args = ["--max-frame-time", "0.05"].iter().map(|s| s.to_string());
+    let config = Config::from_args(args).unwrap();
+    let max = max_frame_time(config.max_frame_time);
+    assert!((max.as_secs_f32() - 0.05).abs() < 1e-6);
+    assert_eq!(clamp_frame_time(Duration::from_secs(2), max), max);
+    assert!(Config::from_args(["--max-frame-time", "0"].iter().map(|s| s.to_string())).is_err());
+}
@@ -0,0 +1,42 @@
+use cgmath::*;
+use rover_engine::render::camera::{Camera, Frustum, Projection};
+use rover_engine::render::math::Aabb;
+
+// a row of chunk sized boxes along x, in front of the camera
+fn chunks() -> Vec<Aabb> {
+    (-8..8).map(|i| {
+        let min = Vector3::new(i as f32 * 8.0, 0.0, -20.0);
+        Aabb::new(min, min + Vector3::new(8.0, 16.0, 8.0))
+    }).collect()
+}
+
+fn culled(frustum: &Frustum) -> Vec<bool> {
+    chunks().iter().map(|chunk| !frustum.contains_box(chunk)).collect()
+}
+
+fn scene() -> (Camera, Projection) {
+    let camera = Camera::new((0.0, 8.0, 10.0), Deg(-90.0), Deg(0.0));
+    let projection = Projection::new(800, 600, Deg(45.0), 0.1, 100.0);
+    (camera, projection)
+}
+
+#[test]
+fn frozen_frustum_ignores_the_camera() {
+    let (mut camera, projection) = scene();
+    let frozen = Frustum::from_camera(&camera, &projection);
+    let before = culled(&Frustum::culling(Some(&frozen), &camera, &projection));
+
+    camera.position = Point3::new(40.0, 8.0, 10.0);
+    camera.yaw = Deg(-45.0).into();
+    let after = culled(&Frustum::culling(Some(&frozen), &camera, &projection));
+    assert_eq!(before, after);
+}
+
+#[test]
+fn frustum_follows_the_camera_once_unfrozen() {
+    let (mut camera, projection) = scene();
+    let before = culled(&Frustum::culling(None, &camera, &projection));
+    camera.position = Point3::new(40.0, 8.0, 10.0);
+    let after = culled(&Frustum::culling(None, &camera, &projection));
+    assert!(before != after);
+}
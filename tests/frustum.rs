@@ -0,0 +1,42 @@
+use cgmath::*;
+use rover_engine::render::camera::{Camera, Frustum, Projection};
+use rover_engine::render::math::Aabb;
+
+// at the origin looking along -z, 45 degrees on each side
+fn frustum() -> Frustum {
+    let camera = Camera::new((0.0, 0.0, 0.0), Deg(-90.0), Deg(0.0));
+    let projection = Projection::new(600, 600, Deg(90.0), 0.1, 100.0);
+    Frustum::from_camera(&camera, &projection)
+}
+
+#[test]
+fn sphere_inside_is_kept() {
+    assert!(frustum().contains_sphere(Point3::new(0.0, 0.0, -10.0), 1.0));
+}
+
+#[test]
+fn spheres_outside_are_culled() {
+    let frustum = frustum();
+    // behind the camera, left of the left plane and past the far plane
+    assert!(!frustum.contains_sphere(Point3::new(0.0, 0.0, 5.0), 1.0));
+    assert!(!frustum.contains_sphere(Point3::new(-20.0, 0.0, -10.0), 1.0));
+    assert!(!frustum.contains_sphere(Point3::new(0.0, 0.0, -110.0), 1.0));
+}
+
+#[test]
+fn sphere_across_a_plane_is_kept() {
+    // the center is 1 outside of the left plane, the sphere reaches 2 inside of it
+    let center = Point3::new(-10.0 - 2.0f32.sqrt(), 0.0, -10.0);
+    let frustum = frustum();
+    assert!(frustum.contains_sphere(center, 3.0));
+    assert!(!frustum.contains_sphere(center, 0.5));
+}
+
+#[test]
+fn box_test_starts_with_the_sphere() {
+    let frustum = frustum();
+    let inside = Aabb::new(Vector3::new(-1.0, -1.0, -11.0), Vector3::new(1.0, 1.0, -9.0));
+    let outside = Aabb::new(Vector3::new(-1.0, -1.0, 9.0), Vector3::new(1.0, 1.0, 11.0));
+    assert!(frustum.contains_box(&inside));
+    assert!(!frustum.contains_box(&outside));
+}
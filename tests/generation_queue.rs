@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+use cgmath::*;
+use rover_engine::render::math::Aabb;
+use rover_engine::render::terrain::block::HALF_BLOCK_SIZE;
+use rover_engine::render::terrain::chunk::{TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::chunk_manager::{in_radius, ChunkManager, SeedMode};
+
+// the middle of the chunk at position, where the camera stands
+fn chunk_center(position: (i32, i32)) -> Point3<f32> {
+    let size = 2.0 * HALF_BLOCK_SIZE;
+    let middle = |count: usize| count as f32 * size / 2.0 - HALF_BLOCK_SIZE;
+    Point3::new(
+        position.0 as f32 * CHUNK_WIDTH as f32 * size + middle(CHUNK_WIDTH),
+        middle(CHUNK_HEIGHT),
+        position.1 as f32 * CHUNK_WIDTH as f32 * size + middle(CHUNK_WIDTH),
+    )
+}
+
+// the positions in the order they are generated, one at a time
+fn generation_order<F: Fn(&Aabb) -> bool>(manager: &mut ChunkManager, camera: Point3<f32>, in_view: F) -> Vec<(i32, i32)> {
+    manager.generation_budget = 1;
+    let mut order = vec![];
+    let mut generated = HashSet::new();
+    while manager.pending_count() > 0 {
+        assert_eq!(manager.generate_pending(camera, &in_view), 1);
+        let new: Vec<_> = manager.chunks.keys().filter(|position| generated.insert(**position)).copied().collect();
+        assert_eq!(new.len(), 1);
+        order.push(new[0]);
+    }
+    order
+}
+
+#[test]
+fn nearest_chunk_is_generated_first() {
+    let mut manager = ChunkManager::new(TerrainConfig::default(), 1, SeedMode::Shared);
+    assert_eq!(manager.pending_count(), 8);
+    let order = generation_order(&mut manager, chunk_center((1, 0)), |_| false);
+    assert_eq!(order.len(), 8);
+    assert_eq!(order[0], (1, 0));
+    // then the ones next to it, the far side of the main chunk comes last
+    assert!(order[1..3].contains(&(1, 1)) && order[1..3].contains(&(1, -1)), "{:?}", order);
+    assert!(order[5..].contains(&(-1, 0)), "{:?}", order);
+}
+
+#[test]
+fn chunks_in_the_view_go_before_closer_ones() {
+    let mut manager = ChunkManager::new(TerrainConfig::default(), 1, SeedMode::Shared);
+    // only the chunks at x <= 0 and z >= 1 are in the view
+    let in_view = |space: &Aabb| space.min.x < 0.0 && space.min.z > 0.0;
+    let order = generation_order(&mut manager, chunk_center((1, 0)), in_view);
+    // the bonus of two chunks brings (0, 1) before the camera chunk, not (-1, 1) which is further
+    assert_eq!(order[..3], [(0, 1), (1, 0), (-1, 1)]);
+}
+
+#[test]
+fn the_budget_limits_each_update() {
+    let mut manager = ChunkManager::new(TerrainConfig::default(), 1, SeedMode::Shared);
+    manager.generation_budget = 3;
+    assert_eq!(manager.generate_pending(Point3::new(0.0, 0.0, 0.0), |_| true), 3);
+    assert_eq!((manager.chunks.len(), manager.pending_count()), (3, 5));
+    manager.generation_budget = 10;
+    assert_eq!(manager.generate_pending(Point3::new(0.0, 0.0, 0.0), |_| true), 5);
+    assert_eq!(manager.generate_pending(Point3::new(0.0, 0.0, 0.0), |_| true), 0);
+}
+
+#[test]
+fn chunks_out_of_the_radius_are_dropped_from_the_queue() {
+    let mut manager = ChunkManager::new(TerrainConfig::default(), 2, SeedMode::Shared);
+    assert_eq!(manager.pending_count(), 24);
+    // the radius shrinks before the queue is done, the ring around it is not wanted anymore
+    manager.radius = 1;
+    manager.generation_budget = usize::MAX;
+    assert_eq!(manager.generate_pending(chunk_center((2, 2)), |_| true), 8);
+    assert_eq!(manager.pending_count(), 0);
+    assert!(manager.chunks.keys().all(|&position| in_radius(position, 1)));
+}
+
+#[test]
+fn chunk_queued_then_out_of_the_radius_is_not_uploaded() {
+    let mut manager = ChunkManager::new(TerrainConfig::default(), 1, SeedMode::Shared);
+    manager.generation_budget = usize::MAX;
+    manager.generate_pending(chunk_center((0, 0)), |_| true);
+    manager.take_dirty();
+
+    // the chunks already there are kept, only the new ring is queued
+    manager.set_radius(2);
+    assert_eq!((manager.chunks.len(), manager.pending_count()), (8, 16));
+    manager.generation_budget = 1;
+    assert_eq!(manager.generate_pending(chunk_center((2, 2)), |_| true), 1);
+    assert!(manager.chunks.contains_key(&(2, 2)));
+
+    manager.set_radius(1);
+    assert_eq!((manager.chunks.len(), manager.pending_count()), (8, 0));
+    assert_eq!(manager.generate_pending(chunk_center((2, 2)), |_| true), 0);
+    // the State uploads the dirty chunks, none of the ring is left to be
+    assert!(manager.take_dirty().is_empty());
+}
@@ -0,0 +1,52 @@
+use image::{Rgba, RgbaImage};
+use rover_engine::render::golden::{check_golden, diff_images, ImageDiff, DEFAULT_PIXEL_TOLERANCE};
+
+fn gray(width: u32, height: u32, value: u8) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+}
+
+#[test]
+fn identical_images_do_not_differ() {
+    let image = gray(4, 4, 100);
+    let diff = diff_images(&image, &image, 0).unwrap();
+    assert_eq!(diff, ImageDiff { differing_pixels: 0, total_pixels: 16, max_channel_diff: 0 });
+    assert_eq!(diff.ratio(), 0.0);
+}
+
+#[test]
+fn pixels_within_the_tolerance_are_the_same() {
+    let golden = gray(4, 4, 100);
+    let mut actual = golden.clone();
+    actual.put_pixel(0, 0, Rgba([100 + DEFAULT_PIXEL_TOLERANCE, 100, 100, 255]));
+    actual.put_pixel(1, 0, Rgba([100, 100 - DEFAULT_PIXEL_TOLERANCE - 1, 100, 255]));
+
+    let diff = diff_images(&actual, &golden, DEFAULT_PIXEL_TOLERANCE).unwrap();
+    // only the second pixel is past the tolerance, below the golden
+    assert_eq!(diff.differing_pixels, 1);
+    assert_eq!(diff.max_channel_diff, DEFAULT_PIXEL_TOLERANCE + 1);
+    assert!((diff.ratio() - 1.0 / 16.0).abs() < 1e-6);
+}
+
+#[test]
+fn images_of_other_sizes_are_refused() {
+    let error = diff_images(&gray(4, 4, 0), &gray(4, 2, 0), 0).unwrap_err();
+    assert!(error.to_string().contains("4x4 but the golden is 4x2"), "{}", error);
+}
+
+#[test]
+fn golden_check_allows_a_few_differing_pixels() {
+    let path = std::env::temp_dir().join(format!("rover-engine-golden-{}.png", std::process::id()));
+    let golden = gray(10, 10, 50);
+    golden.save(&path).unwrap();
+
+    let mut actual = golden.clone();
+    actual.put_pixel(3, 3, Rgba([255, 50, 50, 255]));
+    let one_pixel = check_golden(&path, &actual, 0, 0.01);
+    actual.put_pixel(4, 3, Rgba([255, 50, 50, 255]));
+    let two_pixels = check_golden(&path, &actual, 0, 0.01);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(one_pixel.is_ok());
+    let error = two_pixels.unwrap_err().to_string();
+    assert!(error.contains("2 of 100 pixels differ"), "{}", error);
+}
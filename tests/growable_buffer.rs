@@ -0,0 +1,34 @@
+use rover_engine::render::growable_buffer::{grown_capacity, reallocation};
+
+#[test]
+fn data_that_fits_is_not_reallocated() {
+    assert_eq!(reallocation(1024, 1024), None);
+    assert_eq!(reallocation(1024, 10), None);
+    assert_eq!(reallocation(1024, 1025), Some(grown_capacity(1024, 1025)));
+}
+
+#[test]
+fn capacity_grows_by_half_or_to_the_data() {
+    assert_eq!(grown_capacity(1000, 1001), 1500);
+    // a much bigger mesh is given what it needs, aligned to 4 bytes
+    assert_eq!(grown_capacity(1000, 4001), 4004);
+}
+
+#[test]
+fn growing_meshes_reallocate_less_than_once_each() {
+    // a chunk edited again and again, each mesh 3% bigger than the last
+    let mut capacity = 100_000;
+    let mut reallocations = 0;
+    let mut size = 100_000.0_f64;
+    let meshes = 50;
+    for _ in 0..meshes {
+        size *= 1.03;
+        if let Some(grown) = reallocation(capacity, size as usize) {
+            capacity = grown;
+            reallocations += 1;
+        }
+        assert!(capacity >= size as u64);
+    }
+    // growing to the size needed would have reallocated for every mesh
+    assert!(reallocations < meshes / 4, "{} reallocations for {} meshes", reallocations, meshes);
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+use std::time::Duration;
+use futures::executor::block_on;
+use rover_engine::render::camera::{Camera, UpAxis};
+use rover_engine::render::config::Config;
+use rover_engine::render::golden::{check_golden, DEFAULT_MAX_DIFF_RATIO, DEFAULT_PIXEL_TOLERANCE};
+use rover_engine::render::state::{DeviceContext, State};
+use rover_engine::render::texture::Texture;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+// a device of our own, without any window, None on the machines without an adapter
+fn headless_device() -> Option<(wgpu::Adapter, wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
+    let adapter = block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        compatible_surface: None,
+    }))?;
+    let (device, queue) = block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None)).ok()?;
+    Some((adapter, device, queue))
+}
+
+// an offscreen State of that size on a device of our own, None without an adapter
+fn headless_state(width: u32, height: u32) -> Option<State> {
+    let (adapter, device, queue) = headless_device()?;
+    let context = DeviceContext {
+        device,
+        queue,
+        surface: None,
+        swap_chain_desc: wgpu::SwapChainDescriptor {
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+        },
+        depth_format: Texture::choose_depth_format(&adapter, &Texture::DEPTH_FORMATS),
+        hdr_format: None,
+    };
+    Some(State::from_device(context, &Config::default()))
+}
+
+#[test]
+fn state_draws_on_a_device_created_outside_of_it() {
+    let mut state = match headless_state(WIDTH, HEIGHT) {
+        Some(state) => state,
+        None => {
+            eprintln!("No adapter found, skipping");
+            return;
+        }
+    };
+    assert!(state.swap_chain.is_none());
+
+    state.render().unwrap();
+    let frame = state.capture_frame().unwrap();
+    assert_eq!(frame.dimensions(), (WIDTH, HEIGHT));
+}
+
+// Run with ROVER_UPDATE_GOLDENS=1 to write the golden again once the change of the image
+// is the expected one
+#[test]
+fn default_chunk_matches_its_golden() {
+    let mut state = match headless_state(256, 192) {
+        Some(state) => state,
+        None => {
+            eprintln!("No adapter found, skipping");
+            return;
+        }
+    };
+    // the default seed, seen from above one of its corners
+    state.camera = Camera::look_at((-4.0, 14.0, -4.0), (4.0, 4.0, 4.0), UpAxis::Y);
+    state.update(Duration::from_secs(0));
+
+    let frame = state.capture_frame().unwrap();
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/goldens/default_chunk.png");
+    check_golden(golden, &frame, DEFAULT_PIXEL_TOLERANCE, DEFAULT_MAX_DIFF_RATIO).unwrap();
+}
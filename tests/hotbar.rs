@@ -0,0 +1,31 @@
+use rover_engine::render::hotbar::{Hotbar, HOTBAR_BLOCKS};
+
+#[test]
+fn scrolling_moves_to_the_next_blocks() {
+    let mut hotbar = Hotbar::new();
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[0]);
+    hotbar.scroll(1);
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[1]);
+    hotbar.scroll(2);
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[3]);
+}
+
+#[test]
+fn scrolling_wraps_around_both_ends() {
+    let mut hotbar = Hotbar::new();
+    hotbar.scroll(-1);
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[HOTBAR_BLOCKS.len() - 1]);
+    hotbar.scroll(1);
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[0]);
+    hotbar.scroll(HOTBAR_BLOCKS.len() as i32 * 3 + 2);
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[2]);
+}
+
+#[test]
+fn number_keys_out_of_the_hotbar_are_ignored() {
+    let mut hotbar = Hotbar::new();
+    hotbar.select(4);
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[4]);
+    hotbar.select(HOTBAR_BLOCKS.len());
+    assert_eq!(hotbar.selected(), HOTBAR_BLOCKS[4]);
+}
@@ -0,0 +1,24 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig};
+use rover_engine::render::terrain::noise::{NoiseGenerator, NoiseKind};
+
+fn block_types(chunk: &Chunk) -> Vec<BlockType> {
+    chunk.iter_blocks().map(|(_, _, _, block)| block).collect()
+}
+
+#[test]
+fn same_generator_gives_the_same_chunk() {
+    let a = Chunk::with_noise(NoiseGenerator::from_seed(99, NoiseKind::Perlin));
+    let b = Chunk::with_noise(NoiseGenerator::from_seed(99, NoiseKind::Perlin));
+    assert_eq!(block_types(&a), block_types(&b));
+}
+
+#[test]
+fn chunk_is_shaped_by_the_given_generator() {
+    let default = TerrainConfig::default();
+    // the generator the seed would give, then another one
+    let from_seed = Chunk::with_noise(NoiseGenerator::from_seed(default.seed, default.noise));
+    let other = Chunk::with_noise(NoiseGenerator::from_seed(default.seed + 1, default.noise));
+    assert_eq!(block_types(&from_seed), block_types(&Chunk::generate(default)));
+    assert_ne!(block_types(&other), block_types(&from_seed));
+}
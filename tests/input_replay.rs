@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use rover_engine::render::input_replay::{InputEvent, InputPlayer, InputRecorder, RecordedInput};
+use winit::event::{ElementState, VirtualKeyCode};
+
+fn key(key: VirtualKeyCode) -> InputEvent {
+    InputEvent::Key { key, state: ElementState::Pressed }
+}
+
+fn at(millis: u64, event: InputEvent) -> RecordedInput {
+    RecordedInput { time: Duration::from_millis(millis), event }
+}
+
+fn recording_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rover-engine-{}-{}.json", name, std::process::id()))
+}
+
+#[test]
+fn inputs_are_due_once_the_time_reaches_them() {
+    let motion = InputEvent::MouseMotion { dx: 1.0, dy: -2.0 };
+    // recorded out of order, the two at 20ms keep theirs
+    let mut player = InputPlayer::new(vec![at(50, key(VirtualKeyCode::Space)), at(20, key(VirtualKeyCode::W)), at(20, motion)]);
+    assert!(player.due(Duration::from_millis(10)).is_empty());
+    assert_eq!(player.due(Duration::from_millis(20)), [at(20, key(VirtualKeyCode::W)), at(20, motion)]);
+    // the inputs are only given once
+    assert!(player.due(Duration::from_millis(40)).is_empty());
+    assert_eq!(player.due(Duration::from_secs(1)), [at(50, key(VirtualKeyCode::Space))]);
+    assert!(player.due(Duration::from_secs(2)).is_empty());
+}
+
+#[test]
+fn saved_inputs_are_loaded_back() {
+    let path = recording_file("replay");
+    let mut recorder = InputRecorder::new();
+    recorder.record(Duration::from_millis(16), key(VirtualKeyCode::A));
+    recorder.record(Duration::from_millis(33), InputEvent::MouseMotion { dx: 0.5, dy: 3.0 });
+    recorder.save(&path).unwrap();
+
+    let mut player = InputPlayer::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(player.due(Duration::from_secs(1)), &recorder.inputs[..]);
+}
+
+#[test]
+fn other_versions_are_refused() {
+    let path = recording_file("replay-version");
+    fs::write(&path, r#"{ "version": 0, "inputs": [] }"#).unwrap();
+    let error = InputPlayer::load(&path).err().expect("the recording was accepted");
+    fs::remove_file(&path).unwrap();
+    assert!(format!("{:#}", error).contains("version 0"), "{:#}", error);
+}
@@ -0,0 +1,19 @@
+use std::collections::HashSet;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+
+#[test]
+fn every_block_comes_once_with_its_world_coordinates() {
+    let position = (2, -3);
+    let chunk = Chunk::generate_at(TerrainConfig::default(), position);
+    let (origin_x, origin_z) = (position.0 * CHUNK_WIDTH as i32, position.1 * CHUNK_WIDTH as i32);
+
+    let mut seen = HashSet::new();
+    for (x, y, z, block_type) in chunk.iter_blocks() {
+        assert!(seen.insert((x, y, z)), "({}, {}, {}) came twice", x, y, z);
+        let (local_x, local_z) = (x - origin_x, z - origin_z);
+        assert!((0..CHUNK_WIDTH as i32).contains(&local_x) && (0..CHUNK_WIDTH as i32).contains(&local_z));
+        assert!((0..CHUNK_HEIGHT as i32).contains(&y));
+        assert_eq!(block_type, chunk.blocks[local_x as usize][y as usize][local_z as usize].block_type);
+    }
+    assert_eq!(seen.len(), CHUNK_WIDTH * CHUNK_HEIGHT * CHUNK_WIDTH);
+}
@@ -0,0 +1,34 @@
+use rover_engine::render::layouts::{BindGroupLayouts, BindGroupSlot, BLOCK_PIPELINE_GROUPS, LIGHT_PIPELINE_GROUPS};
+
+// stands for the layouts, a device is needed to create the real ones
+fn layouts() -> BindGroupLayouts<String> {
+    BindGroupLayouts {
+        uniform: String::from("uniform"),
+        light: String::from("light"),
+        diffuse: String::from("diffuse"),
+        material: String::from("material"),
+    }
+}
+
+#[test]
+fn pipelines_share_the_uniform_layout() {
+    let layouts = layouts();
+    let blocks = layouts.for_groups(&BLOCK_PIPELINE_GROUPS);
+    let light = layouts.for_groups(&LIGHT_PIPELINE_GROUPS);
+    // the very same layout, not an equal one
+    assert!(std::ptr::eq(blocks[0], light[0]));
+    assert!(std::ptr::eq(blocks[0], &layouts.uniform));
+    assert!(std::ptr::eq(light[1], &layouts.light));
+}
+
+#[test]
+fn each_slot_has_its_own_layout() {
+    let layouts = layouts();
+    let slots = [BindGroupSlot::Uniforms, BindGroupSlot::Light, BindGroupSlot::Atlas, BindGroupSlot::Materials];
+    for (i, &a) in slots.iter().enumerate() {
+        for &b in slots[i + 1..].iter() {
+            assert!(!std::ptr::eq(layouts.get(a), layouts.get(b)), "{:?} and {:?}", a, b);
+        }
+    }
+    assert_eq!(layouts.for_groups(&BLOCK_PIPELINE_GROUPS), vec!["uniform", "diffuse", "material"]);
+}
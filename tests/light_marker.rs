@@ -0,0 +1,20 @@
+use cgmath::*;
+use rover_engine::render::light::{marker_model, Light, MARKER_SCALE};
+
+#[test]
+fn marker_is_moved_to_the_light() {
+    let position = [1.5, -2.0, 3.25];
+    let model = marker_model(position);
+    assert_eq!(model.w.truncate(), Vector3::from(position));
+    // the corner of the unit cube is scaled down around the light
+    let corner = model.transform_point(Point3::new(1.0, 1.0, 1.0));
+    assert!((corner - Point3::from(position) - Vector3::from_value(MARKER_SCALE)).magnitude() < 1e-6);
+}
+
+#[test]
+fn marker_follows_the_light() {
+    let mut light = Light::new([2.0, 2.0, 2.0], [1.0; 3]);
+    light.set_position([-4.0, 1.0, 0.5]);
+    assert_eq!(light.model[3], [-4.0, 1.0, 0.5, 1.0]);
+    assert_eq!(Matrix4::from(light.model), marker_model(light.position));
+}
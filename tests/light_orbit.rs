@@ -0,0 +1,49 @@
+use std::time::Duration;
+use cgmath::*;
+use rover_engine::render::config::Config;
+use rover_engine::render::light::{LightOrbit, DEFAULT_ORBIT_RADIUS};
+use rover_engine::render::state::light_orbit;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn light_stays_at_the_radius_across_updates() {
+    let center = Point3::new(8.0, 12.0, -8.0);
+    let mut orbit = LightOrbit::new(center, 20.0);
+    // uneven steps, as given by the frame times
+    for millis in [16, 7, 33, 100, 1, 250].iter().cycle().take(600) {
+        orbit.advance(Deg(60.0) * Duration::from_millis(*millis).as_secs_f32());
+        assert!((orbit.position().distance(center) - 20.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn light_goes_around_the_vertical_axis_of_the_center() {
+    let center = Point3::new(8.0, 12.0, -8.0);
+    let mut orbit = LightOrbit::new(center, 5.0);
+    let height = orbit.position().y;
+    orbit.advance(Deg(180.0));
+    let half_turn = orbit.position();
+    assert!((half_turn.y - height).abs() < 1e-5);
+    orbit.advance(Deg(180.0));
+    assert!(orbit.position().distance(LightOrbit::new(center, 5.0).position()) < 1e-4);
+}
+
+#[test]
+fn default_orbit_starts_where_the_light_did() {
+    let position = LightOrbit::default().position();
+    assert!(position.distance(Point3::new(2.0, 2.0, 2.0)) < 1e-5);
+    assert!((light_orbit(&Config::default()).radius - DEFAULT_ORBIT_RADIUS).abs() < 1e-6);
+}
+
+#[test]
+fn orbit_is_read_from_the_flags() {
+    let config = Config::from_args(args(&["--light-center", "8,16,8", "--light-radius", "24"])).unwrap();
+    let orbit = light_orbit(&config);
+    assert_eq!(orbit.center, Point3::new(8.0, 16.0, 8.0));
+    assert_eq!(orbit.radius, 24.0);
+    assert!(Config::from_args(args(&["--light-center", "8,16"])).is_err());
+    assert!(Config::from_args(args(&["--light-radius", "0"])).is_err());
+}
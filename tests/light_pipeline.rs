@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use rover_engine::render::layouts::{BindGroupSlot, LIGHT_PIPELINE_GROUPS};
+
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+
+// Members of the uniform block declared at each set of a SPIR-V shader, the members of
+// nested structs included. The compiled shaders have no names left, the blocks are told
+// apart by their number of members.
+fn block_members_by_set(spirv: &[u8]) -> HashMap<u32, usize> {
+    let words: Vec<u32> = spirv.chunks_exact(4).map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]])).collect();
+    let (mut structs, mut pointees, mut variable_types, mut sets) = (HashMap::new(), HashMap::new(), HashMap::new(), vec![]);
+    // the instructions start after the 5 words of the header
+    let mut i = 5;
+    while i < words.len() {
+        let (count, opcode) = ((words[i] >> 16) as usize, words[i] & 0xffff);
+        let operands = &words[i + 1..i + count];
+        match opcode {
+            OP_TYPE_STRUCT => { structs.insert(operands[0], operands[1..].to_vec()); }
+            OP_TYPE_POINTER => { pointees.insert(operands[0], operands[2]); }
+            OP_VARIABLE => { variable_types.insert(operands[1], operands[0]); }
+            OP_DECORATE if operands[1] == DECORATION_DESCRIPTOR_SET => sets.push((operands[0], operands[2])),
+            _ => {}
+        }
+        i += count;
+    }
+    sets.into_iter()
+        .map(|(variable, set)| (set, leaf_members(&structs, pointees[&variable_types[&variable]])))
+        .collect()
+}
+
+fn leaf_members(structs: &HashMap<u32, Vec<u32>>, id: u32) -> usize {
+    match structs.get(&id) {
+        Some(members) => members.iter().map(|&member| leaf_members(structs, member)).sum(),
+        None => 1,
+    }
+}
+
+// members of the blocks in light.vert
+fn members(slot: BindGroupSlot) -> usize {
+    match slot {
+        // the view position and the view projection
+        BindGroupSlot::Uniforms => 2,
+        // the position, the color and the model matrix of the marker
+        BindGroupSlot::Light => 3,
+        _ => panic!("light.vert does not read {:?}", slot),
+    }
+}
+
+#[test]
+fn light_groups_are_the_sets_of_the_light_shader() {
+    let blocks = block_members_by_set(include_bytes!("../src/render/shaders/light.vert.spv"));
+    assert_eq!(blocks.len(), LIGHT_PIPELINE_GROUPS.len());
+    for (set, &slot) in LIGHT_PIPELINE_GROUPS.iter().enumerate() {
+        assert_eq!(blocks.get(&(set as u32)), Some(&members(slot)), "set {} is not {:?}", set, slot);
+    }
+}
+
+#[test]
+fn light_fragment_shader_reads_no_group() {
+    assert!(block_members_by_set(include_bytes!("../src/render/shaders/light.frag.spv")).is_empty());
+}
@@ -0,0 +1,46 @@
+use cgmath::*;
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours};
+use rover_engine::render::terrain::lod::{self, MAX_LOD};
+
+const SCREEN_HEIGHT: u32 = 600;
+const THRESHOLD: f32 = 1.0;
+
+fn select(distance: f32) -> u32 {
+    lod::select_lod(distance, Deg(90.0).into(), SCREEN_HEIGHT, THRESHOLD)
+}
+
+#[test]
+fn nearest_chunks_keep_their_blocks() {
+    assert_eq!(select(0.0), 0);
+    assert_eq!(select(10.0), 0);
+}
+
+#[test]
+fn twice_as_far_is_coarser() {
+    for &distance in [100.0, 300.0].iter() {
+        assert!(select(2.0 * distance) > select(distance), "no coarser level at {}", 2.0 * distance);
+    }
+}
+
+#[test]
+fn level_never_goes_down_with_the_distance() {
+    let levels: Vec<u32> = (0..100).map(|i| select(i as f32 * 10.0)).collect();
+    assert!(levels.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(*levels.last().unwrap(), MAX_LOD);
+}
+
+#[test]
+fn zero_threshold_turns_it_off() {
+    assert_eq!(lod::select_lod(10_000.0, Deg(90.0).into(), SCREEN_HEIGHT, 0.0), 0);
+}
+
+#[test]
+fn coarser_mesh_has_fewer_triangles() {
+    let chunk = Chunk::new();
+    let mut triangles = chunk.create_mesh(&VerticalNeighbours::default()).triangle_count();
+    for level in 1..=MAX_LOD {
+        let coarse = chunk.create_lod_mesh(level).triangle_count();
+        assert!(coarse > 0 && coarse < triangles, "level {} has {} triangles", level, coarse);
+        triangles = coarse;
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Mutex;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours};
+
+// keeps the records instead of printing them
+struct CapturingLogger {
+    records: Mutex<Vec<(Level, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.records.lock().unwrap().push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger { records: Mutex::new(Vec::new()) };
+
+#[test]
+fn meshing_logs_at_info_level() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let mesh = Chunk::new().create_mesh(&VerticalNeighbours::default());
+    let expected = format!("Sending to GPU: {} vertices and {} indices", mesh.vertex_count(), mesh.indices.len());
+    let records = LOGGER.records.lock().unwrap();
+    assert!(records.contains(&(Level::Info, expected)), "{:?}", records);
+}
@@ -0,0 +1,41 @@
+use cgmath::*;
+use rover_engine::render::camera::{Camera, UpAxis};
+
+fn assert_near(a: Vector3<f32>, b: Vector3<f32>) {
+    assert!((a - b).magnitude() < 1e-5, "{:?} instead of {:?}", a, b);
+}
+
+#[test]
+fn looking_at_the_origin_from_the_front_faces_minus_z() {
+    let camera = Camera::look_at((0.0, 0.0, 10.0), (0.0, 0.0, 0.0), UpAxis::Y);
+    assert_near(camera.forward(), -Vector3::unit_z());
+    assert_near(camera.up(), Vector3::unit_y());
+}
+
+#[test]
+fn forward_goes_to_the_target() {
+    for &up_axis in [UpAxis::Y, UpAxis::Z].iter() {
+        let position = Point3::new(1.0, -2.0, 3.0);
+        let target = Point3::new(-4.0, 5.0, 0.5);
+        let camera = Camera::look_at(position, target, up_axis);
+        assert_near(camera.forward(), (target - position).normalize());
+        assert_eq!(camera.up_axis, up_axis);
+    }
+}
+
+#[test]
+fn z_up_keeps_z_at_the_top() {
+    // above the target, the yaw has to go around z
+    let position = Point3::new(0.0, -10.0, 4.0);
+    let camera = Camera::look_at(position, (3.0, 0.0, 0.0), UpAxis::Z);
+    assert_near(camera.forward(), (Point3::new(3.0, 0.0, 0.0) - position).normalize());
+    assert!(camera.pitch.0 < 0.0, "the camera looks down");
+    assert!(camera.right().z.abs() < 1e-6, "the view rolled");
+    assert!(camera.up().z > 0.0);
+}
+
+#[test]
+fn looking_straight_down_is_not_a_nan() {
+    let camera = Camera::look_at((0.0, 10.0, 0.0), (0.0, 0.0, 0.0), UpAxis::Y);
+    assert!(camera.forward().x.is_finite() && camera.forward().y < -0.99);
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use cgmath::*;
+use rover_engine::render::terrain::marching_cubes::{marching_cubes, DensityField};
+use rover_engine::render::terrain::mesh::Mesh;
+
+const CENTER: [f32; 3] = [4.0, 4.0, 4.0];
+const RADIUS: f32 = 2.5;
+
+// 1 at the center fading to 0 at twice the radius, the 0.5 iso surface is the sphere
+fn sphere() -> Mesh {
+    let field = DensityField::from_fn([9, 9, 9], 1.0, [0.0; 3], |x, y, z| {
+        let distance = (Vector3::new(x as f32, y as f32, z as f32) - Vector3::from(CENTER)).magnitude();
+        1.0 - distance / (2.0 * RADIUS)
+    });
+    marching_cubes(&field, 0.5, [0.5; 3])
+}
+
+fn position(mesh: &Mesh, index: u16) -> Vector3<f32> {
+    Vector3::from(mesh.vertices[index as usize].position)
+}
+
+#[test]
+fn sphere_is_not_empty() {
+    let mesh = sphere();
+    assert!(mesh.triangle_count() > 0);
+    for vertex in mesh.vertices.iter() {
+        let distance = (Vector3::from(vertex.position) - Vector3::from(CENTER)).magnitude();
+        assert!((distance - RADIUS).abs() < 0.5, "{}", distance);
+    }
+}
+
+#[test]
+fn sphere_is_closed_and_consistently_wound() {
+    let mesh = sphere();
+    // each edge goes once in each direction when the neighbouring triangles agree on the winding
+    let mut edges: HashMap<(u16, u16), usize> = HashMap::new();
+    for triangle in mesh.indices.chunks_exact(3) {
+        for i in 0..3 {
+            *edges.entry((triangle[i], triangle[(i + 1) % 3])).or_insert(0) += 1;
+        }
+    }
+    for (&(a, b), &count) in edges.iter() {
+        assert_eq!(count, 1, "edge {}-{} is used twice in the same direction", a, b);
+        assert_eq!(edges.get(&(b, a)), Some(&1), "edge {}-{} is open", a, b);
+    }
+}
+
+#[test]
+fn sphere_faces_outward() {
+    let mesh = sphere();
+    for triangle in mesh.indices.chunks_exact(3) {
+        let (a, b, c) = (position(&mesh, triangle[0]), position(&mesh, triangle[1]), position(&mesh, triangle[2]));
+        // clockwise when looking from the outside
+        let normal = (c - a).cross(b - a);
+        let outward = (a + b + c) / 3.0 - Vector3::from(CENTER);
+        assert!(normal.dot(outward) > 0.0);
+    }
+}
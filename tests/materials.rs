@@ -0,0 +1,51 @@
+use rover_engine::render::material::{self, BlockMaterial, Materials, MAX_MATERIALS};
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::registry::BlockRegistry;
+
+// the std140 layout of the Materials block in simple.frag
+const MATERIAL_STRIDE: usize = 48;
+const SPECULAR_OFFSET: usize = 16;
+const SHININESS_OFFSET: usize = 20;
+const EMISSIVE_OFFSET: usize = 24;
+const TILES_OFFSET: usize = 32;
+
+fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_ne_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+#[test]
+fn materials_match_the_shader_layout() {
+    assert_eq!(std::mem::size_of::<BlockMaterial>(), MATERIAL_STRIDE);
+    assert_eq!(std::mem::size_of::<Materials>(), MAX_MATERIALS * MATERIAL_STRIDE);
+    // the shader declares the array with this many elements
+    assert_eq!(MAX_MATERIALS, 16);
+}
+
+#[test]
+fn packed_materials_are_at_their_index() {
+    let registry = BlockRegistry::builtin();
+    let materials = Materials::from_registry(&registry);
+    let bytes: &[u8] = bytemuck::bytes_of(&materials);
+    for block in registry.iter() {
+        let element = block.id as usize * MATERIAL_STRIDE;
+        for channel in 0..4 {
+            assert_eq!(read_f32(bytes, element + channel * 4), 1.0);
+        }
+        assert_eq!(read_f32(bytes, element + SPECULAR_OFFSET), block.specular);
+        assert_eq!(read_f32(bytes, element + SHININESS_OFFSET), block.shininess);
+        assert_eq!(read_f32(bytes, element + EMISSIVE_OFFSET), if block.emissive { 1.0 } else { 0.0 });
+        let tile = |i: usize| read_f32(bytes, element + TILES_OFFSET + i * 4).to_bits();
+        let textures = block.textures();
+        assert_eq!([tile(0), tile(1), tile(2)], [textures.top, textures.bottom, textures.side]);
+    }
+}
+
+#[test]
+fn every_block_type_has_a_material() {
+    let registry = BlockRegistry::builtin();
+    assert_eq!(material::material_id(BlockType::GLASS) as usize, registry.iter().count() - 1);
+    assert_eq!(material::PLAIN_MATERIAL, material::material_id(BlockType::AIR));
+    let materials = Materials::new().materials;
+    assert_eq!(materials[material::material_id(BlockType::LAMP) as usize].emissive, 1.0);
+    assert_eq!(materials[material::material_id(BlockType::WATER) as usize].specular, 0.6);
+}
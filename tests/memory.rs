@@ -0,0 +1,33 @@
+use rover_engine::render::memory::{aligned_size, format_bytes, init_buffer_size, texture_size};
+use rover_engine::render::vertex::ColorVertex;
+
+#[test]
+fn vertex_buffer_takes_the_size_of_its_vertices() {
+    let bytes = 100 * std::mem::size_of::<ColorVertex>();
+    assert_eq!(bytes % 4, 0);
+    assert_eq!(aligned_size(bytes), bytes as u64);
+}
+
+#[test]
+fn buffers_are_rounded_up_to_whole_words() {
+    assert_eq!(aligned_size(6), 8);
+    assert_eq!(aligned_size(0), 4);
+    // create_buffer_init always adds some padding
+    assert_eq!(init_buffer_size(6), 8);
+    assert_eq!(init_buffer_size(8), 12);
+}
+
+#[test]
+fn mip_levels_add_up() {
+    let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+    assert_eq!(texture_size(16, 16, 1, format), 16 * 16 * 4);
+    assert_eq!(texture_size(16, 16, 5, format), (256 + 64 + 16 + 4 + 1) * 4);
+    // the levels of a long texture stop shrinking along its short side
+    assert_eq!(texture_size(4, 1, 3, format), (4 + 2 + 1) * 4);
+    assert_eq!(texture_size(8, 8, 1, wgpu::TextureFormat::Rgba16Float), 8 * 8 * 8);
+}
+
+#[test]
+fn overlay_shows_megabytes() {
+    assert_eq!(format_bytes(3 * 1024 * 1024 + 512 * 1024), "3.5 MB");
+}
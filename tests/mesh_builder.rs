@@ -0,0 +1,64 @@
+use cgmath::*;
+use rover_engine::render::terrain::mesh::MeshBuilder;
+
+const TOWARD_MINUS_Z: [f32; 3] = [0.0, 0.0, -1.0];
+
+// clockwise when seen from -z, where +x is on the left
+fn quad(builder: &mut MeshBuilder, x: f32) {
+    builder.add_quad([[x + 1.0, 0.0, 0.0], [x + 1.0, 1.0, 0.0], [x, 1.0, 0.0], [x, 0.0, 0.0]], [1.0; 3], TOWARD_MINUS_Z);
+}
+
+#[test]
+fn quad_adds_four_vertices_and_six_indices() {
+    let mut builder = MeshBuilder::new();
+    quad(&mut builder, 0.0);
+    assert_eq!((builder.vertices.len(), builder.indices.len()), (4, 6));
+    quad(&mut builder, 2.0);
+    assert_eq!((builder.vertices.len(), builder.indices.len()), (8, 12));
+    // the second quad points to its own vertices
+    assert!(builder.indices[6..].iter().all(|&i| (4..8).contains(&i)));
+    assert!(builder.vertices.iter().all(|v| v.normal == TOWARD_MINUS_Z && v.color == [1.0; 3]));
+}
+
+#[test]
+fn both_triangles_keep_the_winding_of_the_corners() {
+    let mut builder = MeshBuilder::new();
+    quad(&mut builder, 0.0);
+    for triangle in builder.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vector3::from(builder.vertices[triangle[i] as usize].position));
+        // clockwise seen from the front side, see FrontFace::Cw
+        assert!((c - a).cross(b - a).dot(Vector3::from(TOWARD_MINUS_Z)) > 0.0, "{:?}", triangle);
+    }
+}
+
+#[test]
+fn appended_indices_point_past_the_vertices_already_there() {
+    let mut first = MeshBuilder::new();
+    quad(&mut first, 0.0);
+    let mut second = MeshBuilder::new();
+    quad(&mut second, 2.0);
+    let second_indices = second.indices.clone();
+
+    let mut mesh = first.build();
+    let other = second.build();
+    mesh.append(&other);
+    assert_eq!((mesh.vertex_count(), mesh.triangle_count()), (8, 4));
+    let rebased: Vec<u16> = second_indices.iter().map(|&i| i + 4).collect();
+    assert_eq!(mesh.indices[6..], rebased[..]);
+    assert_eq!(mesh.vertices[4].position, other.vertices[0].position);
+}
+
+#[test]
+fn builders_append_the_same_way_as_meshes() {
+    let build = |x| {
+        let mut builder = MeshBuilder::new();
+        quad(&mut builder, x);
+        builder
+    };
+    let mut builder = build(0.0);
+    builder.append(build(2.0));
+
+    let mut mesh = build(0.0).build();
+    mesh.append(&build(2.0).build());
+    assert_eq!(builder.indices, mesh.indices);
+}
@@ -0,0 +1,63 @@
+use std::cell::Cell;
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, MeshMode, TerrainConfig, VerticalNeighbours};
+use rover_engine::render::terrain::mesh_cache::{mesh_key, MeshCache};
+
+fn flat_chunk(position: (i32, i32)) -> Chunk {
+    // no jitter, the mesh only depends on the blocks
+    let terrain = TerrainConfig { amplitude: 0.0, color_jitter: 0.0, ..TerrainConfig::default() };
+    Chunk::generate_at(terrain, position)
+}
+
+fn key(chunk: &Chunk) -> u64 {
+    mesh_key(chunk, MeshMode::Blocky, true)
+}
+
+#[test]
+fn identical_chunks_reuse_one_mesh() {
+    let (a, b) = (flat_chunk((0, 0)), flat_chunk((3, -2)));
+    assert_eq!(key(&a), key(&b));
+
+    let mut cache = MeshCache::new(4);
+    let builds = Cell::new(0);
+    for chunk in [&a, &b].iter() {
+        cache.get_or_insert_with(key(chunk), || {
+            builds.set(builds.get() + 1);
+            chunk.create_mesh(&VerticalNeighbours::default())
+        });
+    }
+    assert_eq!(builds.get(), 1);
+}
+
+#[test]
+fn an_edit_changes_the_key() {
+    let mut chunk = flat_chunk((0, 0));
+    let before = key(&chunk);
+    chunk.set_block(1, 20, 1, BlockType::STONE);
+    assert_ne!(key(&chunk), before);
+}
+
+#[test]
+fn mesh_mode_and_dedup_change_the_key() {
+    let chunk = flat_chunk((0, 0));
+    assert_ne!(mesh_key(&chunk, MeshMode::Blocky, true), mesh_key(&chunk, MeshMode::Blocky, false));
+    assert_ne!(mesh_key(&chunk, MeshMode::Blocky, true), mesh_key(&chunk, MeshMode::MarchingCubes, true));
+}
+
+#[test]
+fn oldest_mesh_is_dropped_when_full() {
+    let chunk = flat_chunk((0, 0));
+    let mut cache = MeshCache::new(2);
+    let builds = Cell::new(0);
+    let get = |cache: &mut MeshCache, key: u64| {
+        cache.get_or_insert_with(key, || {
+            builds.set(builds.get() + 1);
+            chunk.create_mesh(&VerticalNeighbours::default())
+        });
+    };
+    for &key in [1, 2, 3, 2, 1].iter() {
+        get(&mut cache, key);
+    }
+    // 1 was dropped for 3, 2 was still there
+    assert_eq!(builds.get(), 4);
+}
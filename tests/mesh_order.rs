@@ -0,0 +1,48 @@
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, VerticalNeighbours, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::lighting::LightMap;
+use rover_engine::render::terrain::mesh::{Mesh, MeshBuilder};
+
+// the bytes of each quad, 4 vertices in a row
+fn quads(mesh: &Mesh) -> Vec<Vec<u8>> {
+    mesh.vertices.chunks_exact(4).map(|quad| bytemuck::cast_slice(quad).to_vec()).collect()
+}
+
+// every block meshed in the given order of the three loops
+fn mesh_in_order(chunk: &Chunk, order: impl Iterator<Item = [usize; 3]>) -> Mesh {
+    let neighbours = VerticalNeighbours::default();
+    let light = LightMap::compute(chunk);
+    let mut builder = MeshBuilder::new();
+    for [x, y, z] in order {
+        if !chunk.registry.get(chunk.blocks[x][y][z].block_type).is_transparent() {
+            chunk.create_cube(&mut builder, x, y, z, &neighbours, &light);
+        }
+    }
+    builder.build()
+}
+
+fn chunk() -> Chunk {
+    Chunk::generate_at(TerrainConfig { color_jitter: 0.2, ..TerrainConfig::default() }, (1, -2))
+}
+
+#[test]
+fn mesh_follows_the_blocks_array() {
+    let chunk = chunk();
+    let order = (0..CHUNK_WIDTH).flat_map(|x| (0..CHUNK_HEIGHT).flat_map(move |y| (0..CHUNK_WIDTH).map(move |z| [x, y, z])));
+    let expected = mesh_in_order(&chunk, order);
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    assert_eq!(quads(&mesh), quads(&expected));
+    assert_eq!(mesh.indices, expected.indices);
+}
+
+#[test]
+fn layer_order_gives_the_same_quads() {
+    // the order create_mesh used before, y outermost
+    let chunk = chunk();
+    let order = (0..CHUNK_HEIGHT).flat_map(|y| (0..CHUNK_WIDTH).flat_map(move |x| (0..CHUNK_WIDTH).map(move |z| [x, y, z])));
+    let mut by_layers = quads(&mesh_in_order(&chunk, order));
+    let mut mesh = quads(&chunk.create_mesh(&VerticalNeighbours::default()));
+    assert!(!mesh.is_empty());
+    by_layers.sort();
+    mesh.sort();
+    assert_eq!(mesh, by_layers);
+}
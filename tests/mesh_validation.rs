@@ -0,0 +1,41 @@
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours};
+use rover_engine::render::terrain::mesh::{debug_validate_mesh, mesh_error, MeshBuilder};
+
+fn quad() -> MeshBuilder {
+    let mut builder = MeshBuilder::new();
+    builder.add_quad([[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0], [1.0, 0.0, 0.0]], [1.0; 3], [0.0, 0.0, -1.0]);
+    builder
+}
+
+#[test]
+fn generated_meshes_are_valid() {
+    let chunk = Chunk::new();
+    let mesh = chunk.create_mesh(&VerticalNeighbours::default());
+    assert_eq!(mesh_error(&mesh.vertices, &mesh.indices), None);
+    let water = chunk.create_water_mesh();
+    assert_eq!(mesh_error(&water.vertices, &water.indices), None);
+}
+
+#[test]
+fn out_of_range_index_is_detected() {
+    let mut builder = quad();
+    builder.indices[4] = 4;
+    let error = mesh_error(&builder.vertices, &builder.indices).unwrap();
+    assert!(error.contains("index 4 at 4"), "{}", error);
+}
+
+#[test]
+fn cut_triangle_is_detected() {
+    let mut builder = quad();
+    builder.indices.pop();
+    assert!(mesh_error(&builder.vertices, &builder.indices).is_some());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "Invalid mesh")]
+fn debug_builds_panic_on_an_invalid_mesh() {
+    let mut builder = quad();
+    builder.indices[0] = 100;
+    debug_validate_mesh(&builder.vertices, &builder.indices);
+}
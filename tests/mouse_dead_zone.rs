@@ -0,0 +1,37 @@
+use std::time::Duration;
+use cgmath::*;
+use rover_engine::render::camera::{Camera, CameraController};
+
+const FRAME: Duration = Duration::from_millis(16);
+
+// yaw and pitch after one mouse motion
+fn look(dead_zone: f32, dx: f64, dy: f64) -> (Rad<f32>, Rad<f32>) {
+    let mut camera = Camera::new((0.0, 0.0, 0.0), Deg(-90.0), Deg(0.0));
+    let mut controller = CameraController::new(5.0, 0.6);
+    controller.dead_zone = dead_zone;
+    controller.process_mouse(dx, dy);
+    controller.update_camera(&mut camera, FRAME);
+    (camera.yaw, camera.pitch)
+}
+
+#[test]
+fn motion_under_the_dead_zone_is_ignored() {
+    let (yaw, pitch) = look(2.0, 1.0, 1.0);
+    assert_eq!(yaw, Rad::from(Deg(-90.0)));
+    assert_eq!(pitch, Rad(0.0));
+}
+
+#[test]
+fn motion_over_the_dead_zone_turns_the_camera() {
+    let (yaw, pitch) = look(2.0, 3.0, 0.0);
+    assert!(yaw != Rad::from(Deg(-90.0)));
+    assert_eq!(pitch, Rad(0.0));
+}
+
+#[test]
+fn dead_zone_is_off_by_default() {
+    let controller = CameraController::new(5.0, 0.6);
+    assert_eq!(controller.dead_zone, 0.0);
+    let (yaw, _) = look(0.0, 0.1, 0.0);
+    assert!(yaw != Rad::from(Deg(-90.0)));
+}
@@ -0,0 +1,86 @@
+use rover_engine::render::terrain::noise::{hash_rng, NoiseGenerator, NoiseKind};
+
+const KINDS: [NoiseKind; 3] = [NoiseKind::OpenSimplex, NoiseKind::Perlin, NoiseKind::Value];
+
+// points off the integer grid, where the gradient noises are always zero
+fn samples() -> impl Iterator<Item = (f64, f64)> {
+    (0..64).map(|i| (i as f64 * 0.37 + 0.1, i as f64 * 0.61 + 0.3))
+}
+
+#[test]
+fn same_seed_gives_the_same_noise() {
+    for &kind in KINDS.iter() {
+        let a = NoiseGenerator::from_seed(42, kind);
+        let b = NoiseGenerator::from_seed(42, kind);
+        for (x, z) in samples() {
+            assert_eq!(a.get(x, z), b.get(x, z));
+            assert_eq!(a.get_3d(x, 1.5, z), b.get_3d(x, 1.5, z));
+        }
+    }
+}
+
+#[test]
+fn other_seed_gives_other_noise() {
+    for &kind in KINDS.iter() {
+        let a = NoiseGenerator::from_seed(1, kind);
+        let b = NoiseGenerator::from_seed(2, kind);
+        assert!(samples().any(|(x, z)| a.get(x, z) != b.get(x, z)), "{:?} ignores the seed", kind);
+    }
+}
+
+#[test]
+fn noise_stays_in_range() {
+    for &kind in KINDS.iter() {
+        let generator = NoiseGenerator::from_seed(7, kind);
+        for (x, z) in samples() {
+            let value = generator.get(x, z);
+            assert!((-1.0..=1.0).contains(&value), "{:?} gave {}", kind, value);
+        }
+    }
+}
+
+#[test]
+fn hash_rng_is_in_unit_range() {
+    for x in -16..16 {
+        let value = hash_rng(3, x, 0, x * 7);
+        assert!((0.0..1.0).contains(&value));
+        assert_eq!(value, hash_rng(3, x, 0, x * 7));
+    }
+}
+
+#[test]
+fn hash_rng_differs_between_coordinates_and_seeds() {
+    let mut values: Vec<u32> = vec![];
+    for x in -4..4 {
+        for y in -4..4 {
+            for z in -4..4 {
+                values.push(hash_rng(3, x, y, z).to_bits());
+            }
+        }
+    }
+    let count = values.len();
+    values.sort_unstable();
+    values.dedup();
+    assert_eq!(values.len(), count);
+    assert_ne!(hash_rng(3, 1, 2, 3), hash_rng(4, 1, 2, 3));
+    // the axes are not interchangeable
+    assert_ne!(hash_rng(3, 1, 2, 3), hash_rng(3, 3, 2, 1));
+}
+
+#[test]
+fn kinds_differ_for_the_same_seed() {
+    for (i, &a) in KINDS.iter().enumerate() {
+        for &b in KINDS[i + 1..].iter() {
+            let (first, second) = (NoiseGenerator::from_seed(42, a), NoiseGenerator::from_seed(42, b));
+            assert!(samples().any(|(x, z)| first.get(x, z) != second.get(x, z)), "{:?} is {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn kinds_are_read_from_their_names() {
+    assert_eq!("opensimplex".parse::<NoiseKind>().unwrap(), NoiseKind::OpenSimplex);
+    assert_eq!("perlin".parse::<NoiseKind>().unwrap(), NoiseKind::Perlin);
+    assert_eq!("value".parse::<NoiseKind>().unwrap(), NoiseKind::Value);
+    assert!("worley".parse::<NoiseKind>().is_err());
+}
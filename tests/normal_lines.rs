@@ -0,0 +1,28 @@
+use cgmath::*;
+use rover_engine::render::terrain::block::{BlockType, HALF_BLOCK_SIZE};
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours};
+
+const LENGTH: f32 = 0.2;
+
+#[test]
+fn lone_cube_has_a_line_along_each_axis() {
+    let mut chunk = Chunk::from_voxel_str(".").unwrap();
+    chunk.set_block(4, 4, 4, BlockType::STONE);
+    let center = Vector3::new(4.0, 4.0, 4.0) * (2.0 * HALF_BLOCK_SIZE);
+    let lines = chunk.create_normal_lines(&VerticalNeighbours::default(), LENGTH);
+    assert_eq!(lines.len(), 6 * 2);
+
+    let mut directions = vec![];
+    for line in lines.chunks_exact(2) {
+        let (start, end) = (Vector3::from(line[0].position), Vector3::from(line[1].position));
+        let direction = (end - start) / LENGTH;
+        // from the middle of the face, straight out of the cube
+        assert!((start - center - direction * HALF_BLOCK_SIZE).magnitude() < 1e-5, "{:?} is not on its face", start);
+        assert!((direction - Vector3::from(line[0].normal)).magnitude() < 1e-5);
+        directions.push(direction);
+    }
+    let axes = [Vector3::unit_x(), -Vector3::unit_x(), Vector3::unit_y(), -Vector3::unit_y(), Vector3::unit_z(), -Vector3::unit_z()];
+    for axis in axes.iter() {
+        assert_eq!(directions.iter().filter(|d| (*d - axis).magnitude() < 1e-5).count(), 1, "{:?}", axis);
+    }
+}
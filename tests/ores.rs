@@ -0,0 +1,52 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::ore::OreConfig;
+
+fn stone_chunk() -> Chunk {
+    let mut chunk = Chunk::new();
+    for x in 0..CHUNK_WIDTH {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_WIDTH {
+                chunk.set_block(x, y, z, BlockType::STONE);
+            }
+        }
+    }
+    chunk
+}
+
+fn coal(rarity: f32) -> OreConfig {
+    OreConfig { block_type: BlockType::COAL, rarity, max_height: 10, vein_size: 6 }
+}
+
+fn coal_heights(chunk: &Chunk) -> Vec<i32> {
+    chunk.iter_blocks().filter(|&(_, _, _, block)| block == BlockType::COAL).map(|(_, y, _, _)| y).collect()
+}
+
+#[test]
+fn no_ore_without_rarity() {
+    let mut chunk = stone_chunk();
+    chunk.place_ores(&[coal(0.0)]);
+    assert!(coal_heights(&chunk).is_empty());
+}
+
+#[test]
+fn ore_stays_under_its_height() {
+    let mut chunk = stone_chunk();
+    chunk.place_ores(&[coal(0.5)]);
+    let heights = coal_heights(&chunk);
+    assert!(!heights.is_empty());
+    assert!(heights.iter().all(|&y| y < 10), "coal at {:?}", heights.iter().max());
+}
+
+#[test]
+fn ore_only_replaces_stone() {
+    let mut chunk = stone_chunk();
+    for x in 0..CHUNK_WIDTH {
+        for z in 0..CHUNK_WIDTH {
+            chunk.set_block(x, 4, z, BlockType::DIRT);
+        }
+    }
+    chunk.place_ores(&[coal(0.5)]);
+    assert!(!coal_heights(&chunk).is_empty());
+    assert!(chunk.iter_blocks().all(|(_, y, _, block)| y != 4 || block == BlockType::DIRT));
+}
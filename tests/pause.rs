@@ -0,0 +1,36 @@
+use std::time::Duration;
+use rover_engine::render::light::LightOrbit;
+use rover_engine::render::stats::animation_step;
+use rover_engine::render::uniform::Uniforms;
+
+const FRAME: Duration = Duration::from_millis(16);
+
+#[test]
+fn light_stands_still_while_paused() {
+    let mut orbit = LightOrbit::default();
+    let start = orbit.position();
+    for _ in 0..100 {
+        orbit.update(animation_step(FRAME, 1.0, true));
+        assert_eq!(orbit.position(), start);
+    }
+    orbit.update(animation_step(FRAME, 1.0, false));
+    assert_ne!(orbit.position(), start);
+}
+
+#[test]
+fn waves_stop_while_paused() {
+    let mut uniforms = Uniforms::new();
+    uniforms.advance_time(animation_step(FRAME, 1.0, false));
+    let time = uniforms.time;
+    for _ in 0..100 {
+        uniforms.advance_time(animation_step(FRAME, 1.0, true));
+    }
+    assert_eq!(uniforms.time, time);
+}
+
+#[test]
+fn pause_ignores_the_time_scale() {
+    assert_eq!(animation_step(FRAME, 8.0, true), Duration::from_secs(0));
+    let running = animation_step(FRAME, 1.0, false);
+    assert!((running.as_secs_f64() - FRAME.as_secs_f64()).abs() < 1e-6);
+}
@@ -0,0 +1,22 @@
+use rover_engine::render::state::{primitive_state, POINT_TOPOLOGY};
+use rover_engine::render::stats::DrawStats;
+use rover_engine::render::terrain::chunk::{Chunk, VerticalNeighbours};
+
+#[test]
+fn point_pipeline_draws_points() {
+    let primitive = primitive_state(POINT_TOPOLOGY, wgpu::PolygonMode::Fill);
+    assert_eq!(primitive.topology, wgpu::PrimitiveTopology::PointList);
+    // the vertices are drawn without their indices
+    assert_eq!(primitive.strip_index_format, None);
+}
+
+#[test]
+fn every_vertex_is_one_point() {
+    let mesh = Chunk::new().create_mesh(&VerticalNeighbours::default());
+    let num_vertices = mesh.vertices.len() as u32;
+    assert!(num_vertices > 0);
+
+    let mut stats = DrawStats::default();
+    stats.record_points(num_vertices);
+    assert_eq!(stats, DrawStats { draw_calls: 1, vertices: num_vertices, triangles: 0 });
+}
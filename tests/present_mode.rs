@@ -0,0 +1,25 @@
+use wgpu::PresentMode;
+use rover_engine::render::state::{next_present_mode, PRESENT_MODES};
+
+#[test]
+fn fifo_only_surface_stays_on_fifo() {
+    assert_eq!(next_present_mode(PresentMode::Fifo, &[PresentMode::Fifo]), PresentMode::Fifo);
+}
+
+#[test]
+fn cycle_goes_through_the_supported_modes() {
+    let mut mode = PresentMode::Fifo;
+    let mut seen = vec![];
+    for _ in 0..PRESENT_MODES.len() {
+        mode = next_present_mode(mode, &PRESENT_MODES);
+        seen.push(mode);
+    }
+    assert_eq!(seen, vec![PresentMode::Mailbox, PresentMode::Immediate, PresentMode::Fifo]);
+}
+
+#[test]
+fn unsupported_modes_are_skipped() {
+    let supported = [PresentMode::Fifo, PresentMode::Immediate];
+    assert_eq!(next_present_mode(PresentMode::Fifo, &supported), PresentMode::Immediate);
+    assert_eq!(next_present_mode(PresentMode::Immediate, &supported), PresentMode::Fifo);
+}
@@ -0,0 +1,107 @@
+use cgmath::*;
+use rover_engine::render::camera::Projection;
+
+const ZNEAR: f32 = 0.1;
+const ZFAR: f32 = 100.0;
+
+// clip space position divided by w
+fn project(projection: &Projection, point: Point3<f32>) -> Vector3<f32> {
+    let clip = projection.calc_matrix() * point.to_homogeneous();
+    clip.truncate() / clip.w
+}
+
+#[test]
+fn depth_goes_from_zero_to_one() {
+    let projection = Projection::new(800, 600, Deg(45.0), ZNEAR, ZFAR);
+    let near = project(&projection, Point3::new(0.0, 0.0, -ZNEAR));
+    let far = project(&projection, Point3::new(0.0, 0.0, -ZFAR));
+    assert!(near.z.abs() < 1e-5, "near plane at {}", near.z);
+    assert!((far.z - 1.0).abs() < 1e-5, "far plane at {}", far.z);
+}
+
+#[test]
+fn field_of_view_reaches_the_top_of_the_screen() {
+    let projection = Projection::new(800, 600, Deg(90.0), ZNEAR, ZFAR);
+    // 45 degrees above the view direction
+    let top = project(&projection, Point3::new(0.0, 10.0, -10.0));
+    assert!((top.y - 1.0).abs() < 1e-5, "top at {}", top.y);
+}
+
+#[test]
+fn aspect_squeezes_the_width() {
+    let mut projection = Projection::new(800, 400, Deg(90.0), ZNEAR, ZFAR);
+    let side = project(&projection, Point3::new(10.0, 0.0, -10.0));
+    assert!((side.x - 0.5).abs() < 1e-5, "side at {}", side.x);
+
+    projection.resize(400, 400);
+    let side = project(&projection, Point3::new(10.0, 0.0, -10.0));
+    assert!((side.x - 1.0).abs() < 1e-5, "side at {}", side.x);
+}
+
+#[test]
+fn field_of_view_is_clamped() {
+    let mut projection = Projection::new(800, 600, Deg(45.0), ZNEAR, ZFAR);
+    projection.set_fov_y(Deg(500.0));
+    assert!((Deg::from(projection.fov_y()).0 - 110.0).abs() < 1e-3);
+}
+
+#[test]
+fn aspect_follows_the_logical_size_at_any_scale_factor() {
+    use winit::dpi::{LogicalSize, PhysicalSize};
+    let logical = LogicalSize::new(800.0, 450.0);
+    let matrices: Vec<Matrix4<f32>> = [1.0, 2.0, 1.5].iter().map(|&scale_factor| {
+        let physical: PhysicalSize<u32> = logical.to_physical(scale_factor);
+        let mut projection = Projection::new(1, 1, Deg(45.0), ZNEAR, ZFAR);
+        projection.resize(physical.width, physical.height);
+        projection.calc_matrix()
+    }).collect();
+    for matrix in matrices.iter() {
+        assert!((matrix.x.x - matrices[0].x.x).abs() < 1e-6);
+        assert_eq!(matrix.y.y, matrices[0].y.y);
+    }
+}
+
+#[test]
+fn terrain_fits_at_the_fit_distance() {
+    use rover_engine::render::camera::Camera;
+    use rover_engine::render::math::Aabb;
+    let bounds = Aabb::new(Vector3::new(-0.25, -0.25, -0.25), Vector3::new(7.75, 12.0, 7.75));
+    let radius = bounds.size().magnitude() / 2.0;
+    for &(width, height) in [(800, 600), (600, 800)].iter() {
+        let projection = Projection::new(width, height, Deg(45.0), ZNEAR, ZFAR);
+        let mut camera = Camera::new((0.0, 0.0, 0.0), Deg(-60.0), Deg(-30.0));
+        camera.position = Point3::from_vec(bounds.center()) - camera.direction() * projection.fit_distance(radius);
+
+        let view_proj = projection.calc_matrix() * camera.calc_matrix();
+        for corner in bounds.corners().iter() {
+            let clip = view_proj * corner.extend(1.0);
+            let ndc = clip.truncate() / clip.w;
+            assert!(ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0, "{:?} is out of the {}x{} view", corner, width, height);
+            assert!((0.0..=1.0).contains(&ndc.z));
+        }
+    }
+}
+
+#[test]
+fn wider_view_fits_from_closer() {
+    let narrow = Projection::new(800, 600, Deg(30.0), ZNEAR, ZFAR);
+    let wide = Projection::new(800, 600, Deg(90.0), ZNEAR, ZFAR);
+    assert!(wide.fit_distance(5.0) < narrow.fit_distance(5.0));
+    // a sphere of radius r seen under a half angle a is r / sin(a) away
+    assert!((wide.fit_distance(5.0) - 5.0 / Deg(45.0).sin()).abs() < 1e-4);
+}
+
+#[test]
+fn chunk_bounds_cover_its_blocks() {
+    use rover_engine::render::terrain::block::{BlockType, HALF_BLOCK_SIZE};
+    use rover_engine::render::terrain::chunk::Chunk;
+    let mut chunk = Chunk::from_voxel_str("s\n\n..s\n").unwrap();
+    let bounds = chunk.bounds().unwrap();
+    let h = HALF_BLOCK_SIZE;
+    assert_eq!(bounds.min, Vector3::new(-h, -h, -h));
+    assert_eq!(bounds.max, Vector3::new(5.0 * h, 3.0 * h, h));
+
+    chunk.set_block(0, 0, 0, BlockType::AIR);
+    chunk.set_block(2, 1, 0, BlockType::AIR);
+    assert!(chunk.bounds().is_none());
+}
@@ -0,0 +1,86 @@
+use cgmath::*;
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, VerticalNeighbours};
+use rover_engine::render::terrain::chunk_manager::{ChunkManager, SeedMode};
+use rover_engine::render::terrain::mesh::Mesh;
+
+fn positions(mesh: &Mesh) -> Vec<[f32; 3]> {
+    mesh.vertices.iter().map(|vertex| vertex.position).collect()
+}
+
+fn mesh(chunk: &Chunk) -> Mesh {
+    chunk.create_mesh(&VerticalNeighbours::default())
+}
+
+fn same_mesh(a: &Mesh, b: &Mesh) -> bool {
+    positions(a) == positions(b) && a.indices == b.indices
+}
+
+// State::regenerate builds the main chunk and the manager again from the terrain with the new seed
+fn regenerate(manager: &mut ChunkManager, terrain: TerrainConfig, camera: Point3<f32>) -> Chunk {
+    manager.regenerate(terrain);
+    manager.generation_budget = usize::MAX;
+    manager.generate_pending(camera, |_| true);
+    Chunk::generate(terrain)
+}
+
+#[test]
+fn new_seed_changes_the_mesh() {
+    let terrain = TerrainConfig::default();
+    let camera = Point3::new(3.0, 40.0, -2.0);
+    let mut manager = ChunkManager::new(terrain, 1, SeedMode::Shared);
+    let before = regenerate(&mut manager, terrain, camera);
+    let neighbour_before = mesh(&manager.chunks[&(1, 0)]);
+
+    let reseeded = TerrainConfig { seed: terrain.seed.wrapping_add(1), ..terrain };
+    let after = regenerate(&mut manager, reseeded, camera);
+    assert_eq!(after.terrain.seed, reseeded.seed);
+    assert!(!same_mesh(&mesh(&before), &mesh(&after)), "the main chunk kept its mesh");
+    assert!(!same_mesh(&neighbour_before, &mesh(&manager.chunks[&(1, 0)])), "the neighbour kept its mesh");
+}
+
+#[test]
+fn same_seed_gives_the_same_mesh() {
+    let terrain = TerrainConfig { seed: 42, ..TerrainConfig::default() };
+    assert!(same_mesh(&mesh(&Chunk::generate(terrain)), &mesh(&Chunk::generate(terrain))));
+}
+
+#[test]
+fn regenerated_chunks_are_dirty() {
+    let terrain = TerrainConfig::default();
+    let mut manager = ChunkManager::new(terrain, 1, SeedMode::Shared);
+    let mut chunk = regenerate(&mut manager, terrain, Point3::new(0.0, 0.0, 0.0));
+    manager.take_dirty();
+
+    let mut chunk_after = regenerate(&mut manager, TerrainConfig { seed: 7, ..terrain }, Point3::new(0.0, 0.0, 0.0));
+    assert!(chunk.take_dirty());
+    assert!(chunk_after.take_dirty());
+    assert_eq!(manager.take_dirty().len(), 8);
+}
+
+#[test]
+fn only_the_visible_chunks_are_regenerated() {
+    let terrain = TerrainConfig::default();
+    let mut manager = ChunkManager::new(terrain, 1, SeedMode::Shared);
+    regenerate(&mut manager, terrain, Point3::new(0.0, 0.0, 0.0));
+    manager.take_dirty();
+    // an edit in every chunk, to tell which ones are built again
+    for chunk in manager.chunks.values_mut() {
+        chunk.set_block(3, 30, 3, BlockType::GLASS);
+    }
+    manager.take_dirty();
+
+    let visible = [(1, 0), (-1, 1), (5, 5)];
+    let reseeded = TerrainConfig { seed: 99, ..terrain };
+    // (5, 5) is out of the radius, the manager does not hold it
+    assert_eq!(manager.regenerate_chunks(reseeded, &visible), 2);
+
+    let mut dirty = manager.take_dirty();
+    dirty.sort_unstable();
+    assert_eq!(dirty, vec![(-1, 1), (1, 0)]);
+    for (position, chunk) in manager.chunks.iter() {
+        let regenerated = visible.contains(position);
+        assert_eq!(chunk.blocks[3][30][3].block_type != BlockType::GLASS, regenerated, "{:?}", position);
+        assert_eq!(chunk.terrain.seed == reseeded.seed, regenerated, "{:?}", position);
+    }
+}
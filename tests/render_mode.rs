@@ -0,0 +1,36 @@
+use cgmath::*;
+use rover_engine::render::terrain::chunk::{Chunk, MeshMode, TerrainConfig};
+use rover_engine::render::terrain::chunk_manager::{ChunkManager, RenderMode, SeedMode};
+use rover_engine::render::terrain::mesh;
+
+// the main chunk and the manager around it, as the State builds them
+fn world() -> (Chunk, ChunkManager) {
+    let main = Chunk::generate(TerrainConfig::default());
+    let mut manager = ChunkManager::new(main.terrain, 1, SeedMode::Shared);
+    manager.generation_budget = usize::MAX;
+    manager.generate_pending(Point3::new(0.0, 0.0, 0.0), |_| true);
+    (main, manager)
+}
+
+// the vertices uploaded for each drawn chunk
+fn drawn_vertices(mode: RenderMode) -> Vec<usize> {
+    let (main, manager) = world();
+    std::iter::once(&main).chain(manager.chunks.values())
+        .filter(|chunk| mode.draws(chunk.position, main.position))
+        .map(|chunk| mesh::deduplicate_vertices(&chunk.create_mesh_with(MeshMode::Blocky)).vertex_count())
+        .collect()
+}
+
+#[test]
+fn single_chunk_only_draws_the_main_chunk() {
+    let drawn = drawn_vertices(RenderMode::SingleChunk);
+    assert_eq!(drawn.len(), 1);
+    assert!(drawn[0] > 0);
+}
+
+#[test]
+fn multi_chunk_draws_the_chunks_around() {
+    let drawn = drawn_vertices(RenderMode::MultiChunk);
+    assert_eq!(drawn.len(), 9);
+    assert!(drawn.iter().all(|&vertices| vertices > 0));
+}
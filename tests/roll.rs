@@ -0,0 +1,44 @@
+use cgmath::*;
+use rover_engine::render::camera::Camera;
+
+fn assert_near(a: Vector3<f32>, b: Vector3<f32>) {
+    assert!((a - b).magnitude() < 1e-5, "{:?} instead of {:?}", a, b);
+}
+
+// looking to -z, with +x on the right
+fn free_camera() -> Camera {
+    let mut camera = Camera::new((0.0, 0.0, 0.0), Deg(-90.0), Deg(0.0));
+    camera.set_free(true);
+    camera
+}
+
+#[test]
+fn free_mode_starts_from_the_yaw_and_pitch() {
+    let camera = free_camera();
+    assert_near(camera.forward(), -Vector3::unit_z());
+    assert_near(camera.right(), Vector3::unit_x());
+    assert_near(camera.up(), Vector3::unit_y());
+}
+
+#[test]
+fn rolling_a_quarter_turn_puts_the_right_at_the_top() {
+    let mut camera = free_camera();
+    camera.rotate(Rad(0.0), Rad(0.0), Deg(90.0).into());
+    assert_near(camera.forward(), -Vector3::unit_z());
+    assert_near(camera.up(), Vector3::unit_x());
+    assert_near(camera.right(), -Vector3::unit_y());
+    // the view follows, what was on the right is now at the top of the screen
+    let on_screen = camera.calc_matrix().transform_vector(Vector3::unit_x());
+    assert_near(on_screen, Vector3::unit_y());
+}
+
+#[test]
+fn roll_is_dropped_out_of_the_free_mode() {
+    let mut camera = free_camera();
+    camera.rotate(Rad(0.0), Rad(0.0), Deg(90.0).into());
+    camera.set_free(false);
+    assert_near(camera.up(), Vector3::unit_y());
+    // the yaw and pitch mode does not roll
+    camera.rotate(Rad(0.0), Rad(0.0), Deg(90.0).into());
+    assert_near(camera.up(), Vector3::unit_y());
+}
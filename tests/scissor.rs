@@ -0,0 +1,24 @@
+use rover_engine::render::scissor::{Corner, ScissorRect};
+
+const SURFACE: [u32; 2] = [1280, 720];
+
+fn rect(x: u32, y: u32, width: u32, height: u32) -> ScissorRect {
+    ScissorRect { x, y, width, height }
+}
+
+#[test]
+fn minimap_in_each_corner() {
+    let minimap = [200, 150];
+    assert_eq!(ScissorRect::corner(Corner::TopLeft, minimap, 10, SURFACE), rect(10, 10, 200, 150));
+    assert_eq!(ScissorRect::corner(Corner::TopRight, minimap, 10, SURFACE), rect(1070, 10, 200, 150));
+    assert_eq!(ScissorRect::corner(Corner::BottomLeft, minimap, 10, SURFACE), rect(10, 560, 200, 150));
+    assert_eq!(ScissorRect::corner(Corner::BottomRight, minimap, 10, SURFACE), rect(1070, 560, 200, 150));
+}
+
+#[test]
+fn rect_stays_on_the_surface() {
+    // bigger than a small window, it is cut at the right and bottom edges
+    assert_eq!(ScissorRect::corner(Corner::TopLeft, [200, 150], 10, [100, 80]), rect(10, 10, 90, 70));
+    assert_eq!(ScissorRect::corner(Corner::BottomRight, [200, 150], 10, [100, 80]), rect(0, 0, 100, 80));
+    assert_eq!(rect(2000, 5, 10, 10).clamped(SURFACE), rect(1280, 5, 0, 10));
+}
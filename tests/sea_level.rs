@@ -0,0 +1,48 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+
+// blocks of a column from the bottom up
+fn column(chunk: &Chunk, x: usize, z: usize) -> Vec<BlockType> {
+    (0..CHUNK_HEIGHT).map(|y| chunk.blocks[x][y][z].block_type).collect()
+}
+
+#[test]
+fn flat_ground_under_the_sea_is_covered_up_to_the_level() {
+    // no noise, the ground is the grass at y = 0
+    let terrain = TerrainConfig { amplitude: 0.0, sea_level: 5, ..TerrainConfig::default() };
+    let chunk = Chunk::generate(terrain);
+    for &(x, z) in [(0, 0), (7, 3), (CHUNK_WIDTH - 1, CHUNK_WIDTH - 1)].iter() {
+        let blocks = column(&chunk, x, z);
+        assert_eq!(blocks[0], BlockType::GRASS);
+        assert!(blocks[1..5].iter().all(|&b| b == BlockType::WATER), "{:?}", &blocks[..6]);
+        assert!(blocks[5..].iter().all(|&b| b == BlockType::AIR));
+    }
+}
+
+#[test]
+fn water_only_fills_the_columns_under_the_level() {
+    let sea_level = 8;
+    let terrain = TerrainConfig { sea_level, ..TerrainConfig::default() };
+    let chunk = Chunk::generate(terrain);
+    let (mut flooded, mut dry) = (0, 0);
+    for x in 0..CHUNK_WIDTH {
+        for z in 0..CHUNK_WIDTH {
+            let blocks = column(&chunk, x, z);
+            let ground = blocks.iter().rposition(|&b| b != BlockType::AIR && b != BlockType::WATER);
+            let first_air = ground.map_or(0, |g| g + 1);
+            for (y, &block) in blocks.iter().enumerate().skip(first_air) {
+                let expected = if y < sea_level { BlockType::WATER } else { BlockType::AIR };
+                assert_eq!(block, expected, "({}, {}, {})", x, y, z);
+            }
+            if first_air < sea_level { flooded += 1 } else { dry += 1 }
+        }
+    }
+    // the default terrain goes both under and over that level
+    assert!(flooded > 0 && dry > 0, "{} flooded and {} dry columns", flooded, dry);
+}
+
+#[test]
+fn no_water_without_a_sea_level() {
+    let chunk = Chunk::generate(TerrainConfig { amplitude: 0.0, ..TerrainConfig::default() });
+    assert!(chunk.iter_blocks().all(|(_, _, _, block)| block != BlockType::WATER));
+}
@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::PathBuf;
+use cgmath::*;
+use rover_engine::render::camera::Camera;
+use rover_engine::render::session::{Session, SESSION_VERSION};
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+use rover_engine::render::terrain::registry::BlockRegistry;
+
+// a file of its own for each test, they run at the same time
+fn session_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rover-engine-session-{}-{}.json", name, std::process::id()))
+}
+
+fn block_types(chunk: &Chunk) -> Vec<BlockType> {
+    chunk.iter_blocks().map(|(_, _, _, block_type)| block_type).collect()
+}
+
+#[test]
+fn session_reloads_the_edits_and_the_camera() {
+    let mut chunk = Chunk::generate(TerrainConfig { seed: 99, ..TerrainConfig::default() });
+    chunk.set_block(3, CHUNK_HEIGHT - 1, 5, BlockType::LAMP);
+    chunk.set_block(0, 0, 0, BlockType::AIR);
+    let camera = Camera::new((4.5, 20.0, -3.25), Deg(-30.0), Deg(12.0));
+
+    let path = session_file("round-trip");
+    Session::capture(&chunk, &camera).save(&path).unwrap();
+    let session = Session::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(session.edits.len(), 2);
+    let (restored, restored_camera) = session.restore();
+    assert_eq!(block_types(&restored), block_types(&chunk));
+    assert_eq!(restored.terrain, chunk.terrain);
+    assert_eq!(restored_camera.position, camera.position);
+    assert_eq!(restored_camera.yaw, camera.yaw);
+    assert_eq!(restored_camera.pitch, camera.pitch);
+}
+
+#[test]
+fn other_versions_are_refused() {
+    let chunk = Chunk::generate(TerrainConfig::default());
+    let mut session = Session::capture(&chunk, &Camera::new((0.0, 0.0, 0.0), Deg(0.0), Deg(0.0)));
+    session.version = SESSION_VERSION + 1;
+    let path = session_file("version");
+    session.save(&path).unwrap();
+    let error = Session::load(&path).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert!(error.to_string().contains("Unsupported session version"), "{}", error);
+}
+
+#[test]
+fn edits_out_of_the_chunk_are_refused() {
+    let mut chunk = Chunk::generate(TerrainConfig::default());
+    chunk.set_block(1, 1, 1, BlockType::IRON);
+    let mut session = Session::capture(&chunk, &Camera::new((0.0, 0.0, 0.0), Deg(0.0), Deg(0.0)));
+    session.edits[0].x = CHUNK_WIDTH;
+    let path = session_file("out-of-chunk");
+    session.save(&path).unwrap();
+    assert!(Session::load(&path).is_err());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn edits_of_blocks_out_of_the_registry_are_refused() {
+    let mut chunk = Chunk::generate(TerrainConfig::default());
+    chunk.set_block(1, 1, 1, BlockType(9));
+    let session = Session::capture(&chunk, &Camera::new((0.0, 0.0, 0.0), Deg(0.0), Deg(0.0)));
+    let error = session.check_blocks(&BlockRegistry::builtin()).unwrap_err();
+    assert!(error.to_string().contains("Unknown block 9"), "{}", error);
+    let basalt = r#"[{ "name": "basalt", "id": 9, "color": [0.1, 0.1, 0.1] }]"#;
+    assert!(session.check_blocks(&BlockRegistry::from_json(basalt).unwrap()).is_ok());
+}
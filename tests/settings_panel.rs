@@ -0,0 +1,93 @@
+use std::time::Duration;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::*;
+use winit::window::WindowId;
+use rover_engine::render::settings::{Settings, SettingsPanel, PANEL_MARGIN, PANEL_WIDTH};
+
+const WINDOW_SIZE: [u32; 2] = [800, 600];
+// a point of the panel, just under its title bar
+const OVER_THE_PANEL: [f64; 2] = [WINDOW_SIZE[0] as f64 - (PANEL_MARGIN + PANEL_WIDTH / 2.0) as f64, PANEL_MARGIN as f64 + 40.0];
+const AWAY_FROM_THE_PANEL: [f64; 2] = [100.0, 500.0];
+
+fn settings() -> Settings {
+    Settings {
+        fov: 45.0,
+        light_color: [1.0, 1.0, 1.0],
+        render_distance: 2,
+        seed: 1234,
+        sea_level: 8,
+        fog: true,
+        wireframe: false,
+        ao_strength: 1.0,
+        exposure: None,
+    }
+}
+
+#[allow(deprecated)]
+fn key(keycode: VirtualKeyCode) -> DeviceEvent {
+    DeviceEvent::Key(KeyboardInput { scancode: 0, state: ElementState::Pressed, virtual_keycode: Some(keycode), modifiers: ModifiersState::empty() })
+}
+
+fn camera_events() -> Vec<DeviceEvent> {
+    vec![
+        DeviceEvent::MouseMotion { delta: (12.0, -3.0) },
+        DeviceEvent::MouseWheel { delta: MouseScrollDelta::LineDelta(0.0, 1.0) },
+    ]
+}
+
+// the panel after a few frames with the pointer at that position of the window
+#[allow(deprecated)]
+fn panel_with_pointer_at([x, y]: [f64; 2], open: bool) -> SettingsPanel {
+    let mut panel = SettingsPanel::new(PhysicalSize::new(WINDOW_SIZE[0], WINDOW_SIZE[1]), 1.0);
+    if open {
+        panel.toggle();
+    }
+    let moved: Event<()> = Event::WindowEvent {
+        window_id: unsafe { WindowId::dummy() },
+        event: WindowEvent::CursorMoved {
+            device_id: unsafe { DeviceId::dummy() },
+            position: PhysicalPosition::new(x, y),
+            modifiers: ModifiersState::empty(),
+        },
+    };
+    panel.handle_event(&moved);
+    // the first frame places the panel, the next ones find the pointer over it
+    for _ in 0..3 {
+        panel.run(Duration::from_millis(16), &mut settings());
+    }
+    panel
+}
+
+#[test]
+fn open_panel_keeps_the_input_from_the_camera() {
+    let panel = panel_with_pointer_at(OVER_THE_PANEL, true);
+    assert!(panel.wants_pointer());
+    assert!(camera_events().iter().all(|event| panel.captures(event)));
+    // no field is typed in, the keys still move the camera
+    assert!(!panel.captures(&key(VirtualKeyCode::Z)));
+    assert!(!panel.captures(&DeviceEvent::Added));
+}
+
+#[test]
+fn pointer_away_from_the_panel_lets_the_input_through() {
+    let panel = panel_with_pointer_at(AWAY_FROM_THE_PANEL, true);
+    assert!(!panel.wants_pointer());
+    assert!(camera_events().iter().all(|event| !panel.captures(event)));
+}
+
+#[test]
+fn closed_panel_lets_the_input_through() {
+    let panel = panel_with_pointer_at(OVER_THE_PANEL, false);
+    assert!(camera_events().iter().all(|event| !panel.captures(event)));
+}
+
+#[test]
+fn untouched_panel_leaves_the_settings_as_they_are() {
+    let mut panel = SettingsPanel::new(PhysicalSize::new(WINDOW_SIZE[0], WINDOW_SIZE[1]), 1.0);
+    panel.toggle();
+    let mut edited = settings();
+    let meshes = panel.run(Duration::from_millis(16), &mut edited);
+    assert_eq!(edited, settings());
+    assert!(!meshes.is_empty());
+    assert!(panel.texture().width > 0);
+}
@@ -0,0 +1,38 @@
+use std::path::{Path, PathBuf};
+use rover_engine::render::shader::ShaderFile;
+
+fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rover-engine-shader-{}-{}.spv", name, std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+fn error(path: &Path) -> String {
+    format!("{:#}", ShaderFile::load(path, "terrain pipeline").err().expect("the shader was loaded"))
+}
+
+#[test]
+fn missing_shader_names_its_file_and_pipeline() {
+    let path = std::env::temp_dir().join("rover-engine-missing-shader.spv");
+    let message = error(&path);
+    assert!(message.contains("rover-engine-missing-shader.spv"), "{}", message);
+    assert!(message.contains("terrain pipeline"), "{}", message);
+}
+
+#[test]
+fn files_that_are_not_spirv_are_refused() {
+    let cut = temp_file("cut", &[0x03, 0x02, 0x23]);
+    let text = temp_file("text", b"void main() {}\n");
+    for path in [&cut, &text].iter() {
+        let message = error(path);
+        assert!(message.contains("is not SPIR-V"), "{}", message);
+        assert!(message.contains("terrain pipeline"), "{}", message);
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn built_shaders_are_loaded() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/render/shaders/text.vert.spv");
+    assert!(ShaderFile::load(path, "text pipeline").is_ok());
+}
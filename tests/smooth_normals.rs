@@ -0,0 +1,61 @@
+use cgmath::*;
+use rover_engine::render::material;
+use rover_engine::render::terrain::atlas;
+use rover_engine::render::terrain::mesh::compute_smooth_normals;
+use rover_engine::render::vertex::ColorVertex;
+
+fn vertices(positions: &[[f32; 3]]) -> Vec<ColorVertex> {
+    positions.iter().map(|&position| ColorVertex {
+        position,
+        color: [1.0; 3],
+        normal: [0.0; 3],
+        tex_coords: atlas::PLAIN_TEX_COORDS,
+        ao: 1.0,
+        material_id: material::PLAIN_MATERIAL,
+    }).collect()
+}
+
+fn assert_normal(vertex: &ColorVertex, expected: Vector3<f32>) {
+    let normal = Vector3::from(vertex.normal);
+    assert!((normal - expected.normalize()).magnitude() < 1e-5, "{:?} instead of {:?}", normal, expected.normalize());
+}
+
+const ORIGIN: [f32; 3] = [0.0, 0.0, 0.0];
+const X: [f32; 3] = [1.0, 0.0, 0.0];
+const Z: [f32; 3] = [0.0, 0.0, 1.0];
+const DOWN: [f32; 3] = [0.0, -1.0, 0.0];
+
+#[test]
+fn folded_quad_averages_the_shared_edge() {
+    // a floor triangle facing up and a wall triangle facing -x, clockwise from the front
+    let mut quad = vertices(&[ORIGIN, X, Z, DOWN]);
+    compute_smooth_normals(&mut quad, &[0, 1, 2, 0, 2, 3]);
+    assert_normal(&quad[1], Vector3::unit_y());
+    assert_normal(&quad[3], -Vector3::unit_x());
+    assert_normal(&quad[0], Vector3::new(-1.0, 1.0, 0.0));
+    assert_normal(&quad[2], Vector3::new(-1.0, 1.0, 0.0));
+}
+
+#[test]
+fn flat_quad_keeps_its_face_normal() {
+    let mut quad = vertices(&[ORIGIN, X, [1.0, 0.0, 1.0], Z]);
+    compute_smooth_normals(&mut quad, &[0, 1, 2, 0, 2, 3]);
+    for vertex in quad.iter() {
+        assert_normal(vertex, Vector3::unit_y());
+    }
+}
+
+#[test]
+fn cube_corner_points_along_the_diagonal() {
+    // three faces of the same size meeting at the origin, facing up, -x and -z
+    let mut corner = vertices(&[ORIGIN, X, Z, DOWN]);
+    compute_smooth_normals(&mut corner, &[0, 1, 2, 0, 2, 3, 0, 3, 1]);
+    assert_normal(&corner[0], Vector3::new(-1.0, 1.0, -1.0));
+}
+
+#[test]
+fn unused_vertex_has_no_normal() {
+    let mut mesh = vertices(&[ORIGIN, X, Z, [5.0, 5.0, 5.0]]);
+    compute_smooth_normals(&mut mesh, &[0, 1, 2]);
+    assert_eq!(mesh[3].normal, [0.0; 3]);
+}
@@ -0,0 +1,42 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, TerrainConfig, CHUNK_HEIGHT, CHUNK_WIDTH};
+
+// height of the highest solid block of the column, 0 when there is none
+fn column_height(chunk: &Chunk, x: usize, z: usize) -> usize {
+    (0..CHUNK_HEIGHT).rev()
+        .find(|&y| !chunk.registry.get(chunk.blocks[x][y][z].block_type).is_transparent())
+        .map_or(0, |y| y + 1)
+}
+
+// how much the height goes up and down along the rows of the chunk
+fn roughness(terrain: TerrainConfig) -> usize {
+    let chunk = Chunk::generate(terrain);
+    (0..CHUNK_WIDTH).map(|z| {
+        (1..CHUNK_WIDTH).map(|x| column_height(&chunk, x - 1, z).abs_diff(column_height(&chunk, x, z))).sum::<usize>()
+    }).sum()
+}
+
+fn terrain(seed: u32, frequency: f64) -> TerrainConfig {
+    TerrainConfig { seed, frequency, amplitude: 12.0, ..TerrainConfig::default() }
+}
+
+#[test]
+fn higher_frequency_is_rougher() {
+    for &seed in [1, 2, 3].iter() {
+        let smooth = roughness(terrain(seed, 0.02));
+        let rough = roughness(terrain(seed, 0.2));
+        assert!(rough > smooth, "seed {}: {} is not rougher than {}", seed, rough, smooth);
+    }
+}
+
+#[test]
+fn zero_amplitude_is_flat() {
+    let flat = Chunk::generate(TerrainConfig { amplitude: 0.0, ..TerrainConfig::default() });
+    for x in 0..CHUNK_WIDTH {
+        for z in 0..CHUNK_WIDTH {
+            // the ground stays at the bottom, only the first layer is grass
+            assert_eq!(flat.blocks[x][0][z].block_type, BlockType::GRASS);
+            assert_eq!(column_height(&flat, x, z), 1);
+        }
+    }
+}
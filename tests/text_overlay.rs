@@ -0,0 +1,52 @@
+use rover_engine::render::text::{build_glyph_quads, text_size, TEXT_MARGIN, TEXT_SCALE};
+
+const WHITE: [f32; 3] = [1.0, 1.0, 1.0];
+
+fn lines(text: &[&str]) -> Vec<String> {
+    text.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn one_quad_per_visible_character() {
+    let (vertices, indices) = build_glyph_quads(&lines(&["FPS: 60", "x 1.0 y 2.0"]), [0.0, 0.0], WHITE);
+    // the spaces are skipped
+    let glyphs = "FPS:60".len() + "x1.0y2.0".len();
+    assert_eq!(vertices.len(), 4 * glyphs);
+    assert_eq!(indices.len(), 6 * glyphs);
+    assert!(indices.iter().all(|&i| (i as usize) < vertices.len()));
+}
+
+#[test]
+fn missing_characters_still_get_a_quad() {
+    let (vertices, _) = build_glyph_quads(&lines(&["é~"]), [0.0, 0.0], WHITE);
+    assert_eq!(vertices.len(), 8);
+}
+
+#[test]
+fn blank_lines_have_no_quads() {
+    let (vertices, indices) = build_glyph_quads(&lines(&["", "   "]), [0.0, 0.0], WHITE);
+    assert!(vertices.is_empty());
+    assert!(indices.is_empty());
+}
+
+#[test]
+fn lines_are_stacked_from_the_origin() {
+    let origin = [TEXT_MARGIN, TEXT_MARGIN];
+    let (vertices, _) = build_glyph_quads(&lines(&["A", "B"]), origin, WHITE);
+    assert_eq!(vertices[0].position, origin);
+    assert_eq!(vertices[4].position[0], origin[0]);
+    assert!(vertices[4].position[1] > vertices[3].position[1], "the second line overlaps the first");
+    assert!(vertices.iter().all(|vertex| vertex.color == WHITE));
+}
+
+#[test]
+fn text_size_holds_the_quads() {
+    let text = lines(&["FPS: 60", "LONGEST LINE"]);
+    let origin = [TEXT_MARGIN, TEXT_MARGIN];
+    let (vertices, _) = build_glyph_quads(&text, origin, WHITE);
+    let [width, height] = text_size(&text);
+    for vertex in &vertices {
+        assert!(vertex.position[0] <= width as f32 - TEXT_MARGIN + TEXT_SCALE);
+        assert!(vertex.position[1] <= height as f32 - TEXT_MARGIN);
+    }
+}
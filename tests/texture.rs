@@ -0,0 +1,33 @@
+use rover_engine::render::terrain::atlas::TILE_SIZE;
+use rover_engine::render::texture::{clamp_anisotropy, mip_level_count, MAX_ANISOTROPY};
+
+#[test]
+fn each_mip_level_halves_the_size_down_to_one_texel() {
+    assert_eq!(mip_level_count(1, 1), 1);
+    assert_eq!(mip_level_count(2, 2), 2);
+    assert_eq!(mip_level_count(256, 256), 9);
+    // the longest side sets the count, odd sizes round down on each level
+    assert_eq!(mip_level_count(256, 16), 9);
+    assert_eq!(mip_level_count(300, 20), 9);
+    assert_eq!(mip_level_count(0, 0), 1);
+}
+
+#[test]
+fn atlas_chain_stops_at_one_texel_per_tile() {
+    // the last level of a 16 texels tile is 1x1, a further one would blend the tiles
+    assert_eq!(TILE_SIZE >> (mip_level_count(TILE_SIZE, TILE_SIZE) - 1), 1);
+}
+
+#[test]
+fn anisotropy_is_clamped_to_the_maximum() {
+    let anisotropy = |requested| clamp_anisotropy(requested, MAX_ANISOTROPY).map(|a| a.get());
+    // 1x is the plain trilinear filtering
+    assert_eq!(anisotropy(0), None);
+    assert_eq!(anisotropy(1), None);
+    assert_eq!(anisotropy(16), Some(16));
+    assert_eq!(anisotropy(32), Some(16));
+    assert_eq!(anisotropy(255), Some(16));
+    // the sampler only takes powers of two
+    assert_eq!(anisotropy(5), Some(4));
+    assert_eq!(clamp_anisotropy(16, 8).map(|a| a.get()), Some(8));
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+use cgmath::*;
+use rover_engine::render::light::LightOrbit;
+use rover_engine::render::stats::animation_step;
+use rover_engine::render::uniform::Uniforms;
+
+// angle travelled around the vertical axis since the start of the orbit
+fn turned(orbit: &LightOrbit) -> Rad<f32> {
+    let start = LightOrbit::new(orbit.center, orbit.radius).position() - orbit.center;
+    let now = orbit.position() - orbit.center;
+    Vector2::new(start.x, start.z).angle(Vector2::new(now.x, now.z))
+}
+
+#[test]
+fn half_time_scale_turns_the_light_half_as_far() {
+    let (mut normal, mut slow) = (LightOrbit::default(), LightOrbit::default());
+    for _ in 0..10 {
+        let dt = Duration::from_millis(16);
+        normal.update(animation_step(dt, 1.0, false));
+        slow.update(animation_step(dt, 0.5, false));
+    }
+    assert!(turned(&normal).0.abs() > 0.1);
+    assert!((turned(&slow).0 - turned(&normal).0 / 2.0).abs() < 1e-4);
+}
+
+#[test]
+fn time_scale_multiplies_the_animation_step() {
+    let dt = Duration::from_millis(20);
+    assert!((animation_step(dt, 0.5, false).as_secs_f32() - 0.01).abs() < 1e-6);
+    assert!((animation_step(dt, 2.0, false).as_secs_f32() - 0.04).abs() < 1e-6);
+
+    let mut uniforms = Uniforms::new();
+    uniforms.advance_time(animation_step(dt, 0.5, false));
+    assert!((uniforms.time - 0.01).abs() < 1e-6);
+}
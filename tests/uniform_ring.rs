@@ -0,0 +1,32 @@
+use rover_engine::render::uniform_ring::{next_index, FRAMES_IN_FLIGHT};
+
+#[test]
+fn ring_wraps_after_its_last_buffer() {
+    let mut active = 0;
+    let visited: Vec<usize> = (0..2 * FRAMES_IN_FLIGHT)
+        .map(|_| {
+            active = next_index(active, FRAMES_IN_FLIGHT);
+            active
+        })
+        .collect();
+    let expected: Vec<usize> = (1..=2 * FRAMES_IN_FLIGHT).map(|frame| frame % FRAMES_IN_FLIGHT).collect();
+    assert_eq!(visited, expected);
+}
+
+#[test]
+fn written_buffer_was_not_bound_by_the_frames_in_flight() {
+    // the frames before still read their own buffer when a frame writes its one
+    let mut active = 0;
+    let mut history = vec![active];
+    for _ in 0..10 {
+        active = next_index(active, FRAMES_IN_FLIGHT);
+        let recent = &history[history.len().saturating_sub(FRAMES_IN_FLIGHT - 1)..];
+        assert!(!recent.contains(&active), "{} is still in use by {:?}", active, recent);
+        history.push(active);
+    }
+}
+
+#[test]
+fn single_buffer_ring_stays_on_it() {
+    assert_eq!(next_index(0, 1), 0);
+}
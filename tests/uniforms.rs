@@ -0,0 +1,46 @@
+use std::time::Duration;
+use rover_engine::render::state::clear_color;
+use rover_engine::render::uniform::{Uniforms, TIME_PERIOD};
+
+const FRAME: Duration = Duration::from_millis(16);
+
+#[test]
+fn time_advances_by_the_frame_time() {
+    let mut uniforms = Uniforms::new();
+    assert_eq!(uniforms.time, 0.0);
+    uniforms.advance_time(FRAME);
+    assert!((uniforms.time - 0.016).abs() < 1e-6);
+    uniforms.advance_time(FRAME);
+    assert!((uniforms.time - 0.032).abs() < 1e-6);
+}
+
+#[test]
+fn time_wraps_around_its_period() {
+    let mut uniforms = Uniforms::new();
+    uniforms.advance_time(Duration::from_secs_f32(TIME_PERIOD - 0.5));
+    uniforms.advance_time(Duration::from_secs(1));
+    assert!((uniforms.time - 0.5).abs() < 1e-3, "{} after wrapping", uniforms.time);
+}
+
+#[test]
+fn time_stays_in_its_period_over_a_long_session() {
+    let mut uniforms = Uniforms::new();
+    // a whole day, one minute at a time
+    for _ in 0..24 * 60 {
+        uniforms.advance_time(Duration::from_secs(60));
+        assert!(uniforms.time >= 0.0 && uniforms.time < TIME_PERIOD);
+    }
+}
+
+#[test]
+fn ambient_color_reaches_the_uniforms_and_the_sky() {
+    let ambient_color = [0.5, 0.25, 0.125];
+    let mut uniforms = Uniforms::new();
+    uniforms.set_ambient_color(ambient_color);
+    assert_eq!(uniforms.ambient_color, [0.5, 0.25, 0.125, 1.0]);
+    // the uniforms are uploaded as they are
+    let bytes: &[u8] = bytemuck::cast_slice(std::slice::from_ref(&uniforms));
+    let uploaded: &[f32] = bytemuck::cast_slice(&bytes[bytes.len() - 16..]);
+    assert_eq!(uploaded, &[0.5, 0.25, 0.125, 1.0]);
+    assert_eq!(clear_color(ambient_color), wgpu::Color { r: 0.5, g: 0.25, b: 0.125, a: 1.0 });
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+use cgmath::*;
+use winit::event::{ElementState, VirtualKeyCode};
+use rover_engine::render::camera::{Camera, CameraController, UpAxis};
+use rover_engine::render::config::Config;
+use rover_engine::render::light::LightOrbit;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn assert_near(a: Vector3<f32>, b: Vector3<f32>) {
+    assert!((a - b).magnitude() < 1e-5, "{:?} instead of {:?}", a, b);
+}
+
+// how far the camera goes in a second with the key held down
+fn moved_by(up_axis: UpAxis, key: VirtualKeyCode) -> Vector3<f32> {
+    let mut camera = Camera::new((0.0, 0.0, 0.0), Deg(30.0), Deg(-20.0)).with_up_axis(up_axis);
+    let mut controller = CameraController::new(1.0, 0.0);
+    controller.process_keyboard(key, ElementState::Pressed);
+    controller.update_camera(&mut camera, Duration::from_secs(1));
+    camera.position.to_vec()
+}
+
+#[test]
+fn up_key_moves_along_z_when_z_is_up() {
+    assert_near(moved_by(UpAxis::Z, VirtualKeyCode::Space), Vector3::unit_z());
+    assert_near(moved_by(UpAxis::Y, VirtualKeyCode::Space), Vector3::unit_y());
+}
+
+#[test]
+fn walking_stays_across_the_up_axis() {
+    for &key in [VirtualKeyCode::Z, VirtualKeyCode::D].iter() {
+        let moved = moved_by(UpAxis::Z, key);
+        assert!(moved.z.abs() < 1e-6);
+        assert!((moved.magnitude() - 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn view_keeps_the_up_axis_at_the_top() {
+    let camera = Camera::new((0.0, 0.0, 0.0), Deg(75.0), Deg(0.0)).with_up_axis(UpAxis::Z);
+    assert!(camera.direction().z.abs() < 1e-6);
+    assert!(camera.right().z.abs() < 1e-6);
+    assert_near(camera.up(), Vector3::unit_z());
+    // the view matrix maps the up axis to the top of the screen
+    let top = camera.calc_matrix().transform_vector(Vector3::unit_z());
+    assert_near(top, Vector3::unit_y());
+}
+
+#[test]
+fn y_up_keeps_the_former_directions() {
+    let camera = Camera::new((0.0, 0.0, 0.0), Deg(0.0), Deg(0.0));
+    assert_near(camera.direction(), Vector3::unit_x());
+    assert_near(camera.right(), Vector3::unit_z());
+}
+
+#[test]
+fn light_orbits_around_the_up_axis() {
+    let mut orbit = LightOrbit::new(Point3::new(0.0, 0.0, 0.0), 2.0);
+    orbit.up_axis = UpAxis::Z;
+    let height = orbit.position().z;
+    orbit.advance(Deg(120.0));
+    assert!((orbit.position().z - height).abs() < 1e-5);
+}
+
+#[test]
+fn up_axis_is_read_from_the_flag() {
+    assert_eq!(Config::from_args(args(&["--up-axis", "z"])).unwrap().up_axis, UpAxis::Z);
+    assert_eq!(Config::default().up_axis, UpAxis::Y);
+    assert!(Config::from_args(args(&["--up-axis", "x"])).is_err());
+}
@@ -0,0 +1,50 @@
+use rover_engine::render::terrain::block::BlockType;
+use rover_engine::render::terrain::chunk::{Chunk, CHUNK_HEIGHT};
+
+const SCENE: &str = "\
+# a pillar of glass on a floor of stone
+sss
+sgs
+
+# the next layer
+.x.
+
+
+..l
+";
+
+fn error(text: &str) -> String {
+    format!("{:#}", Chunk::from_voxel_str(text).err().expect("the scene was accepted"))
+}
+
+#[test]
+fn layers_go_up_and_lines_go_along_z() {
+    let chunk = Chunk::from_voxel_str(SCENE).unwrap();
+    assert_eq!(chunk.block_at(0, 0, 0), Some(BlockType::STONE));
+    assert_eq!(chunk.block_at(1, 0, 1), Some(BlockType::GRASS));
+    assert_eq!(chunk.block_at(1, 1, 0), Some(BlockType::GLASS));
+    assert_eq!(chunk.block_at(0, 1, 0), Some(BlockType::AIR));
+    // several empty lines only end one layer
+    assert_eq!(chunk.block_at(2, 2, 0), Some(BlockType::LAMP));
+}
+
+#[test]
+fn everything_left_out_is_air() {
+    let chunk = Chunk::from_voxel_str(SCENE).unwrap();
+    let solid = chunk.iter_blocks().filter(|&(_, _, _, block)| block != BlockType::AIR).count();
+    assert_eq!(solid, 6 + 1 + 1);
+    assert_eq!(chunk.block_at(5, 0, 0), Some(BlockType::AIR));
+    assert_eq!(chunk.block_at(0, 0, 5), Some(BlockType::AIR));
+}
+
+#[test]
+fn unknown_blocks_are_refused_with_their_line() {
+    let message = error("sss\ns?s\n");
+    assert!(message.contains("line 2: unknown block '?'"), "{}", message);
+}
+
+#[test]
+fn too_many_layers_are_refused() {
+    let message = error(&"s\n\n".repeat(CHUNK_HEIGHT + 1));
+    assert!(message.contains("more than"), "{}", message);
+}
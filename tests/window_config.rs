@@ -0,0 +1,36 @@
+use rover_engine::render::config::Config;
+use rover_engine::render::window::{grab_captures, CursorGrab, WindowConfig, DEFAULT_HEIGHT, DEFAULT_WIDTH};
+
+fn window(args: &[&str]) -> anyhow::Result<WindowConfig> {
+    Config::from_args(args.iter().map(|arg| arg.to_string())).map(|config| config.window)
+}
+
+#[test]
+fn no_flag_gives_the_default_window() {
+    let window = window(&[]).unwrap();
+    assert_eq!(window, WindowConfig::default());
+    assert_eq!((window.width, window.height), (DEFAULT_WIDTH, DEFAULT_HEIGHT));
+    assert!(!window.fullscreen);
+}
+
+#[test]
+fn size_and_fullscreen_are_read_from_the_flags() {
+    let window = window(&["--width", "1280", "--fullscreen", "--height", "720", "--cursor-grab", "confine"]).unwrap();
+    assert_eq!(window, WindowConfig { width: 1280, height: 720, fullscreen: true, cursor_grab: CursorGrab::Confine });
+}
+
+#[test]
+fn invalid_sizes_are_refused() {
+    assert!(window(&["--width", "0"]).is_err());
+    assert!(window(&["--width", "-3"]).is_err());
+    assert!(window(&["--height", "tall"]).is_err());
+    assert!(window(&["--height"]).is_err());
+    assert!(window(&["--cursor-grab", "hold"]).is_err());
+    assert!(window(&["--windowed"]).is_err());
+}
+
+#[test]
+fn refused_grab_leaves_the_mouse_free() {
+    assert!(grab_captures(Ok(())));
+    assert!(!grab_captures(Err(anyhow::anyhow!("the compositor refused the grab"))));
+}